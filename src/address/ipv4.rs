@@ -1,16 +1,18 @@
 // src/address/ipv4.rs
 
-/// IPv4 Address support library
-///
-/// Provides abstraction over the IPv4 Address and various utility
-/// functions that operate on IPv4 Addresses. It allows parsing, formatting,
-/// and manipulation of IPv4 addresses with robust error handling for common
-/// issues encountered
-///
-/// # Example
-/// ```
-/// ```
+//! IPv4 Address support library
+//!
+//! Provides abstraction over the IPv4 Address and various utility
+//! functions that operate on IPv4 Addresses. It allows parsing, formatting,
+//! and manipulation of IPv4 addresses with robust error handling for common
+//! issues encountered
+//!
+//! # Example
+//! ```
+//! ```
+
 
+use super::ipv6::{self, IPv6};
 
 /// Size of IPv4 address in octets
 pub const ADDR_SIZE: usize = 4;
@@ -21,40 +23,47 @@ pub const UNSPECIFIED: IPv4 = IPv4([0x00; ADDR_SIZE]);
 /// The broadcast address.
 pub const BROADCAST: IPv4 = IPv4([0xff; ADDR_SIZE]);
 
+/// Maximum prefix length of an IPv4 network, in bits.
+pub const MAX_PREFIX_LEN: u8 = 32;
+
 #[derive(Debug, PartialEq)]
 pub enum IPv4AddressError {
     InvalidLength,
     InvalidFormat,
     InvalidCharacter,
     InvalidSegment,
+    InvalidPrefixLength,
+    HostBitsSet,
 }
 
-impl std::fmt::Display for IPv4AddressError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for IPv4AddressError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match *self {
             IPv4AddressError::InvalidLength => write!(f, "IPv4 address must have exactly 4 octets"),
             IPv4AddressError::InvalidFormat => write!(f, "Invalid IPv4 address format"),
             IPv4AddressError::InvalidCharacter => write!(f, "IPv4 address contains invalid characters"),
             IPv4AddressError::InvalidSegment => write!(f, "IPv4 address segment out of range (0-255)"),
+            IPv4AddressError::InvalidPrefixLength => write!(f, "IPv4 prefix length out of range (0-32)"),
+            IPv4AddressError::HostBitsSet => write!(f, "IPv4 network address has host bits set"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for IPv4AddressError {}
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+/// A four-octet IPv4 address.
+///
+/// Addresses are ordered numerically, since the octets are stored in big-endian.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct IPv4(pub [u8; ADDR_SIZE]);
 
 impl IPv4 {
     /// Construct an IPv4 address from octet segments.
-    pub fn new(seg0: u8, seg1: u8, seg2: u8, seg3: u8) -> Self {
+    pub const fn new(seg0: u8, seg1: u8, seg2: u8, seg3: u8) -> Self {
         IPv4([seg0, seg1, seg2, seg3])
     }
 
-    pub fn to_string(&self) -> String {
-        return format!("{}.{}.{}.{}", self.0[0], self.0[1], self.0[2], self.0[3]);
-    }
-
     /// Return an IPv4 address as a single u32.
     pub fn to_u32(&self) -> u32 {
         u32::from_be_bytes(self.0)
@@ -105,25 +114,139 @@ impl IPv4 {
         (self.0[0] == 192 && self.0[1] == 168)
     }
 
+    /// Query if the address is reserved for [documentation].
+    ///
+    /// [documentation]: https://datatracker.ietf.org/doc/html/rfc5737
+    pub fn is_documentation(&self) -> bool {
+        // TEST-NET-1, TEST-NET-2 and TEST-NET-3
+        matches!(
+            (self.0[0], self.0[1], self.0[2]),
+            (192, 0, 2) | (198, 51, 100) | (203, 0, 113)
+        )
+    }
+
+    /// Query if the address is in the [shared address space] (100.64.0.0/10).
+    ///
+    /// [shared address space]: https://datatracker.ietf.org/doc/html/rfc6598
+    pub fn is_shared(&self) -> bool {
+        self.0[0] == 100 && (self.0[1] & 0b1100_0000) == 0b0100_0000
+    }
+
+    /// Query if the address is reserved for [benchmarking] (198.18.0.0/15).
+    ///
+    /// [benchmarking]: https://datatracker.ietf.org/doc/html/rfc2544
+    pub fn is_benchmarking(&self) -> bool {
+        self.0[0] == 198 && (self.0[1] & 0xfe) == 18
+    }
+
+    /// Query if the address is [reserved] for future use (240.0.0.0/4),
+    /// excluding the broadcast address.
+    ///
+    /// [reserved]: https://datatracker.ietf.org/doc/html/rfc1112#section-4
+    pub fn is_reserved(&self) -> bool {
+        (self.0[0] & 0xf0) == 240 && !self.is_broadcast()
+    }
+
+    /// Return a hash of the address that is identical across runs and processes.
+    ///
+    /// The derived `Hash` impl only feeds the octets to a `Hasher`, so its
+    /// result depends on the hasher; `std`'s default `RandomState` is seeded
+    /// per process. Use this instead when the value must be reproducible,
+    /// e.g. to pick a worker shard.
+    pub fn stable_hash(&self) -> u64 {
+        super::fnv1a(&self.0)
+    }
+
+    /// Query if the address may be used as the source of a packet,
+    /// i.e. it is not multicast, broadcast or unspecified.
+    pub fn is_valid_source(&self) -> bool {
+        !self.is_multicast() && !self.is_broadcast() && !self.is_unspecified()
+    }
+
+    /// Query if the address may be used as the destination of a packet,
+    /// i.e. it is not the unspecified address.
+    pub fn is_valid_destination(&self) -> bool {
+        !self.is_unspecified()
+    }
+
+    /// Query if the address is publicly routable, i.e. not in any of the
+    /// special-use ranges covered by the other predicates, nor in "this
+    /// network" (0.0.0.0/8) or the [IETF protocol assignments]
+    /// (192.0.0.0/24). As with `std`, the globally reachable 192.0.0.9 and
+    /// 192.0.0.10 are the exceptions.
+    ///
+    /// [IETF protocol assignments]: https://datatracker.ietf.org/doc/html/rfc6890
+    pub fn is_global(&self) -> bool {
+        let this_network = self.0[0] == 0;
+        let protocol_assignment = self.0[..3] == [192, 0, 0] && self.0[3] != 9 && self.0[3] != 10;
+        !(this_network
+            || protocol_assignment
+            || self.is_private()
+            || self.is_loopback()
+            || self.is_link_local()
+            || self.is_multicast()
+            || self.is_broadcast()
+            || self.is_unspecified()
+            || self.is_documentation()
+            || self.is_shared()
+            || self.is_benchmarking()
+            || self.is_reserved())
+    }
+
+    /// Return the [IPv4-mapped] IPv6 address `::ffff:a.b.c.d`.
+    ///
+    /// [IPv4-mapped]: https://www.rfc-editor.org/rfc/rfc4291#section-2.5.5.2
+    pub fn to_ipv6_mapped(&self) -> IPv6 {
+        let mut octets = [0u8; ipv6::ADDR_SIZE];
+        octets[..12].copy_from_slice(&ipv6::IPV4_MAPPED_PREFIX);
+        octets[12..].copy_from_slice(&self.0);
+        IPv6::from(octets)
+    }
+
 }
 
+/// Construct an IPv4 address from a dotted-quad string, e.g. `192.168.1.1`.
+///
+/// Exactly four decimal octets are required. Unlike `inet_aton`, shorthand
+/// forms such as `127.1` are not accepted, and a leading or trailing dot
+/// (`192.168.1.1.`) or an empty octet (`1..2.3`) is rejected with
+/// `InvalidFormat`.
+///
+/// An octet with anything but decimal digits fails with `InvalidCharacter`,
+/// and one above 255 with `InvalidSegment`.
 pub fn from_string(addr_str: &str) -> Result<IPv4, IPv4AddressError> {
-    let parts: Vec<&str> = addr_str.split('.').collect();
-    if parts.len() != 4 {
+    if addr_str.starts_with('.') || addr_str.ends_with('.') {
+        return Err(IPv4AddressError::InvalidFormat);
+    }
+
+    if addr_str.split('.').count() != 4 {
         return Err(IPv4AddressError::InvalidLength);
     }
 
     let mut addr_bytes = [0u8; 4];
-    for (i, part) in parts.iter().enumerate() {
-        match part.parse::<u8>() {
-            Ok(num) => addr_bytes[i] = num,
-            Err(_) => return Err(IPv4AddressError::InvalidCharacter),
-        }
+    for (i, part) in addr_str.split('.').enumerate() {
+        addr_bytes[i] = parse_octet(part)?;
     }
 
     Ok(IPv4(addr_bytes))
 }
 
+// Parse one decimal octet of a dotted quad
+fn parse_octet(part: &str) -> Result<u8, IPv4AddressError> {
+    if part.is_empty() {
+        return Err(IPv4AddressError::InvalidFormat);
+    }
+    let mut value: u32 = 0;
+    for c in part.bytes() {
+        if !c.is_ascii_digit() {
+            return Err(IPv4AddressError::InvalidCharacter);
+        }
+        // Saturate so a long run of digits stays out of range
+        value = value.saturating_mul(10).saturating_add((c - b'0') as u32);
+    }
+    u8::try_from(value).map_err(|_| IPv4AddressError::InvalidSegment)
+}
+
 /// Construct an IPv4 address from a sequence of octets, in big-endian.
 pub fn from_bytes(data: &[u8]) -> Result<IPv4, IPv4AddressError> {
     if data.len() != ADDR_SIZE {
@@ -140,37 +263,189 @@ pub fn from_u32(addr: u32) -> IPv4 {
 }
 
 /// Display IPv4 address as text representation
-impl std::fmt::Display for IPv4 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+impl core::fmt::Display for IPv4 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.0[0], self.0[1], self.0[2], self.0[3])
     }
 }
 
 /// Debug display IPv4 address
-impl std::fmt::Debug for IPv4 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+impl core::fmt::Debug for IPv4 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}", self)
     }
 }
 
-impl std::str::FromStr for IPv4 {
+impl core::str::FromStr for IPv4 {
     type Err = IPv4AddressError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        return from_string(s);
+        from_string(s)
+    }
+}
+
+impl From<[u8; ADDR_SIZE]> for IPv4 {
+    fn from(octets: [u8; ADDR_SIZE]) -> IPv4 {
+        IPv4(octets)
+    }
+}
+
+/// An IPv4 network in [CIDR notation], e.g. `192.168.1.0/24`.
+///
+/// The address is stored as given; use `network()` to get the
+/// address with the host bits cleared.
+///
+/// [CIDR notation]: https://datatracker.ietf.org/doc/html/rfc4632#section-3.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv4Net {
+    addr: IPv4,
+    prefix: u8,
+}
+
+impl Ipv4Net {
+    /// Construct an IPv4 network from an address and a prefix length.
+    pub fn new(addr: IPv4, prefix: u8) -> Result<Self, IPv4AddressError> {
+        if prefix > MAX_PREFIX_LEN {
+            return Err(IPv4AddressError::InvalidPrefixLength);
+        }
+        Ok(Ipv4Net { addr, prefix })
+    }
+
+    /// Construct an IPv4 network, rejecting an address with any host bits
+    /// set, e.g. `10.0.0.5/24` where `10.0.0.0/24` was meant.
+    pub fn new_strict(addr: IPv4, prefix: u8) -> Result<Self, IPv4AddressError> {
+        let net = Self::new(addr, prefix)?;
+        if net.network() != addr {
+            return Err(IPv4AddressError::HostBitsSet);
+        }
+        Ok(net)
+    }
+
+    /// Return the address the network was constructed with.
+    pub fn addr(&self) -> IPv4 {
+        self.addr
+    }
+
+    /// Return the prefix length, in bits.
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Return the netmask, e.g. `255.255.255.0` for a /24.
+    pub fn netmask(&self) -> IPv4 {
+        from_u32(self.netmask_u32())
+    }
+
+    /// Return the hostmask, e.g. `0.0.0.255` for a /24.
+    pub fn hostmask(&self) -> IPv4 {
+        from_u32(!self.netmask_u32())
+    }
+
+    /// Return the network address (host bits cleared).
+    pub fn network(&self) -> IPv4 {
+        from_u32(self.addr.to_u32() & self.netmask_u32())
+    }
+
+    /// Return the broadcast address (host bits set).
+    pub fn broadcast(&self) -> IPv4 {
+        from_u32(self.addr.to_u32() | !self.netmask_u32())
+    }
+
+    /// Query if the address falls within the network.
+    pub fn contains(&self, addr: &IPv4) -> bool {
+        let mask = self.netmask_u32();
+        (addr.to_u32() & mask) == (self.addr.to_u32() & mask)
+    }
+
+    /// Return an iterator over the usable host addresses of the network.
+    ///
+    /// The network and broadcast addresses are excluded, except for a /31
+    /// which yields both addresses ([RFC 3021]) and a /32 which yields the
+    /// single address.
+    ///
+    /// [RFC 3021]: https://datatracker.ietf.org/doc/html/rfc3021
+    pub fn hosts(&self) -> Ipv4Hosts {
+        let network = self.network().to_u32();
+        let broadcast = self.broadcast().to_u32();
+        let (next, remaining) = match self.prefix {
+            32 => (network, 1),
+            31 => (network, 2),
+            _ => (network + 1, (broadcast - network - 1) as u64),
+        };
+        Ipv4Hosts { next, remaining }
+    }
+
+    fn netmask_u32(&self) -> u32 {
+        // A shift by 32 overflows, so /0 is handled as an empty mask.
+        u32::MAX
+            .checked_shl((MAX_PREFIX_LEN - self.prefix) as u32)
+            .unwrap_or(0)
+    }
+}
+
+/// Lazy iterator over the host addresses of an `Ipv4Net`.
+#[derive(Debug, Clone)]
+pub struct Ipv4Hosts {
+    next: u32,
+    remaining: u64,
+}
+
+impl Iterator for Ipv4Hosts {
+    type Item = IPv4;
+
+    fn next(&mut self) -> Option<IPv4> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let addr = from_u32(self.next);
+        self.next = self.next.wrapping_add(1);
+        self.remaining -= 1;
+        Some(addr)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Ipv4Hosts {}
+
+/// Construct an IPv4 network from a string in CIDR notation
+pub fn net_from_string(s: &str) -> Result<Ipv4Net, IPv4AddressError> {
+    let (addr_str, prefix_str) = s.split_once('/').ok_or(IPv4AddressError::InvalidFormat)?;
+    let addr = from_string(addr_str)?;
+    let prefix = prefix_str
+        .parse::<u8>()
+        .map_err(|_| IPv4AddressError::InvalidPrefixLength)?;
+    Ipv4Net::new(addr, prefix)
+}
+
+/// Display IPv4 network in CIDR notation
+impl core::fmt::Display for Ipv4Net {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl core::str::FromStr for Ipv4Net {
+    type Err = IPv4AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        net_from_string(s)
     }
 }
 
 #[cfg(feature = "std")]
-impl From<::std::net::Ipv4Addr> for IPv4 {
-    fn from(addr: ::std::net::Ipv4Addr) -> IPv4 {
+impl From<core::net::Ipv4Addr> for IPv4 {
+    fn from(addr: core::net::Ipv4Addr) -> IPv4 {
         IPv4(addr.octets())
     }
 }
 
 #[cfg(feature = "std")]
-impl From<IPv4> for ::std::net::Ipv4Addr {
-    fn from(IPv4(addr): IPv4) -> ::std::net::Ipv4Addr {
+impl From<IPv4> for core::net::Ipv4Addr {
+    fn from(IPv4(addr): IPv4) -> core::net::Ipv4Addr {
         addr.into()
     }
 }
@@ -193,6 +468,13 @@ impl defmt::Format for IPv4 {
 mod tests {
     use super::*;
 
+    const GATEWAY: IPv4 = IPv4::new(192, 168, 1, 1);
+
+    #[test]
+    fn test_const_new() {
+        assert_eq!(GATEWAY.to_bytes(), [192, 168, 1, 1]);
+    }
+
     #[test]
     fn test_new() {
         let ipv4 = IPv4::new(192, 168, 1, 1);
@@ -205,6 +487,38 @@ mod tests {
         assert_eq!(ipv4, IPv4::new(192, 168, 1, 1));
     }
 
+    #[test]
+    fn test_from_str_leading_trailing_dot() {
+        assert_eq!(from_string("192.168.1.1."), Err(IPv4AddressError::InvalidFormat));
+        assert_eq!(from_string(".192.168.1.1"), Err(IPv4AddressError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_from_str_shorthand_rejected() {
+        assert_eq!(from_string("127.1"), Err(IPv4AddressError::InvalidLength));
+        assert_eq!(from_string("1.2.3"), Err(IPv4AddressError::InvalidLength));
+        assert_eq!(from_string("1.2.3.4.5"), Err(IPv4AddressError::InvalidLength));
+    }
+
+    #[test]
+    fn test_from_str_segment_out_of_range() {
+        assert_eq!(from_string("256.1.1.1"), Err(IPv4AddressError::InvalidSegment));
+        assert_eq!(from_string("1.1.1.99999999999"), Err(IPv4AddressError::InvalidSegment));
+        assert_eq!(from_string("255.255.255.255"), Ok(BROADCAST));
+    }
+
+    #[test]
+    fn test_from_str_invalid_character() {
+        assert_eq!(from_string("1.2.3.a"), Err(IPv4AddressError::InvalidCharacter));
+        assert_eq!(from_string("1.2.+3.4"), Err(IPv4AddressError::InvalidCharacter));
+        assert_eq!(from_string("1.2.3. 4"), Err(IPv4AddressError::InvalidCharacter));
+    }
+
+    #[test]
+    fn test_from_str_empty_segment() {
+        assert_eq!(from_string("1..2.3"), Err(IPv4AddressError::InvalidFormat));
+    }
+
     #[test]
     fn test_to_bytes() {
         let ipv4 = IPv4::new(192, 168, 1, 1);
@@ -306,4 +620,237 @@ mod tests {
         let public_addr = IPv4::new(8, 8, 8, 8); // Google DNS for example
         assert!(!public_addr.is_private());
     }
+
+    #[test]
+    fn test_ordering() {
+        let a = IPv4::new(10, 0, 0, 1);
+        let b = IPv4::new(10, 0, 0, 2);
+        let c = IPv4::new(192, 168, 0, 1);
+        assert!(a < b && b < c);
+
+        let sorted: std::collections::BTreeSet<IPv4> = [c, a, b].into_iter().collect();
+        assert_eq!(sorted.into_iter().collect::<Vec<_>>(), vec![a, b, c]);
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        let addr = IPv4::new(192, 168, 1, 1);
+        assert_eq!(addr.stable_hash(), 0x6dc4_1fc3_2fd7_d4c7);
+        assert_ne!(addr.stable_hash(), IPv4::new(192, 168, 1, 2).stable_hash());
+    }
+
+    #[test]
+    fn test_is_documentation() {
+        assert!(IPv4::new(192, 0, 2, 0).is_documentation());
+        assert!(IPv4::new(192, 0, 2, 255).is_documentation());
+        assert!(!IPv4::new(192, 0, 1, 255).is_documentation());
+        assert!(!IPv4::new(192, 0, 3, 0).is_documentation());
+
+        assert!(IPv4::new(198, 51, 100, 0).is_documentation());
+        assert!(IPv4::new(198, 51, 100, 255).is_documentation());
+        assert!(!IPv4::new(198, 51, 99, 255).is_documentation());
+        assert!(!IPv4::new(198, 51, 101, 0).is_documentation());
+
+        assert!(IPv4::new(203, 0, 113, 0).is_documentation());
+        assert!(IPv4::new(203, 0, 113, 255).is_documentation());
+        assert!(!IPv4::new(203, 0, 112, 255).is_documentation());
+        assert!(!IPv4::new(203, 0, 114, 0).is_documentation());
+    }
+
+    #[test]
+    fn test_is_shared() {
+        assert!(IPv4::new(100, 64, 0, 0).is_shared());
+        assert!(IPv4::new(100, 127, 255, 255).is_shared());
+        assert!(!IPv4::new(100, 63, 255, 255).is_shared());
+        assert!(!IPv4::new(100, 128, 0, 0).is_shared());
+    }
+
+    #[test]
+    fn test_is_global() {
+        assert!(IPv4::new(8, 8, 8, 8).is_global());
+        assert!(!IPv4::new(10, 0, 0, 1).is_global());
+        assert!(!IPv4::new(127, 0, 0, 1).is_global());
+        assert!(!IPv4::new(169, 254, 1, 1).is_global());
+        assert!(!IPv4::new(224, 0, 0, 1).is_global());
+        assert!(!IPv4::new(192, 0, 2, 1).is_global());
+        assert!(!IPv4::new(100, 64, 0, 1).is_global());
+        assert!(!BROADCAST.is_global());
+        assert!(!UNSPECIFIED.is_global());
+
+        // This network
+        assert!(!IPv4::new(0, 1, 2, 3).is_global());
+        // IETF protocol assignments, except the two global ones
+        assert!(!IPv4::new(192, 0, 0, 0).is_global());
+        assert!(!IPv4::new(192, 0, 0, 255).is_global());
+        assert!(IPv4::new(192, 0, 0, 9).is_global());
+        assert!(IPv4::new(192, 0, 0, 10).is_global());
+        assert!(IPv4::new(192, 0, 1, 1).is_global());
+        // Benchmarking
+        assert!(!IPv4::new(198, 18, 0, 1).is_global());
+        assert!(!IPv4::new(198, 19, 255, 255).is_global());
+        assert!(IPv4::new(198, 20, 0, 1).is_global());
+        // Reserved
+        assert!(!IPv4::new(240, 0, 0, 1).is_global());
+        assert!(!IPv4::new(255, 255, 255, 254).is_global());
+        assert!(IPv4::new(223, 255, 255, 255).is_global());
+    }
+
+    #[test]
+    fn test_is_benchmarking_and_reserved() {
+        assert!(IPv4::new(198, 18, 0, 0).is_benchmarking());
+        assert!(IPv4::new(198, 19, 255, 255).is_benchmarking());
+        assert!(!IPv4::new(198, 17, 255, 255).is_benchmarking());
+        assert!(!IPv4::new(198, 20, 0, 0).is_benchmarking());
+
+        assert!(IPv4::new(240, 0, 0, 0).is_reserved());
+        assert!(IPv4::new(255, 255, 255, 254).is_reserved());
+        assert!(!IPv4::new(239, 255, 255, 255).is_reserved());
+        assert!(!BROADCAST.is_reserved());
+    }
+
+    #[test]
+    fn test_is_valid_source_and_destination() {
+        assert!(IPv4::new(192, 168, 1, 1).is_valid_source());
+        assert!(!IPv4::new(224, 0, 0, 1).is_valid_source());
+        assert!(!BROADCAST.is_valid_source());
+        assert!(!UNSPECIFIED.is_valid_source());
+        assert!(IPv4::new(224, 0, 0, 1).is_valid_destination());
+        assert!(BROADCAST.is_valid_destination());
+        assert!(!UNSPECIFIED.is_valid_destination());
+    }
+
+    #[test]
+    fn test_net_masks() {
+        let net = Ipv4Net::new(IPv4::new(192, 168, 1, 77), 24).unwrap();
+        assert_eq!(net.netmask(), IPv4::new(255, 255, 255, 0));
+        assert_eq!(net.hostmask(), IPv4::new(0, 0, 0, 255));
+        assert_eq!(net.network(), IPv4::new(192, 168, 1, 0));
+        assert_eq!(net.broadcast(), IPv4::new(192, 168, 1, 255));
+    }
+
+    #[test]
+    fn test_net_contains() {
+        let net = Ipv4Net::new(IPv4::new(192, 168, 1, 0), 24).unwrap();
+        assert!(net.contains(&IPv4::new(192, 168, 1, 1)));
+        assert!(net.contains(&IPv4::new(192, 168, 1, 255)));
+        assert!(!net.contains(&IPv4::new(192, 168, 2, 1)));
+    }
+
+    #[test]
+    fn test_net_single_host() {
+        let net = Ipv4Net::new(IPv4::new(10, 0, 0, 1), 32).unwrap();
+        assert_eq!(net.netmask(), BROADCAST);
+        assert_eq!(net.network(), IPv4::new(10, 0, 0, 1));
+        assert_eq!(net.broadcast(), IPv4::new(10, 0, 0, 1));
+        assert!(net.contains(&IPv4::new(10, 0, 0, 1)));
+        assert!(!net.contains(&IPv4::new(10, 0, 0, 2)));
+    }
+
+    #[test]
+    fn test_net_all_addresses() {
+        let net = Ipv4Net::new(IPv4::new(10, 0, 0, 1), 0).unwrap();
+        assert_eq!(net.netmask(), UNSPECIFIED);
+        assert_eq!(net.hostmask(), BROADCAST);
+        assert_eq!(net.network(), UNSPECIFIED);
+        assert_eq!(net.broadcast(), BROADCAST);
+        assert!(net.contains(&IPv4::new(8, 8, 8, 8)));
+        assert!(net.contains(&BROADCAST));
+    }
+
+    #[test]
+    fn test_net_new_strict() {
+        let host = IPv4::new(10, 0, 0, 5);
+        assert_eq!(Ipv4Net::new_strict(host, 24), Err(IPv4AddressError::HostBitsSet));
+        // `new` accepts it; `network()` gives the normalized address.
+        assert_eq!(Ipv4Net::new(host, 24).unwrap().network(), IPv4::new(10, 0, 0, 0));
+
+        let net = Ipv4Net::new_strict(IPv4::new(10, 0, 0, 0), 24).unwrap();
+        assert_eq!(net.addr(), IPv4::new(10, 0, 0, 0));
+        assert!(Ipv4Net::new_strict(host, 32).is_ok());
+        assert_eq!(
+            Ipv4Net::new_strict(host, 33),
+            Err(IPv4AddressError::InvalidPrefixLength)
+        );
+    }
+
+    #[test]
+    fn test_net_invalid_prefix() {
+        assert_eq!(
+            Ipv4Net::new(IPv4::new(10, 0, 0, 0), 33),
+            Err(IPv4AddressError::InvalidPrefixLength)
+        );
+        assert_eq!(
+            "10.0.0.0/33".parse::<Ipv4Net>(),
+            Err(IPv4AddressError::InvalidPrefixLength)
+        );
+    }
+
+    #[test]
+    fn test_net_hosts_slash_30() {
+        let net: Ipv4Net = "192.168.1.0/30".parse().unwrap();
+        let hosts = net.hosts();
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(
+            hosts.collect::<Vec<_>>(),
+            vec![IPv4::new(192, 168, 1, 1), IPv4::new(192, 168, 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_net_hosts_slash_31() {
+        let net: Ipv4Net = "192.168.1.0/31".parse().unwrap();
+        let hosts = net.hosts();
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(
+            hosts.collect::<Vec<_>>(),
+            vec![IPv4::new(192, 168, 1, 0), IPv4::new(192, 168, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_net_hosts_slash_32() {
+        let net: Ipv4Net = "10.0.0.7/32".parse().unwrap();
+        let hosts = net.hosts();
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts.collect::<Vec<_>>(), vec![IPv4::new(10, 0, 0, 7)]);
+    }
+
+    #[test]
+    fn test_net_hosts_len_without_consuming() {
+        let net: Ipv4Net = "10.0.0.0/24".parse().unwrap();
+        let mut hosts = net.hosts();
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(hosts.next(), Some(IPv4::new(10, 0, 0, 1)));
+        assert_eq!(hosts.len(), 253);
+        assert_eq!(hosts.last(), Some(IPv4::new(10, 0, 0, 254)));
+
+        let all: Ipv4Net = "0.0.0.0/0".parse().unwrap();
+        assert_eq!(all.hosts().len(), u32::MAX as usize - 1);
+    }
+
+    #[test]
+    fn test_net_from_str() {
+        let net: Ipv4Net = "192.168.1.0/24".parse().unwrap();
+        assert_eq!(net.addr(), IPv4::new(192, 168, 1, 0));
+        assert_eq!(net.prefix(), 24);
+        assert_eq!(net.to_string(), "192.168.1.0/24");
+        assert_eq!("192.168.1.0".parse::<Ipv4Net>(), Err(IPv4AddressError::InvalidFormat));
+        assert!("192.168.1.0/x".parse::<Ipv4Net>().is_err());
+    }
+
+    #[test]
+    fn test_from_array() {
+        assert_eq!(IPv4::from([192, 168, 1, 1]), GATEWAY);
+        let addr: IPv4 = [255; ADDR_SIZE].into();
+        assert_eq!(addr, BROADCAST);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_to_ipv6_mapped() {
+        let mapped = GATEWAY.to_ipv6_mapped();
+        assert!(ipv6::is_ipv4_mapped(&mapped));
+        assert_eq!(ipv6::to_string(&mapped), "::ffff:192.168.1.1");
+        assert_eq!(ipv6::to_ipv4(&mapped), Some(GATEWAY));
+    }
 }
@@ -1,15 +1,20 @@
 // src/address/ipv6.rs
 
-/// IPv6 Address support library
-///
-/// Provides abstraction over the IPv6 Address and various utility
-/// functions that operate on IPv6 Addresses. It allows parsing, formatting,
-/// and manipulation of IPv6 addresses with robust error handling for common
-/// issues encountered
-///
-/// # Example
-/// ```
-/// ```
+//! IPv6 Address support library
+//!
+//! Provides abstraction over the IPv6 Address and various utility
+//! functions that operate on IPv6 Addresses. It allows parsing, formatting,
+//! and manipulation of IPv6 addresses with robust error handling for common
+//! issues encountered
+//!
+//! # Example
+//! ```
+//! ```
+
+use super::ipv4::IPv4;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
 
 
 /// Size of IPv6 adderess in octets.
@@ -36,45 +41,75 @@ pub const LOOPBACK: IPv6 = IPv6([
 pub const IPV4_MAPPED_PREFIX: [u8; 12] =
     [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff];
 
+/// The first 104 bits of a [solicited-node multicast address],
+/// `ff02::1:ff00:0/104`.
+///
+/// [solicited-node multicast address]: https://www.rfc-editor.org/rfc/rfc4291#section-2.7.1
+pub const SOLICITED_NODE_PREFIX: [u8; 13] =
+    [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0xff];
+
+/// Maximum prefix length of an IPv6 network, in bits.
+pub const MAX_PREFIX_LEN: u8 = 128;
+
 #[derive(Debug, PartialEq)]
 pub enum IPv6AddressError {
     InvalidLength,
     InvalidFormat,
     InvalidCharacter,
     UnsupportedOperation,
+    InvalidPrefixLength,
 }
 
-impl std::fmt::Display for IPv6AddressError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for IPv6AddressError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match *self {
             IPv6AddressError::InvalidLength => write!(f, "IPv6 address must have exactly 16 octets"),
             IPv6AddressError::InvalidFormat => write!(f, "Invalid IPv6 address format"),
             IPv6AddressError::InvalidCharacter => write!(f, "IPv6 address contains invalid characters"),
             IPv6AddressError::UnsupportedOperation => write!(f, "Unsupported operation for IPv6 address"),
+            IPv6AddressError::InvalidPrefixLength => write!(f, "IPv6 prefix length out of range (0-128)"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for IPv6AddressError {}
 
 
 /// A sixteen-octet (128 bits) IPv6 address.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Addresses are ordered as unsigned 128-bit integers, since the octets are
+/// stored in big-endian.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct IPv6([u8; 16]);
 
 /// Display IPv6 address as text representation
 ///
 /// [Text Representation of Addresses]: https://datatracker.ietf.org/doc/html/rfc4291#section-2.2
-impl std::fmt::Display for IPv6 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", to_string(self))
+impl core::fmt::Display for IPv6 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write_compressed(self, f)
     }
 }
 
 /// Debug display IPv6 address
-impl std::fmt::Debug for IPv6 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", to_string(self))
+impl core::fmt::Debug for IPv6 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write_compressed(self, f)
+    }
+}
+
+impl core::str::FromStr for IPv6 {
+    type Err = IPv6AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        from_string(s)
+    }
+}
+
+impl From<[u8; ADDR_SIZE]> for IPv6 {
+    fn from(octets: [u8; ADDR_SIZE]) -> IPv6 {
+        IPv6(octets)
     }
 }
 
@@ -84,7 +119,8 @@ impl IPv6 {
     /// Construct an IPv6 address from word segments.
     // TODO
     // - Benchmark direct casting vs manual bitwise masking with 0xFF
-    pub fn new(
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
         seg0: u16,
         seg1: u16,
         seg2: u16,
@@ -119,13 +155,98 @@ impl IPv6 {
         &self.0
     }
 
+    /// Return a hash of the address that is identical across runs and processes.
+    ///
+    /// Unlike the derived `Hash`, the result does not depend on a `Hasher`
+    /// or its per-process seed.
+    pub fn stable_hash(&self) -> u64 {
+        super::fnv1a(&self.0)
+    }
+
 }
 
-/// Construct an IPv6 address from a string
+/// Construct an IPv6 address from its [text representation], e.g.
+/// `2001:db8::1` or `::ffff:192.0.2.1`.
+///
+/// A group with anything but hex digits fails with `InvalidCharacter`.
+/// More than one `::`, an empty or over-long group, or a bad embedded
+/// IPv4 address fail with `InvalidFormat`, and the wrong number of groups
+/// with `InvalidLength`.
+///
+/// [text representation]: https://datatracker.ietf.org/doc/html/rfc4291#section-2.2
 pub fn from_string(addr_str: &str) -> Result<IPv6, IPv6AddressError> {
-    addr_str.parse::<std::net::Ipv6Addr>()
-        .map(|addr| IPv6(addr.octets()))
-        .map_err(|_| IPv6AddressError::InvalidFormat)
+    if addr_str.is_empty() {
+        return Err(IPv6AddressError::InvalidFormat);
+    }
+
+    // Split around the `::` that stands for one or more groups of zeros.
+    let (head, tail) = match addr_str.find("::") {
+        Some(pos) => {
+            let tail = &addr_str[pos + 2..];
+            if tail.contains("::") {
+                return Err(IPv6AddressError::InvalidFormat);
+            }
+            (&addr_str[..pos], Some(tail))
+        }
+        None => (addr_str, None),
+    };
+
+    let mut segments = [0u16; 8];
+    // An embedded IPv4 address may only end the address.
+    let head_len = parse_groups(head, &mut segments, tail.is_none())?;
+    match tail {
+        None if head_len != 8 => return Err(IPv6AddressError::InvalidLength),
+        None => {}
+        Some(tail) => {
+            let mut tail_segments = [0u16; 8];
+            let tail_len = parse_groups(tail, &mut tail_segments, true)?;
+            if head_len + tail_len > 7 {
+                return Err(IPv6AddressError::InvalidLength);
+            }
+            segments[8 - tail_len..].copy_from_slice(&tail_segments[..tail_len]);
+        }
+    }
+    from_segments(&segments)
+}
+
+// Parse colon-separated groups into `segments`, returning how many were
+// filled. An embedded IPv4 address fills two.
+fn parse_groups(s: &str, segments: &mut [u16; 8], ipv4_allowed: bool) -> Result<usize, IPv6AddressError> {
+    if s.is_empty() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    let mut groups = s.split(':').peekable();
+    while let Some(group) = groups.next() {
+        if ipv4_allowed && groups.peek().is_none() && group.contains('.') {
+            if count + 2 > segments.len() {
+                return Err(IPv6AddressError::InvalidLength);
+            }
+            let octets = super::ipv4::from_string(group)
+                .map_err(|_| IPv6AddressError::InvalidFormat)?
+                .to_bytes();
+            segments[count] = u16::from_be_bytes([octets[0], octets[1]]);
+            segments[count + 1] = u16::from_be_bytes([octets[2], octets[3]]);
+            return Ok(count + 2);
+        }
+        if count == segments.len() {
+            return Err(IPv6AddressError::InvalidLength);
+        }
+        segments[count] = parse_group(group)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+// Parse a single group of one to four hex digits
+fn parse_group(group: &str) -> Result<u16, IPv6AddressError> {
+    if !group.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(IPv6AddressError::InvalidCharacter);
+    }
+    if group.is_empty() || group.len() > 4 {
+        return Err(IPv6AddressError::InvalidFormat);
+    }
+    u16::from_str_radix(group, 16).map_err(|_| IPv6AddressError::InvalidFormat)
 }
 
 /// Construct an IPv6 address from an array of octets in big-endian
@@ -159,7 +280,7 @@ pub fn to_segments(addr: &IPv6) -> [u16; 8]{
     let mut segments = [0u16; 8];
     for (i, chunk) in addr.0.chunks(2).enumerate() {
         let upper_word = (chunk[0] as u16) << 8;
-        segments[i] = upper_word | (chunk[1] as u16) as u16;
+        segments[i] = upper_word | chunk[1] as u16;
     }
     segments
 }
@@ -167,10 +288,16 @@ pub fn to_segments(addr: &IPv6) -> [u16; 8]{
 /// Return an IPv6 address as a zero compressed string
 ///
 /// [Zero compressed notation]: https://tools.ietf.org/html/rfc4291#section-2.2
+#[cfg(feature = "alloc")]
 pub fn to_string(addr: &IPv6) -> String {
+    format!("{}", addr)
+}
 
+// Write an IPv6 address in zero compressed notation
+fn write_compressed(addr: &IPv6, f: &mut core::fmt::Formatter) -> core::fmt::Result {
     if is_ipv4_mapped(addr) {
-        return format!(
+        return write!(
+            f,
             "::ffff:{}.{}.{}.{}",
             addr.0[12],
             addr.0[13],
@@ -187,34 +314,37 @@ pub fn to_string(addr: &IPv6) -> String {
     }
     let segments = to_segments(addr);
     let mut state = State::Head;
-    let mut result = String::new();
     for segment in segments.iter() {
         match (*segment, &state) {
             (0, State::Head) | (0, State::HeadBody) => {
-                result.push_str("::");
+                f.write_str("::")?;
                 state = State::Tail
             }
             (0, State::Tail) => {}, // continue
             (_, State::Head) => {
-                result.push_str(&format!("{:x}", segment));
+                write!(f, "{:x}", segment)?;
                 state = State::HeadBody
             }
             (_, State::Tail) => {
-                result.push_str(&format!("{:x}", segment));
+                write!(f, "{:x}", segment)?;
                 state = State::TailBody
             }
             (_, State::HeadBody) | (_, State::TailBody) => {
-                result.push_str(&format!(":{:x}", segment));
+                write!(f, ":{:x}", segment)?;
             }
         };
     }
 
-    result
+    Ok(())
 }
 
-// Cpnvert an IPv4 mapped IPv6 address to an IPv4 mapped
-pub fn to_ipv4(_addr: IPv6) {
-    todo!()
+/// Return the IPv4 address embedded in an IPv4-mapped IPv6 address, or
+/// `None` if the address is not IPv4-mapped.
+pub fn to_ipv4(addr: &IPv6) -> Option<IPv4> {
+    if !is_ipv4_mapped(addr) {
+        return None;
+    }
+    Some(IPv4::new(addr.0[12], addr.0[13], addr.0[14], addr.0[15]))
 }
 
 /// Query if the IPv6 address is a unicast address.
@@ -234,6 +364,59 @@ pub fn is_multicast(addr: &IPv6) -> bool {
     addr.0[0] == 0xFF
 }
 
+/// Scope of an IPv6 multicast address, as listed in
+/// [IPv6 Multicast Address Scopes].
+///
+/// [IPv6 Multicast Address Scopes]: https://datatracker.ietf.org/doc/html/rfc7346#section-2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MulticastScope {
+    InterfaceLocal = 0x1,
+    LinkLocal = 0x2,
+    RealmLocal = 0x3,
+    AdminLocal = 0x4,
+    SiteLocal = 0x5,
+    OrganizationLocal = 0x8,
+    Global = 0xE,
+}
+
+/// Return the scope of a multicast address, or `None` if the address is
+/// not multicast or its scope value is reserved or unassigned.
+pub fn multicast_scope(addr: &IPv6) -> Option<MulticastScope> {
+    if !is_multicast(addr) {
+        return None;
+    }
+    match addr.0[1] & 0x0F {
+        0x1 => Some(MulticastScope::InterfaceLocal),
+        0x2 => Some(MulticastScope::LinkLocal),
+        0x3 => Some(MulticastScope::RealmLocal),
+        0x4 => Some(MulticastScope::AdminLocal),
+        0x5 => Some(MulticastScope::SiteLocal),
+        0x8 => Some(MulticastScope::OrganizationLocal),
+        0xE => Some(MulticastScope::Global),
+        _ => None,
+    }
+}
+
+/// Multicast flag for a transient (not permanently assigned) address.
+pub const MULTICAST_FLAG_TRANSIENT: u8 = 0b0001;
+/// Multicast flag for an address [based on a unicast prefix].
+///
+/// [based on a unicast prefix]: https://datatracker.ietf.org/doc/html/rfc3306
+pub const MULTICAST_FLAG_PREFIX: u8 = 0b0010;
+/// Multicast flag for an address [embedding a rendezvous point].
+///
+/// [embedding a rendezvous point]: https://datatracker.ietf.org/doc/html/rfc3956
+pub const MULTICAST_FLAG_RENDEZVOUS: u8 = 0b0100;
+
+/// Return the flags nibble of a multicast address, or `None` if the
+/// address is not multicast.
+pub fn multicast_flags(addr: &IPv6) -> Option<u8> {
+    if !is_multicast(addr) {
+        return None;
+    }
+    Some(addr.0[1] >> 4)
+}
+
 /// Query if the IPv6 address is the [unspecified address].
 pub fn is_unspecified(addr: &IPv6) -> bool {
     addr.0 == UNSPECIFIED.0
@@ -263,8 +446,28 @@ pub fn is_ipv4_mapped(addr: &IPv6) -> bool {
 
 /// Checks if an IPv6 address is a solicited-node multicast address.
 pub fn is_solicited_node_multicast(addr: &IPv6) -> bool {
-    addr.0[0] == 0xff && addr.0[1] == 0x02 && addr.0[11] == 0x01 &&
-    addr.0[12] == 0xff && (addr.0[13] & addr.0[14] & addr.0[15]) != 0x00
+    addr.0[..13] == SOLICITED_NODE_PREFIX
+}
+
+/// Return the solicited-node multicast address for `addr`, formed from
+/// its low 24 bits, which Neighbor Solicitations are sent to.
+pub fn solicited_node_multicast(addr: &IPv6) -> IPv6 {
+    let mut octets = [0u8; ADDR_SIZE];
+    octets[..13].copy_from_slice(&SOLICITED_NODE_PREFIX);
+    octets[13..].copy_from_slice(&addr.0[13..]);
+    IPv6(octets)
+}
+
+/// Query if the IPv6 address may be used as the source of a packet,
+/// i.e. it is neither multicast nor unspecified.
+pub fn is_valid_source(addr: &IPv6) -> bool {
+    !is_multicast(addr) && !is_unspecified(addr)
+}
+
+/// Query if the IPv6 address may be used as the destination of a packet,
+/// i.e. it is not the unspecified address.
+pub fn is_valid_destination(addr: &IPv6) -> bool {
+    !is_unspecified(addr)
 }
 
 /// Checks if an IPv6 address is a Teredo tunneling address.
@@ -277,30 +480,172 @@ pub fn is_6to4_tunneling(addr: &IPv6) -> bool {
     addr.0[0] == 0x20 && addr.0[1] == 0x02
 }
 
-// Helper function to mask an IPv6 address
-pub fn mask(_addr: &IPv6, _mask: u8) -> [u8; ADDR_SIZE]{
-    todo!()
+/// Return the octets of the address with all but the first `mask` bits
+/// cleared. A mask above 128 keeps the whole address.
+pub fn mask(addr: &IPv6, mask: u8) -> [u8; ADDR_SIZE]{
+    let bits = u128::from_be_bytes(addr.0);
+    let netmask = u128::MAX
+        .checked_shl(MAX_PREFIX_LEN.saturating_sub(mask) as u32)
+        .unwrap_or(0);
+    (bits & netmask).to_be_bytes()
+}
+
+/// An IPv6 network in [CIDR notation], e.g. `2001:db8::/32`.
+///
+/// The address is stored as given; use `network()` to get the
+/// address with the host bits cleared.
+///
+/// [CIDR notation]: https://datatracker.ietf.org/doc/html/rfc4291#section-2.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv6Net {
+    addr: IPv6,
+    prefix: u8,
+}
+
+impl Ipv6Net {
+    /// Construct an IPv6 network from an address and a prefix length.
+    pub fn new(addr: IPv6, prefix: u8) -> Result<Self, IPv6AddressError> {
+        if prefix > MAX_PREFIX_LEN {
+            return Err(IPv6AddressError::InvalidPrefixLength);
+        }
+        Ok(Ipv6Net { addr, prefix })
+    }
+
+    /// Return the address the network was constructed with.
+    pub fn addr(&self) -> IPv6 {
+        self.addr
+    }
+
+    /// Return the prefix length, in bits.
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Return the network address (host bits cleared).
+    pub fn network(&self) -> IPv6 {
+        IPv6(mask(&self.addr, self.prefix))
+    }
+
+    /// Query if the address falls within the network.
+    pub fn contains(&self, addr: &IPv6) -> bool {
+        mask(addr, self.prefix) == mask(&self.addr, self.prefix)
+    }
+}
+
+/// Construct an IPv6 network from a string in CIDR notation
+pub fn net_from_string(s: &str) -> Result<Ipv6Net, IPv6AddressError> {
+    let (addr_str, prefix_str) = s.split_once('/').ok_or(IPv6AddressError::InvalidFormat)?;
+    let addr = from_string(addr_str)?;
+    let prefix = prefix_str
+        .parse::<u8>()
+        .map_err(|_| IPv6AddressError::InvalidPrefixLength)?;
+    Ipv6Net::new(addr, prefix)
+}
+
+/// Display IPv6 network in CIDR notation
+impl core::fmt::Display for Ipv6Net {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl core::str::FromStr for Ipv6Net {
+    type Err = IPv6AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        net_from_string(s)
+    }
 }
 
 #[cfg(feature = "std")]
-impl From<std::net::Ipv6Addr> for IPv6 {
-    fn from(addr: std::net::Ipv6Addr) -> IPv6 {
+impl From<core::net::Ipv6Addr> for IPv6 {
+    fn from(addr: core::net::Ipv6Addr) -> IPv6 {
         IPv6(addr.octets())
     }
 }
 
 #[cfg(feature = "std")]
-impl From<IPv6> for std::net::Ipv6Addr {
-    fn from(IPv6(addr): IPv6) -> std::net::Ipv6Addr {
+impl From<IPv6> for core::net::Ipv6Addr {
+    fn from(IPv6(addr): IPv6) -> core::net::Ipv6Addr {
         addr.into()
     }
 }
 
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for IPv6 {
+    /// Formats in zero compressed notation, like `Display`.
+    fn format(&self, f: defmt::Formatter) {
+        if is_ipv4_mapped(self) {
+            defmt::write!(f, "::ffff:{=u8}.{=u8}.{=u8}.{=u8}", self.0[12], self.0[13], self.0[14], self.0[15]);
+            return;
+        }
+
+        let segments = to_segments(self);
+        let write_all = |segments: &[u16]| {
+            for (i, segment) in segments.iter().enumerate() {
+                if i > 0 {
+                    defmt::write!(f, ":");
+                }
+                defmt::write!(f, "{=u16:x}", segment);
+            }
+        };
+        // The first run of zero segments is compressed
+        match segments.iter().position(|segment| *segment == 0) {
+            Some(start) => {
+                let end = segments[start..].iter().position(|segment| *segment != 0)
+                    .map_or(segments.len(), |len| start + len);
+                write_all(&segments[..start]);
+                defmt::write!(f, "::");
+                write_all(&segments[end..]);
+            }
+            None => write_all(&segments),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format() {
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<IPv6>();
+    }
+
+    const ALL_NODES: IPv6 = IPv6::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+
+    #[test]
+    fn test_net_from_str() {
+        let net: Ipv6Net = "2001:db8::1/32".parse().unwrap();
+        assert_eq!(net.prefix(), 32);
+        assert_eq!(net.addr(), from_string("2001:db8::1").unwrap());
+        assert_eq!(net.network(), from_string("2001:db8::").unwrap());
+        assert!(net.contains(&from_string("2001:db8:ffff::7").unwrap()));
+        assert!(!net.contains(&from_string("2001:db9::").unwrap()));
+        assert_eq!(net.to_string(), "2001:db8::1/32");
+
+        let all: Ipv6Net = "::/0".parse().unwrap();
+        assert!(all.contains(&LOOPBACK));
+        let host: Ipv6Net = "::1/128".parse().unwrap();
+        assert_eq!(host.network(), LOOPBACK);
+    }
+
+    #[test]
+    fn test_net_from_str_invalid() {
+        assert_eq!("2001:db8::".parse::<Ipv6Net>(), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!("2001:db8::/129".parse::<Ipv6Net>(), Err(IPv6AddressError::InvalidPrefixLength));
+        assert_eq!("2001:db8::/x".parse::<Ipv6Net>(), Err(IPv6AddressError::InvalidPrefixLength));
+        assert_eq!("2001:zz8::/32".parse::<Ipv6Net>(), Err(IPv6AddressError::InvalidCharacter));
+    }
+
+    #[test]
+    fn test_const_new() {
+        assert_eq!(ALL_NODES, from_string("ff02::1").unwrap());
+    }
+
     #[test]
     fn test_checks() {
         let lla: IPv6 = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
@@ -351,14 +696,52 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_is_valid_source_and_destination() {
+        let link_local = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 0x1);
+        let all_nodes = IPv6::new(0xff02, 0, 0, 0, 0, 0, 0, 0x1);
+        assert!(is_valid_source(&link_local));
+        assert!(!is_valid_source(&all_nodes));
+        assert!(!is_valid_source(&UNSPECIFIED));
+        assert!(is_valid_destination(&all_nodes));
+        assert!(!is_valid_destination(&UNSPECIFIED));
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        let addr = IPv6::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x1);
+        assert_eq!(addr.stable_hash(), 0xf971_61b7_a3be_1c14);
+        assert_ne!(addr.stable_hash(), LOOPBACK.stable_hash());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let a = IPv6::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x1);
+        let b = IPv6::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x2);
+        let c = IPv6::new(0x2001, 0xdb8, 0, 0, 0, 0, 0x1, 0);
+        let d = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 0x1);
+        assert!(UNSPECIFIED < LOOPBACK);
+        assert!(a < b && b < c && c < d);
+
+        // Ordering matches the addresses read as unsigned 128-bit integers.
+        for (x, y) in [(a, b), (b, c), (c, d), (d, a)] {
+            assert_eq!(
+                x.cmp(&y),
+                u128::from_be_bytes(x.0).cmp(&u128::from_be_bytes(y.0))
+            );
+        }
+    }
+
     // Text representation
     #[test]
+    #[cfg(feature = "alloc")]
     fn display_standard_ipv6() {
         let addr = IPv6::new(0x2001, 0x0db8, 0x85a3, 0, 0, 0x8a2e, 0x0370, 0x7334);
         assert_eq!(to_string(&addr), "2001:db8:85a3::8a2e:370:7334");
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn display_zero_compressed_ipv6() {
         let addr = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 0x1);
         assert_eq!(to_string(&addr), "fe80::1");
@@ -375,24 +758,28 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn display_ipv4_mapped_ipv6() {
         let addr = IPv6([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 192, 168, 1, 1]);
         assert_eq!(to_string(&addr), "::ffff:192.168.1.1");
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn display_unique_local_address() {
         let addr = IPv6::new(0xfd00, 0, 0, 0, 0, 0, 0, 0x1);
         assert_eq!(to_string(&addr), "fd00::1");
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn display_link_local_address() {
         let addr = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 0x1);
         assert_eq!(to_string(&addr), "fe80::1");
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn display_full_ipv6() {
         let addr = IPv6::new(0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff);
         assert_eq!(to_string(&addr), "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff");
@@ -474,6 +861,50 @@ mod tests {
     }
 
     #[test]
+    fn test_parse_errors_are_specific() {
+        assert_eq!(from_string("2001::db8::1"), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!(from_string("2001:xyz::1"), Err(IPv6AddressError::InvalidCharacter));
+        assert_eq!(from_string("fe80::1%eth0"), Err(IPv6AddressError::InvalidCharacter));
+        assert_eq!(from_string(""), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!(from_string(":1::"), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!(from_string("2001:db8:::1"), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!(from_string("2001:12345::1"), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!(from_string("1:2:3:4:5:6:7"), Err(IPv6AddressError::InvalidLength));
+        assert_eq!(from_string("1:2:3:4:5:6:7:8:9"), Err(IPv6AddressError::InvalidLength));
+        assert_eq!(from_string("1:2:3:4::5:6:7:8"), Err(IPv6AddressError::InvalidLength));
+    }
+
+    #[test]
+    fn test_parse_compressed_forms() {
+        assert_eq!(from_string("::").unwrap(), UNSPECIFIED);
+        assert_eq!(from_string("::1").unwrap(), LOOPBACK);
+        assert_eq!(from_string("2001:db8::").unwrap(), IPv6::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0));
+        assert_eq!(from_string("1:2:3::6:7:8").unwrap(), IPv6::new(1, 2, 3, 0, 0, 6, 7, 8));
+        assert_eq!(from_string("1:2:3:4:5:6:7::").unwrap(), IPv6::new(1, 2, 3, 4, 5, 6, 7, 0));
+        assert_eq!(from_string("FE80::ABCD").unwrap(), IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 0xabcd));
+    }
+
+    #[test]
+    fn test_parse_embedded_ipv4() {
+        assert_eq!(
+            from_string("::ffff:1.2.3.4").unwrap(),
+            IPv6::new(0, 0, 0, 0, 0, 0xffff, 0x0102, 0x0304)
+        );
+        assert_eq!(
+            from_string("64:ff9b::192.0.2.33").unwrap(),
+            IPv6::new(0x64, 0xff9b, 0, 0, 0, 0, 0xc000, 0x0221)
+        );
+        assert_eq!(
+            from_string("0:0:0:0:0:ffff:10.0.0.1").unwrap(),
+            IPv6::new(0, 0, 0, 0, 0, 0xffff, 0x0a00, 0x0001)
+        );
+        assert_eq!(from_string("::ffff:1.2.3.256"), Err(IPv6AddressError::InvalidFormat));
+        assert_eq!(from_string("::1.2.3.4:ffff"), Err(IPv6AddressError::InvalidCharacter));
+        assert_eq!(from_string("1:2:3:4:5:6:7:1.2.3.4"), Err(IPv6AddressError::InvalidLength));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv6_to_string() {
         let ipv6 = IPv6([0x20, 0x01, 0x0d, 0xb8, 0x85, 0xa3, 0x00, 0x00, 0x00, 0x00, 0x8a, 0x2e, 0x03, 0x70, 0x73, 0x34]);
         assert_eq!(to_string(&ipv6), "2001:db8:85a3::8a2e:370:7334");
@@ -560,4 +991,56 @@ mod tests {
         let result = from_string(ipv6_str).unwrap();
         assert_eq!(result, expected_ipv6);
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_from_str_and_array() {
+        let addr: IPv6 = "::1".parse().unwrap();
+        assert_eq!(addr, LOOPBACK);
+        assert_eq!("2001:db8::x".parse::<IPv6>(), Err(IPv6AddressError::InvalidCharacter));
+
+        let mut octets = [0u8; ADDR_SIZE];
+        octets[15] = 1;
+        assert_eq!(IPv6::from(octets), LOOPBACK);
+        let addr: IPv6 = [0xff; ADDR_SIZE].into();
+        assert_eq!(to_string(&addr), "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff");
+    }
+
+    #[test]
+    fn test_to_ipv4() {
+        let mapped = from_string("::ffff:10.0.0.1").unwrap();
+        assert_eq!(to_ipv4(&mapped), Some(IPv4::new(10, 0, 0, 1)));
+        assert_eq!(to_ipv4(&LOOPBACK), None);
+    }
+
+    #[test]
+    fn test_multicast_scope() {
+        assert_eq!(multicast_scope(&ALL_NODES), Some(MulticastScope::LinkLocal));
+        assert_eq!(multicast_scope(&from_string("ff0e::1").unwrap()), Some(MulticastScope::Global));
+        assert_eq!(multicast_scope(&from_string("ff05::2").unwrap()), Some(MulticastScope::SiteLocal));
+        assert_eq!(multicast_scope(&from_string("ff06::1").unwrap()), None);
+        assert_eq!(multicast_scope(&LOOPBACK), None);
+    }
+
+    #[test]
+    fn test_multicast_flags() {
+        assert_eq!(multicast_flags(&ALL_NODES), Some(0));
+        let flags = multicast_flags(&from_string("ff3e:30:2001:db8::1").unwrap()).unwrap();
+        assert_eq!(flags, MULTICAST_FLAG_PREFIX | MULTICAST_FLAG_TRANSIENT);
+        assert_eq!(multicast_scope(&from_string("ff3e:30:2001:db8::1").unwrap()), Some(MulticastScope::Global));
+        assert_eq!(multicast_flags(&LOOPBACK), None);
+    }
+
+    #[test]
+    fn test_solicited_node_multicast() {
+        let addr = from_string("fe80::1:2:3:4").unwrap();
+        let solicited = solicited_node_multicast(&addr);
+        assert_eq!(solicited, from_string("ff02::1:ff03:0004").unwrap());
+        assert!(is_solicited_node_multicast(&solicited));
+        assert_eq!(multicast_scope(&solicited), Some(MulticastScope::LinkLocal));
+
+        assert!(is_solicited_node_multicast(&from_string("ff02::1:ff00:0").unwrap()));
+        assert!(!is_solicited_node_multicast(&from_string("ff02:1::1:ff00:1").unwrap()));
+        assert!(!is_solicited_node_multicast(&ALL_NODES));
+    }
 }
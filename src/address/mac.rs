@@ -1,15 +1,17 @@
 // src/address/mac.rs
 
-/// MAC Address support library
-///
-/// Provides abstraction over the MAC Address and various utility
-/// functions that operate on MAC Addresses. It allows parsing, formatting,
-/// and manipulation of MAC addresses with robust error handling for common
-/// issues encountered
-///
-/// # Example
-/// ```
-/// ```
+//! MAC Address support library
+//!
+//! Provides abstraction over the MAC Address and various utility
+//! functions that operate on MAC Addresses. It allows parsing, formatting,
+//! and manipulation of MAC addresses with robust error handling for common
+//! issues encountered
+//!
+//! # Example
+//! ```
+//! ```
+
+use crate::address::ipv6::IPv6;
 
 #[derive(Debug, PartialEq)]
 pub enum MacAddressParseError {
@@ -18,8 +20,8 @@ pub enum MacAddressParseError {
     InvalidCharacter,
 }
 
-impl std::fmt::Display for MacAddressParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for MacAddressParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match *self {
             MacAddressParseError::InvalidLength => write!(f, "MAC address must have exactly 6 octets"),
             MacAddressParseError::InvalidFormat => write!(f, "Each octet in a MAC address must be two hexadecimal digits"),
@@ -31,22 +33,29 @@ impl std::fmt::Display for MacAddressParseError {
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Mac(pub [u8; 6]);
 
+/// The broadcast address.
+pub const BROADCAST: Mac = Mac([0xff; 6]);
 
-impl std::fmt::Display for Mac {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+
+impl core::fmt::Display for Mac {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            self.0[0], self.0[1], self.0[2], self.0[3], self.0[4], self.0[5]
+        )
     }
 }
 
-impl std::fmt::Debug for Mac {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Mac Address({})", self.to_string())
+impl core::fmt::Debug for Mac {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Mac Address({})", self)
     }
 }
 
 // Allows using .parse() directly on string slices to create MacAddress instances.
 // Mac::from_str("...")
-impl std::str::FromStr for Mac {
+impl core::str::FromStr for Mac {
     type Err = MacAddressParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -54,9 +63,15 @@ impl std::str::FromStr for Mac {
     }
 }
 
+impl From<[u8; 6]> for Mac {
+    fn from(octets: [u8; 6]) -> Mac {
+        Mac(octets)
+    }
+}
+
 impl Mac {
     /// Construct a Mac address from bytes segments.
-    pub fn new(
+    pub const fn new(
         seg0: u8,
         seg1: u8,
         seg2: u8,
@@ -67,19 +82,26 @@ impl Mac {
         Mac([seg0, seg1, seg2, seg3, seg4, seg5])
     }
 
-    /// Convert Mac ddress into a string.
-    pub fn to_string(&self) -> String {
-        format!(
-            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-            self.0[0], self.0[1], self.0[2], self.0[3], self.0[4], self.0[5]
-        )
-    }
-
     /// Convert Mac address into array of bytes
     pub fn to_bytes(&self) -> [u8; 6] {
         self.0
     }
 
+    /// Return the Organizationally Unique Identifier (first three octets).
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// Return the NIC-specific part (last three octets).
+    pub fn nic_specific(&self) -> [u8; 3] {
+        [self.0[3], self.0[4], self.0[5]]
+    }
+
+    // Checks if the MAC address is the broadcast address
+    pub fn is_broadcast(&self) -> bool {
+        *self == BROADCAST
+    }
+
     // Checks if the MAC address is multicast
     pub fn is_multicast(&self) -> bool {
         (self.0[0] & 0x01) != 0
@@ -90,22 +112,66 @@ impl Mac {
         (self.0[0] & 0x02) != 0
     }
 
+    /// Return a random unicast, locally administered address drawn from
+    /// `rng`, e.g. for address privacy or test fixtures.
+    #[cfg(feature = "rand")]
+    pub fn random_local(rng: &mut impl rand::RngCore) -> Self {
+        let mut octets = [0u8; 6];
+        rng.fill_bytes(&mut octets);
+        octets[0] = (octets[0] | 0x02) & !0x01;
+        Mac(octets)
+    }
+
+    /// Return the [modified EUI-64] interface identifier for the MAC address.
+    ///
+    /// `ff:fe` is inserted between the OUI and the NIC-specific octets and
+    /// the universal/local bit is inverted.
+    ///
+    /// [modified EUI-64]: https://datatracker.ietf.org/doc/html/rfc4291#appendix-A
+    pub fn to_eui64(&self) -> [u8; 8] {
+        [
+            self.0[0] ^ 0x02,
+            self.0[1],
+            self.0[2],
+            0xff,
+            0xfe,
+            self.0[3],
+            self.0[4],
+            self.0[5],
+        ]
+    }
+
+    /// Return the `fe80::/64` link-local IPv6 address derived from the MAC address.
+    pub fn to_link_local_ipv6(&self) -> IPv6 {
+        let eui = self.to_eui64();
+        IPv6::new(
+            0xfe80,
+            0,
+            0,
+            0,
+            u16::from_be_bytes([eui[0], eui[1]]),
+            u16::from_be_bytes([eui[2], eui[3]]),
+            u16::from_be_bytes([eui[4], eui[5]]),
+            u16::from_be_bytes([eui[6], eui[7]]),
+        )
+    }
+
 }
 
 
 /// Construct a Mac address from a string
 pub fn from_string(s: &str) -> Result<Mac, MacAddressParseError> {
-    // Remove common MAC address delimiters to simplify parsing
-    let clean_s = s.replace(&[':', '-', '.'][..], "");
+    // Skip common MAC address delimiters to simplify parsing
+    let digits = || s.bytes().filter(|b| !matches!(b, b':' | b'-' | b'.'));
 
-    if clean_s.len() != 12 {
+    if digits().count() != 12 {
         return Err(MacAddressParseError::InvalidLength);
     }
     let mut mac_bytes = [0u8; 6];
-    for (i, byte_str) in clean_s.as_bytes().chunks(2).enumerate() {
-        let byte = u8::from_str_radix(std::str::from_utf8(byte_str).unwrap(), 16)
-            .map_err(|_| MacAddressParseError::InvalidCharacter)?;
-        mac_bytes[i] = byte;
+    for (i, digit) in digits().enumerate() {
+        let nibble = (digit as char).to_digit(16)
+            .ok_or(MacAddressParseError::InvalidCharacter)? as u8;
+        mac_bytes[i / 2] = (mac_bytes[i / 2] << 4) | nibble;
     }
 
     Ok(Mac(mac_bytes))
@@ -121,10 +187,40 @@ pub fn from_bytes(data: &[u8]) -> Result<Mac, MacAddressParseError> {
     Ok(Mac(bytes))
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Mac {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{=u8:02x}:{=u8:02x}:{=u8:02x}:{=u8:02x}:{=u8:02x}:{=u8:02x}",
+            self.0[0],
+            self.0[1],
+            self.0[2],
+            self.0[3],
+            self.0[4],
+            self.0[5]
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format() {
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<Mac>();
+    }
+
+    const ROUTER: Mac = Mac::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+
+    #[test]
+    fn test_const_new() {
+        assert_eq!(ROUTER.to_bytes(), [0x02, 0, 0, 0, 0, 0x01]);
+    }
+
     const VALID_CONVERSION_TEST_CASES: [(&str, [u8; 6]); 3] = [
         ("00:00:00:00:00:00", [0, 0, 0, 0, 0, 0]),
         ("01:23:45:67:89:ab", [0x01, 0x23, 0x45, 0x67, 0x89, 0xab]),
@@ -225,9 +321,65 @@ mod tests {
         assert!(!universal_mac.is_local());
     }
 
+    #[test]
+    fn test_oui_and_nic_specific() {
+        let mac = from_string("de:ad:be:ef:00:01").unwrap();
+        assert_eq!(mac.oui(), [0xde, 0xad, 0xbe]);
+        assert_eq!(mac.nic_specific(), [0xef, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn test_is_broadcast() {
+        let broadcast = from_string("ff:ff:ff:ff:ff:ff").unwrap();
+        assert!(broadcast.is_broadcast());
+        assert!(broadcast.is_multicast());
+        assert!(!from_string("de:ad:be:ef:00:01").unwrap().is_broadcast());
+        assert!(!from_string("ff:ff:ff:ff:ff:fe").unwrap().is_broadcast());
+    }
+
+    #[test]
+    fn test_to_eui64() {
+        let mac = Mac::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+        assert_eq!(mac.to_eui64(), [0x02, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55]);
+
+        // The universal/local bit is flipped back for locally administered addresses.
+        let local = Mac::new(0x02, 0x00, 0x00, 0x77, 0x77, 0x77);
+        assert_eq!(local.to_eui64()[0], 0x00);
+    }
+
+    #[test]
+    fn test_to_link_local_ipv6() {
+        let mac = Mac::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let addr = mac.to_link_local_ipv6();
+        assert_eq!(addr.to_string(), "fe80::211:22ff:fe33:4455");
+        assert!(crate::address::ipv6::is_link_local(&addr));
+    }
+
     #[test]
     fn test_parse_invalid_format() {
         let mac_str = "11:22:33:GG:55:66";
         assert_eq!(from_string(mac_str), Err(MacAddressParseError::InvalidCharacter));
     }
+
+    #[test]
+    fn test_from_str_and_array() {
+        let mac: Mac = "02:00:00:00:00:01".parse().unwrap();
+        assert_eq!(mac, Mac::from([0x02, 0, 0, 0, 0, 0x01]));
+        let broadcast: Mac = [0xff; 6].into();
+        assert_eq!(broadcast, BROADCAST);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random_local() {
+        use rand::rngs::mock::StepRng;
+
+        // The local bit is forced on and the multicast bit off
+        assert_eq!(Mac::random_local(&mut StepRng::new(0, 0)), Mac::new(0x02, 0, 0, 0, 0, 0));
+        assert_eq!(Mac::random_local(&mut StepRng::new(u64::MAX, 0)), Mac::new(0xfe, 0xff, 0xff, 0xff, 0xff, 0xff));
+
+        let mut rng = StepRng::new(0x0123_4567_89ab_cdef, 0x1111_1111_1111_1111);
+        let first = Mac::random_local(&mut rng);
+        assert_ne!(Mac::random_local(&mut rng), first);
+    }
 }
@@ -3,3 +3,164 @@
 pub mod mac;
 pub mod ipv4;
 pub mod ipv6;
+
+#[cfg(feature = "serde")]
+mod serde;
+
+pub use ipv4::{IPv4, Ipv4Net};
+pub use ipv6::{IPv6, Ipv6Net};
+pub use mac::Mac;
+
+use ipv4::IPv4AddressError;
+use ipv6::IPv6AddressError;
+
+/// Either an IPv4 or an IPv6 address, like `std::net::IpAddr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IpAddr {
+    V4(IPv4),
+    V6(IPv6),
+}
+
+impl IpAddr {
+    /// Query if the address is the unspecified address of its family.
+    pub fn is_unspecified(&self) -> bool {
+        match self {
+            IpAddr::V4(addr) => addr.is_unspecified(),
+            IpAddr::V6(addr) => ipv6::is_unspecified(addr),
+        }
+    }
+
+    /// Query if the address is a loopback address.
+    pub fn is_loopback(&self) -> bool {
+        match self {
+            IpAddr::V4(addr) => addr.is_loopback(),
+            IpAddr::V6(addr) => ipv6::is_loopback(addr),
+        }
+    }
+
+    /// Query if the address is a multicast address.
+    pub fn is_multicast(&self) -> bool {
+        match self {
+            IpAddr::V4(addr) => addr.is_multicast(),
+            IpAddr::V6(addr) => ipv6::is_multicast(addr),
+        }
+    }
+
+    /// Query if the address is IPv4.
+    pub fn is_ipv4(&self) -> bool {
+        matches!(self, IpAddr::V4(_))
+    }
+
+    /// Query if the address is IPv6.
+    pub fn is_ipv6(&self) -> bool {
+        matches!(self, IpAddr::V6(_))
+    }
+}
+
+impl From<IPv4> for IpAddr {
+    fn from(addr: IPv4) -> IpAddr {
+        IpAddr::V4(addr)
+    }
+}
+
+impl From<IPv6> for IpAddr {
+    fn from(addr: IPv6) -> IpAddr {
+        IpAddr::V6(addr)
+    }
+}
+
+impl core::fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            IpAddr::V4(addr) => write!(f, "{}", addr),
+            IpAddr::V6(addr) => write!(f, "{}", addr),
+        }
+    }
+}
+
+/// Error parsing an `IpAddr`, from the parser of the family the text
+/// looked like.
+#[derive(Debug, PartialEq)]
+pub enum IpAddrError {
+    V4(IPv4AddressError),
+    V6(IPv6AddressError),
+}
+
+impl core::fmt::Display for IpAddrError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            IpAddrError::V4(e) => write!(f, "{}", e),
+            IpAddrError::V6(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IpAddrError {}
+
+/// Parses IPv4 first, then IPv6. Text containing a `:` is reported with
+/// the IPv6 parser's error, anything else with the IPv4 parser's.
+impl core::str::FromStr for IpAddr {
+    type Err = IpAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match ipv4::from_string(s) {
+            Ok(addr) => Ok(IpAddr::V4(addr)),
+            Err(_) if s.contains(':') => ipv6::from_string(s).map(IpAddr::V6).map_err(IpAddrError::V6),
+            Err(e) => Err(IpAddrError::V4(e)),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash, used for hashes that must not vary between processes.
+///
+/// [FNV]: https://datatracker.ietf.org/doc/html/draft-eastlake-fnv
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_both_families() {
+        assert_eq!("192.168.1.1".parse::<IpAddr>(), Ok(IpAddr::V4(IPv4::new(192, 168, 1, 1))));
+        assert_eq!("::1".parse::<IpAddr>(), Ok(IpAddr::V6(ipv6::LOOPBACK)));
+        assert!("::ffff:10.0.0.1".parse::<IpAddr>().unwrap().is_ipv6());
+    }
+
+    #[test]
+    fn test_parse_errors_follow_family() {
+        assert_eq!("256.0.0.1".parse::<IpAddr>(), Err(IpAddrError::V4(IPv4AddressError::InvalidSegment)));
+        assert_eq!("2001::db8::1".parse::<IpAddr>(), Err(IpAddrError::V6(IPv6AddressError::InvalidFormat)));
+    }
+
+    #[test]
+    fn test_delegated_predicates() {
+        let v4: IpAddr = "0.0.0.0".parse().unwrap();
+        let v6: IpAddr = "::".parse().unwrap();
+        assert!(v4.is_unspecified() && v6.is_unspecified());
+
+        let v4: IpAddr = "127.0.0.1".parse().unwrap();
+        let v6: IpAddr = "::1".parse().unwrap();
+        assert!(v4.is_loopback() && v6.is_loopback());
+        assert!(!v4.is_multicast() && !v6.is_multicast());
+
+        let v4: IpAddr = "224.0.0.1".parse().unwrap();
+        let v6: IpAddr = "ff02::1".parse().unwrap();
+        assert!(v4.is_multicast() && v6.is_multicast());
+        assert!(!v4.is_loopback() && !v6.is_unspecified());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(IpAddr::from(IPv4::new(10, 0, 0, 1)).to_string(), "10.0.0.1");
+        assert_eq!(IpAddr::from(ipv6::LOOPBACK).to_string(), "::1");
+    }
+}
@@ -0,0 +1,247 @@
+// src/address/serde.rs
+
+//! Serde support for IP and MAC addresses, behind the `serde` feature.
+//!
+//! Human-readable formats such as JSON use the textual form
+//! (`"192.168.1.1"`, `"fe80::1"`, `"de:ad:be:ef:00:01"`). Compact formats
+//! such as bincode use the raw octets. Deserializing from a self-describing
+//! format accepts either; a malformed string is reported with the error of
+//! the address parser.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::ipv4::{self, IPv4};
+use super::ipv6::{self, IPv6};
+use super::mac::{self, Mac};
+
+// What the shared visitor needs to know about an address type
+trait Address: Sized + fmt::Display {
+    const LEN: usize;
+    const EXPECTING: &'static str;
+
+    type ParseError: fmt::Display;
+
+    fn octets(&self) -> &[u8];
+    fn parse(s: &str) -> Result<Self, Self::ParseError>;
+    fn from_octets(bytes: &[u8]) -> Option<Self>;
+}
+
+impl Address for IPv4 {
+    const LEN: usize = ipv4::ADDR_SIZE;
+    const EXPECTING: &'static str = "an IPv4 address string or 4 octets";
+    type ParseError = ipv4::IPv4AddressError;
+
+    fn octets(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn parse(s: &str) -> Result<Self, Self::ParseError> {
+        ipv4::from_string(s)
+    }
+
+    fn from_octets(bytes: &[u8]) -> Option<Self> {
+        ipv4::from_bytes(bytes).ok()
+    }
+}
+
+impl Address for IPv6 {
+    const LEN: usize = 16;
+    const EXPECTING: &'static str = "an IPv6 address string or 16 octets";
+    type ParseError = ipv6::IPv6AddressError;
+
+    fn octets(&self) -> &[u8] {
+        self.to_bytes()
+    }
+
+    fn parse(s: &str) -> Result<Self, Self::ParseError> {
+        ipv6::from_string(s)
+    }
+
+    fn from_octets(bytes: &[u8]) -> Option<Self> {
+        ipv6::from_bytes(bytes).ok()
+    }
+}
+
+impl Address for Mac {
+    const LEN: usize = 6;
+    const EXPECTING: &'static str = "a MAC address string or 6 octets";
+    type ParseError = mac::MacAddressParseError;
+
+    fn octets(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn parse(s: &str) -> Result<Self, Self::ParseError> {
+        mac::from_string(s)
+    }
+
+    fn from_octets(bytes: &[u8]) -> Option<Self> {
+        mac::from_bytes(bytes).ok()
+    }
+}
+
+fn serialize<A: Address, S: Serializer>(addr: &A, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(addr)
+    } else {
+        serializer.serialize_bytes(addr.octets())
+    }
+}
+
+fn deserialize<'de, A: Address, D: Deserializer<'de>>(deserializer: D) -> Result<A, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(AddressVisitor(PhantomData))
+    } else {
+        // Formats like bincode cannot describe themselves, so ask for bytes
+        deserializer.deserialize_bytes(AddressVisitor(PhantomData))
+    }
+}
+
+struct AddressVisitor<A>(PhantomData<A>);
+
+impl<'de, A: Address> Visitor<'de> for AddressVisitor<A> {
+    type Value = A;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(A::EXPECTING)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        A::parse(v).map_err(|e| E::custom(format_args!("invalid address {:?}: {}", v, e)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<A, E> {
+        A::from_octets(v).ok_or_else(|| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<A, S::Error> {
+        let mut bytes = Vec::with_capacity(A::LEN);
+        while let Some(byte) = seq.next_element::<u8>()? {
+            if bytes.len() == A::LEN {
+                return Err(de::Error::invalid_length(A::LEN + 1, &self));
+            }
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl Serialize for IPv4 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IPv4 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl Serialize for IPv6 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IPv6 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl Serialize for Mac {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mac {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let v4 = IPv4::new(192, 168, 1, 1);
+        let json = serde_json::to_string(&v4).unwrap();
+        assert_eq!(json, "\"192.168.1.1\"");
+        assert_eq!(serde_json::from_str::<IPv4>(&json).unwrap(), v4);
+
+        let v6 = ipv6::from_string("fe80::1").unwrap();
+        let json = serde_json::to_string(&v6).unwrap();
+        assert_eq!(json, "\"fe80::1\"");
+        assert_eq!(serde_json::from_str::<IPv6>(&json).unwrap(), v6);
+
+        let mac = Mac::new(0xde, 0xad, 0xbe, 0xef, 0x00, 0x01);
+        let json = serde_json::to_string(&mac).unwrap();
+        assert_eq!(json, "\"de:ad:be:ef:00:01\"");
+        assert_eq!(serde_json::from_str::<Mac>(&json).unwrap(), mac);
+    }
+
+    #[test]
+    fn test_json_round_trip_global_ipv6() {
+        let v6 = ipv6::from_string("2001:db8::1").unwrap();
+        let json = serde_json::to_string(&v6).unwrap();
+        assert_eq!(json, "\"2001:db8::1\"");
+        assert_eq!(serde_json::from_str::<IPv6>(&json).unwrap(), v6);
+    }
+
+    #[test]
+    fn test_json_reports_parser_error() {
+        let err = serde_json::from_str::<Mac>("\"de:ad:be\"").unwrap_err();
+        assert!(err.to_string().contains(&mac::MacAddressParseError::InvalidLength.to_string()), "{}", err);
+        assert!(serde_json::from_str::<Mac>("[1, 2, 3, 4, 5, 6]").is_ok());
+    }
+
+    #[test]
+    fn test_json_accepts_octets() {
+        assert_eq!(serde_json::from_str::<IPv4>("[10, 0, 0, 1]").unwrap(), IPv4::new(10, 0, 0, 1));
+        let v6 = serde_json::from_str::<IPv6>("[254,128,0,0,0,0,0,0,0,0,0,0,0,0,0,1]").unwrap();
+        assert_eq!(v6, ipv6::from_string("fe80::1").unwrap());
+    }
+
+    #[test]
+    fn test_json_rejects_invalid() {
+        assert!(serde_json::from_str::<IPv4>("\"10.0.0\"").is_err());
+        assert!(serde_json::from_str::<IPv4>("[10, 0, 0]").is_err());
+        assert!(serde_json::from_str::<IPv4>("[10, 0, 0, 1, 2]").is_err());
+        assert!(serde_json::from_str::<IPv6>("\"fe80::g\"").is_err());
+        assert!(serde_json::from_str::<IPv4>("true").is_err());
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let v4 = IPv4::new(192, 168, 1, 1);
+        let bytes = bincode::serialize(&v4).unwrap();
+        // Length prefix followed by the raw octets
+        assert_eq!(&bytes[8..], &[192, 168, 1, 1]);
+        assert_eq!(bincode::deserialize::<IPv4>(&bytes).unwrap(), v4);
+
+        let v6 = ipv6::from_string("2001:db8::1").unwrap();
+        let bytes = bincode::serialize(&v6).unwrap();
+        assert_eq!(bytes.len(), 8 + 16);
+        assert_eq!(bincode::deserialize::<IPv6>(&bytes).unwrap(), v6);
+
+        let mac = Mac::new(0xde, 0xad, 0xbe, 0xef, 0x00, 0x01);
+        let bytes = bincode::serialize(&mac).unwrap();
+        assert_eq!(&bytes[8..], &mac.0);
+        assert_eq!(bincode::deserialize::<Mac>(&bytes).unwrap(), mac);
+    }
+
+    #[test]
+    fn test_bincode_rejects_wrong_length() {
+        let bytes = bincode::serialize(&[1u8, 2, 3][..]).unwrap();
+        assert!(bincode::deserialize::<IPv4>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+// src/assemblers/arp.rs
+use crate::address::ipv4::IPv4;
+use crate::address::mac::Mac;
+use crate::parsers::arp::{Hardware, Operation};
+use crate::parsers::ethernet::ETHERTYPE_IPV4;
+
+/// Length of an Ethernet/IPv4 ARP packet
+pub const PACKET_LEN: usize = 28;
+
+/// Writer for an Ethernet/IPv4 ARP packet
+///
+/// The typed address setters use the fixed Ethernet/IPv4 offsets, so the
+/// buffer must be at least `PACKET_LEN` bytes.
+pub struct ArpPacket<'a> {
+    buffer: &'a mut [u8],
+}
+
+impl<'a> ArpPacket<'a> {
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        ArpPacket { buffer }
+    }
+
+    /// Set the hardware type
+    pub fn set_hardware_type(&mut self, hardware: Hardware) {
+        self.buffer[0..2].copy_from_slice(&(hardware as u16).to_be_bytes());
+    }
+
+    /// Set the protocol type
+    pub fn set_protocol_type(&mut self, protocol: u16) {
+        self.buffer[2..4].copy_from_slice(&protocol.to_be_bytes());
+    }
+
+    /// Set the hardware address length
+    pub fn set_hardware_address_length(&mut self, len: u8) {
+        self.buffer[4] = len;
+    }
+
+    /// Set the protocol address length
+    pub fn set_protocol_address_length(&mut self, len: u8) {
+        self.buffer[5] = len;
+    }
+
+    /// Set the operation
+    pub fn set_operation(&mut self, operation: Operation) {
+        self.buffer[6..8].copy_from_slice(&(operation as u16).to_be_bytes());
+    }
+
+    /// Set the sender MAC address
+    pub fn set_sender_mac(&mut self, mac: Mac) {
+        self.buffer[8..14].copy_from_slice(&mac.to_bytes());
+    }
+
+    /// Set the sender IPv4 address
+    pub fn set_sender_ip(&mut self, ip: IPv4) {
+        self.buffer[14..18].copy_from_slice(&ip.to_bytes());
+    }
+
+    /// Set the target MAC address
+    pub fn set_target_mac(&mut self, mac: Mac) {
+        self.buffer[18..24].copy_from_slice(&mac.to_bytes());
+    }
+
+    /// Set the target IPv4 address
+    pub fn set_target_ip(&mut self, ip: IPv4) {
+        self.buffer[24..28].copy_from_slice(&ip.to_bytes());
+    }
+
+    // Fill in the fixed Ethernet/IPv4 header fields
+    fn set_ethernet_ipv4(&mut self) {
+        self.set_hardware_type(Hardware::Ethernet);
+        self.set_protocol_type(ETHERTYPE_IPV4);
+        self.set_hardware_address_length(6);
+        self.set_protocol_address_length(4);
+    }
+}
+
+/// Build an Ethernet/IPv4 ARP request asking who has `target_ip`. The
+/// target MAC address is left zeroed.
+pub fn build_request(sender_mac: Mac, sender_ip: IPv4, target_ip: IPv4) -> [u8; PACKET_LEN] {
+    let mut buffer = [0u8; PACKET_LEN];
+    let mut packet = ArpPacket::new(&mut buffer);
+    packet.set_ethernet_ipv4();
+    packet.set_operation(Operation::Request);
+    packet.set_sender_mac(sender_mac);
+    packet.set_sender_ip(sender_ip);
+    packet.set_target_ip(target_ip);
+    buffer
+}
+
+/// Build an Ethernet/IPv4 ARP reply telling `target` that `sender_ip` is
+/// at `sender_mac`.
+pub fn build_reply(sender_mac: Mac, sender_ip: IPv4, target_mac: Mac, target_ip: IPv4) -> [u8; PACKET_LEN] {
+    let mut buffer = [0u8; PACKET_LEN];
+    let mut packet = ArpPacket::new(&mut buffer);
+    packet.set_ethernet_ipv4();
+    packet.set_operation(Operation::Reply);
+    packet.set_sender_mac(sender_mac);
+    packet.set_sender_ip(sender_ip);
+    packet.set_target_mac(target_mac);
+    packet.set_target_ip(target_ip);
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers;
+
+    #[test]
+    fn request_round_trips() {
+        let sender_mac = Mac::new(0xde, 0xad, 0xbe, 0xef, 0xde, 0xad);
+        let buffer = build_request(sender_mac, IPv4::new(192, 168, 1, 1), IPv4::new(192, 168, 1, 2));
+
+        let parsed = parsers::arp::ArpPacket::new_with_validation(&buffer).unwrap();
+        assert_eq!(parsed.hardware(), Ok(Hardware::Ethernet));
+        assert_eq!(parsed.protocol_type(), ETHERTYPE_IPV4);
+        assert_eq!(parsed.operation_type(), Ok(Operation::Request));
+        assert_eq!(parsed.sender_mac(), Ok(sender_mac));
+        assert_eq!(parsed.sender_ip(), Ok(IPv4::new(192, 168, 1, 1)));
+        assert_eq!(parsed.target_mac(), Ok(Mac([0; 6])));
+        assert_eq!(parsed.target_ip(), Ok(IPv4::new(192, 168, 1, 2)));
+    }
+
+    #[test]
+    fn reply_round_trips() {
+        let sender_mac = Mac::new(0x02, 0, 0, 0, 0, 0x02);
+        let target_mac = Mac::new(0x02, 0, 0, 0, 0, 0x01);
+        let buffer = build_reply(sender_mac, IPv4::new(10, 0, 0, 2), target_mac, IPv4::new(10, 0, 0, 1));
+
+        let parsed = parsers::arp::ArpPacket::new(&buffer);
+        assert_eq!(parsed.operation_type(), Ok(Operation::Reply));
+        assert_eq!(parsed.sender_mac(), Ok(sender_mac));
+        assert_eq!(parsed.target_mac(), Ok(target_mac));
+        assert_eq!(parsed.target_ip(), Ok(IPv4::new(10, 0, 0, 1)));
+    }
+}
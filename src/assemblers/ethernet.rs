@@ -1,13 +1,7 @@
 // src/assemblers/ethernet
+use bytes::BytesMut;
 use crate::address::mac::Mac;
-
-/// Represents the Ethernet frame Ethertype.
-#[derive(Debug, Clone, Copy)]
-pub enum EtherType {
-    Ipv4 = 0x0800,
-    Arp = 0x0806,
-    Ipv6 = 0x86DD,
-}
+pub use crate::parsers::ethernet::EtherType;
 
 
 pub struct EthernetFrame<'a> {
@@ -15,11 +9,24 @@ pub struct EthernetFrame<'a> {
 }
 
 impl<'a> EthernetFrame<'a> {
+    // Length of the Ethernet header
+    const HEADER_LEN: usize = 14; // 6 (Dest) + 6 (Source) + 2 (Ethertype)
+
     /// Creates a new `EthernetFrame` with a mutable reference to a buffer.
     pub fn new(buffer: &'a mut [u8]) -> Self {
         EthernetFrame { buffer }
     }
 
+    /// Creates a new `EthernetFrame` over a reusable `BytesMut`.
+    ///
+    /// Any previous contents are discarded and the buffer is zero-filled
+    /// to hold the header and `payload_len` bytes of payload.
+    pub fn from_bytes_mut(buffer: &'a mut BytesMut, payload_len: usize) -> Self {
+        buffer.clear();
+        buffer.resize(Self::HEADER_LEN + payload_len, 0);
+        EthernetFrame { buffer: &mut buffer[..] }
+    }
+
     /// Set the destination MAC address.
     pub fn set_destination(&mut self, value: Mac) {
         self.buffer[0..6].copy_from_slice(&value.to_bytes());
@@ -37,7 +44,7 @@ impl<'a> EthernetFrame<'a> {
 
     /// Get a mutable reference to the payload.
     pub fn mut_payload_ref(&mut self) -> &mut [u8] {
-        &mut self.buffer[14..]
+        &mut self.buffer[Self::HEADER_LEN..]
     }
 }
 
@@ -74,4 +81,39 @@ mod tests {
 
         assert_eq!(&buffer[..], &FRAME_BYTES[..]);
     }
+
+    #[test]
+    fn construct_ethernet_frame_into_bytes_mut() {
+        // Start from a dirty, oversized buffer to mimic reuse.
+        let mut buffer = BytesMut::from(&[0xffu8; 128][..]);
+        buffer.clear();
+
+        let mut frame = EthernetFrame::from_bytes_mut(&mut buffer, 4);
+        frame.set_destination(Mac::new(0x01, 0x02, 0x03, 0x04, 0x05, 0x06));
+        frame.set_source(Mac::new(0x11, 0x12, 0x13, 0x14, 0x15, 0x16));
+        frame.set_ethertype(EtherType::Ipv6);
+        frame.mut_payload_ref().copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        assert_eq!(buffer.len(), 18);
+        let parsed = crate::parsers::ethernet::EthernetFrame::new_with_validation(&buffer).unwrap();
+        assert_eq!(parsed.destination(), &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(parsed.source(), &[0x11, 0x12, 0x13, 0x14, 0x15, 0x16]);
+        assert_eq!(parsed.ethertype(), 0x86DD);
+        assert_eq!(parsed.payload(), &[0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn reuse_bytes_mut_leaves_no_stale_bytes() {
+        let mut buffer = BytesMut::new();
+        let mut frame = EthernetFrame::from_bytes_mut(&mut buffer, 4);
+        frame.set_destination(Mac::new(0xff, 0xff, 0xff, 0xff, 0xff, 0xff));
+        frame.set_source(Mac::new(0x11, 0x12, 0x13, 0x14, 0x15, 0x16));
+        frame.set_ethertype(EtherType::Ipv6);
+        frame.mut_payload_ref().copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        // The second build only sets the destination.
+        let mut frame = EthernetFrame::from_bytes_mut(&mut buffer, 2);
+        frame.set_destination(Mac::new(0x01, 0x02, 0x03, 0x04, 0x05, 0x06));
+        assert_eq!(&buffer[..], &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0][..]);
+    }
 }
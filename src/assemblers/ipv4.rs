@@ -0,0 +1,235 @@
+// src/assemblers/ipv4
+
+use bytes::BytesMut;
+use crate::address::ipv4::IPv4;
+use crate::parsers::ValidationError;
+use crate::utils::checksum;
+use crate::Config;
+
+pub struct IPv4Packet<'a> {
+    buffer: &'a mut [u8],
+}
+
+impl<'a> IPv4Packet<'a> {
+
+    // Length of the IPv4 header without options
+    const HEADER_LEN: usize = 20;
+
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        IPv4Packet { buffer }
+    }
+
+    /// Creates a new `IPv4Packet` with the TTL preset to the configured
+    /// default. `set_ttl` still overrides it.
+    pub fn with_config(buffer: &'a mut [u8], config: &Config) -> Self {
+        let mut packet = IPv4Packet { buffer };
+        packet.set_ttl(config.default_ttl);
+        packet
+    }
+
+    /// Creates a new `IPv4Packet` over a reusable `BytesMut`.
+    ///
+    /// Any previous contents are discarded and the buffer is zero-filled
+    /// to hold an option-less header and `payload_len` bytes of payload.
+    /// The version, IHL and total length are set, and the TTL is preset
+    /// to the configured default as in `with_config`.
+    pub fn from_bytes_mut(buffer: &'a mut BytesMut, payload_len: u16, config: &Config) -> Self {
+        buffer.clear();
+        buffer.resize(Self::HEADER_LEN + payload_len as usize, 0);
+        let mut packet = IPv4Packet { buffer: &mut buffer[..] };
+        packet.set_version(4);
+        packet.set_ihl(5);
+        packet.set_total_length(Self::HEADER_LEN as u16 + payload_len);
+        packet.set_ttl(config.default_ttl);
+        packet
+    }
+
+    /// Set the version
+    pub fn set_version(&mut self, version: u8) {
+        self.buffer[0] = (self.buffer[0] & 0x0F) | (version << 4);
+    }
+
+    /// Set the header length in 32-bit words
+    pub fn set_ihl(&mut self, ihl: u8) {
+        self.buffer[0] = (self.buffer[0] & 0xF0) | (ihl & 0x0F);
+    }
+
+    /// Set the Differentiated Services Code Point
+    pub fn set_dscp(&mut self, dscp: u8) {
+        self.buffer[1] = (self.buffer[1] & 0x03) | (dscp << 2);
+    }
+
+    /// Set the Explicit Congestion Notification
+    pub fn set_ecn(&mut self, ecn: u8) {
+        self.buffer[1] = (self.buffer[1] & 0xFC) | (ecn & 0x03);
+    }
+
+    /// Set the total length
+    pub fn set_total_length(&mut self, total_length: u16) {
+        self.buffer[2..4].copy_from_slice(&total_length.to_be_bytes());
+    }
+
+    /// Set the identification
+    pub fn set_identification(&mut self, identification: u16) {
+        self.buffer[4..6].copy_from_slice(&identification.to_be_bytes());
+    }
+
+    /// Set the 3 bit flags
+    pub fn set_flags(&mut self, flags: u8) {
+        self.buffer[6] = (self.buffer[6] & 0x1F) | (flags << 5);
+    }
+
+    /// Set the 13 bit fragment offset, in 8-byte units
+    pub fn set_fragment_offset(&mut self, offset: u16) {
+        self.buffer[6] = (self.buffer[6] & 0xE0) | ((offset >> 8) & 0x1F) as u8;
+        self.buffer[7] = (offset & 0xFF) as u8;
+    }
+
+    /// Set the time to live
+    pub fn set_ttl(&mut self, ttl: u8) {
+        self.buffer[8] = ttl;
+    }
+
+    /// Set the protocol
+    pub fn set_protocol(&mut self, protocol: u8) {
+        self.buffer[9] = protocol;
+    }
+
+    /// Set the source, rejecting multicast, broadcast and unspecified addresses
+    pub fn set_source(&mut self, source: IPv4) -> Result<(), ValidationError> {
+        if !source.is_valid_source() {
+            return Err(ValidationError::InvalidSourceAddress);
+        }
+        self.buffer[12..16].copy_from_slice(&source.to_bytes());
+        Ok(())
+    }
+
+    /// Set the destination, rejecting the unspecified address
+    pub fn set_destination(&mut self, destination: IPv4) -> Result<(), ValidationError> {
+        if !destination.is_valid_destination() {
+            return Err(ValidationError::InvalidDestinationAddress);
+        }
+        self.buffer[16..20].copy_from_slice(&destination.to_bytes());
+        Ok(())
+    }
+
+    /// Compute the header checksum and write it into the header.
+    ///
+    /// Call this last, after every other header field is set.
+    pub fn fill_checksum(&mut self) {
+        self.buffer[10..12].copy_from_slice(&[0, 0]);
+        let sum = checksum::checksum(&self.buffer[..self.header_len()], 0);
+        self.buffer[10..12].copy_from_slice(&sum.to_be_bytes());
+    }
+
+    /// Return a mutable reference to payload
+    pub fn mut_payload_ref(&mut self) -> &mut [u8] {
+        let total_length = u16::from_be_bytes([self.buffer[2], self.buffer[3]]) as usize;
+        let header_len = self.header_len();
+        &mut self.buffer[header_len..total_length]
+    }
+
+    // Header length in bytes from the IHL field
+    fn header_len(&self) -> usize {
+        (self.buffer[0] & 0x0F) as usize * 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers;
+
+    #[test]
+    fn construct_minimal_ipv4_header() {
+        let mut buffer = [0u8; 20];
+        let mut packet = IPv4Packet::new(&mut buffer);
+        packet.set_version(4);
+        packet.set_ihl(5);
+        packet.set_dscp(46);
+        packet.set_ecn(1);
+        packet.set_total_length(20);
+        packet.set_identification(0x1c46);
+        packet.set_flags(0b010); // Don't Fragment
+        packet.set_fragment_offset(0);
+        packet.set_ttl(64);
+        packet.set_protocol(17);
+        packet.set_source(IPv4::new(192, 168, 1, 1)).unwrap();
+        packet.set_destination(IPv4::new(10, 0, 0, 1)).unwrap();
+        packet.fill_checksum();
+
+        let parsed = parsers::ipv4::IPv4Packet::new_with_validation(&buffer).unwrap();
+        assert_eq!(parsed.version().unwrap(), 4);
+        assert_eq!(parsed.ihl().unwrap(), 20);
+        assert_eq!(parsed.total_length().unwrap(), 20);
+        assert_eq!(parsed.identification().unwrap(), 0x1c46);
+        assert_eq!(parsed.ttl().unwrap(), 64);
+        assert_eq!(parsed.protocol().unwrap(), 17);
+        assert_eq!(parsed.source().unwrap(), IPv4::new(192, 168, 1, 1));
+        assert_eq!(parsed.destination().unwrap(), IPv4::new(10, 0, 0, 1));
+        assert!(parsed.verify_checksum().unwrap());
+        assert_eq!(buffer[1], (46 << 2) | 1);
+        assert_eq!(buffer[6], 0x40);
+    }
+
+    #[test]
+    fn construct_ipv4_packet_into_bytes_mut() {
+        let mut config = Config::new();
+        config.default_ttl = 32;
+
+        let mut buffer = BytesMut::with_capacity(64);
+        let mut packet = IPv4Packet::from_bytes_mut(&mut buffer, 4, &config);
+        packet.set_fragment_offset(0x1abc);
+        packet.mut_payload_ref().copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        packet.fill_checksum();
+
+        let parsed = parsers::ipv4::IPv4Packet::new_with_validation(&buffer).unwrap();
+        assert_eq!(parsed.ttl().unwrap(), 32);
+        assert_eq!(parsed.payload().unwrap(), &[0xde, 0xad, 0xbe, 0xef]);
+        assert!(parsed.verify_checksum().unwrap());
+        assert_eq!(&buffer[6..8], &[0x1a, 0xbc]);
+    }
+
+    #[test]
+    fn reuse_bytes_mut_leaves_no_stale_bytes() {
+        let config = Config::new();
+        let mut buffer = BytesMut::new();
+        let mut packet = IPv4Packet::from_bytes_mut(&mut buffer, 4, &config);
+        packet.set_identification(0x1c46);
+        packet.set_ttl(64);
+        packet.set_protocol(17);
+        packet.set_source(IPv4::new(192, 168, 1, 1)).unwrap();
+        packet.fill_checksum();
+
+        // The second build sets none of those fields.
+        IPv4Packet::from_bytes_mut(&mut buffer, 4, &config);
+        let parsed = parsers::ipv4::IPv4Packet::new(&buffer);
+        assert_eq!(parsed.identification().unwrap(), 0);
+        assert_eq!(parsed.protocol().unwrap(), 0);
+        assert_eq!(parsed.source().unwrap(), IPv4::new(0, 0, 0, 0));
+        assert_eq!(&buffer[10..12], &[0, 0]);
+    }
+
+    #[test]
+    fn construct_ipv4_packet_with_config_ttl() {
+        let mut config = Config::new();
+        config.default_ttl = 128;
+        let mut buffer = [0u8; 20];
+        IPv4Packet::with_config(&mut buffer, &config);
+        assert_eq!(parsers::ipv4::IPv4Packet::new(&buffer).ttl().unwrap(), 128);
+    }
+
+    #[test]
+    fn reject_invalid_addresses() {
+        let mut buffer = [0u8; 20];
+        let mut packet = IPv4Packet::new(&mut buffer);
+        assert_eq!(
+            packet.set_source(IPv4::new(224, 0, 0, 1)),
+            Err(ValidationError::InvalidSourceAddress)
+        );
+        assert_eq!(
+            packet.set_destination(IPv4::new(0, 0, 0, 0)),
+            Err(ValidationError::InvalidDestinationAddress)
+        );
+    }
+}
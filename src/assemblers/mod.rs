@@ -1,3 +1,23 @@
 // src/assemblers/mod.rs
+//! Writers that build packet headers in place over mutable buffers.
+//!
+//! ```
+//! use thunda::address::mac::Mac;
+//! use thunda::assemblers::ethernet::{EthernetFrame, EtherType};
+//! use thunda::parsers;
+//!
+//! let mut buffer = [0u8; 14];
+//! let mut frame = EthernetFrame::new(&mut buffer);
+//! frame.set_destination(Mac::new(0x01, 0x02, 0x03, 0x04, 0x05, 0x06));
+//! frame.set_source(Mac::new(0x11, 0x12, 0x13, 0x14, 0x15, 0x16));
+//! frame.set_ethertype(EtherType::Ipv4);
+//!
+//! let parsed = parsers::ethernet::EthernetFrame::new(&buffer);
+//! assert_eq!(parsed.ethertype_parsed(), Some(EtherType::Ipv4));
+//! ```
+pub mod arp;
 pub mod ethernet;
+pub mod ipv4;
 pub mod ipv6;
+pub mod tcp;
+pub mod udp;
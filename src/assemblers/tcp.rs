@@ -0,0 +1,151 @@
+// src/assemblers/tcp
+
+use crate::address::ipv4::IPv4;
+use crate::parsers::ipv4::pseudo_header_checksum;
+use crate::parsers::tcp::{FLAG_CWR, FLAG_ECE, PROTOCOL_TCP};
+use crate::utils::checksum;
+
+pub struct TcpSegment<'a> {
+    buffer: &'a mut [u8],
+}
+
+impl<'a> TcpSegment<'a> {
+
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        TcpSegment { buffer }
+    }
+
+    /// Set the source port
+    pub fn set_source_port(&mut self, port: u16) {
+        self.buffer[0..2].copy_from_slice(&port.to_be_bytes());
+    }
+
+    /// Set the destination port
+    pub fn set_destination_port(&mut self, port: u16) {
+        self.buffer[2..4].copy_from_slice(&port.to_be_bytes());
+    }
+
+    /// Set the sequence number
+    pub fn set_seq_number(&mut self, seq: u32) {
+        self.buffer[4..8].copy_from_slice(&seq.to_be_bytes());
+    }
+
+    /// Set the acknowledgment number
+    pub fn set_ack_number(&mut self, ack: u32) {
+        self.buffer[8..12].copy_from_slice(&ack.to_be_bytes());
+    }
+
+    /// Set the header length in bytes; must be a multiple of 4
+    pub fn set_header_length(&mut self, len: u8) {
+        self.buffer[12] = (self.buffer[12] & 0x0F) | ((len / 4) << 4);
+    }
+
+    /// Set the control flags
+    pub fn set_flags(&mut self, flags: u8) {
+        self.buffer[13] = flags;
+    }
+
+    /// Set or clear the ECN-Echo flag
+    pub fn set_ece(&mut self, ece: bool) {
+        self.set_flag(FLAG_ECE, ece);
+    }
+
+    /// Set or clear the Congestion Window Reduced flag
+    pub fn set_cwr(&mut self, cwr: bool) {
+        self.set_flag(FLAG_CWR, cwr);
+    }
+
+    fn set_flag(&mut self, flag: u8, on: bool) {
+        if on {
+            self.buffer[13] |= flag;
+        } else {
+            self.buffer[13] &= !flag;
+        }
+    }
+
+    /// Set the window
+    pub fn set_window(&mut self, window: u16) {
+        self.buffer[14..16].copy_from_slice(&window.to_be_bytes());
+    }
+
+    /// Set the urgent pointer
+    pub fn set_urgent_pointer(&mut self, urgent: u16) {
+        self.buffer[18..20].copy_from_slice(&urgent.to_be_bytes());
+    }
+
+    /// Compute the checksum over the IPv4 pseudo-header and the whole
+    /// segment, and write it into the header.
+    ///
+    /// Call this last, after the header and payload are in place.
+    pub fn fill_checksum(&mut self, source: IPv4, destination: IPv4) {
+        self.buffer[16..18].copy_from_slice(&[0, 0]);
+        let pseudo_header = pseudo_header_checksum(&source, &destination, PROTOCOL_TCP, self.buffer.len() as u16);
+        let sum = checksum::checksum(self.buffer, pseudo_header);
+        self.buffer[16..18].copy_from_slice(&sum.to_be_bytes());
+    }
+
+    /// Return a mutable reference to the payload
+    pub fn mut_payload_ref(&mut self) -> &mut [u8] {
+        let header_len = (self.buffer[12] >> 4) as usize * 4;
+        &mut self.buffer[header_len..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers;
+
+    #[test]
+    fn construct_tcp_segment() {
+        let mut buffer = [0u8; 24];
+        let mut segment = TcpSegment::new(&mut buffer);
+        segment.set_source_port(40000);
+        segment.set_destination_port(80);
+        segment.set_seq_number(1000);
+        segment.set_ack_number(2000);
+        segment.set_header_length(20);
+        segment.set_flags(parsers::tcp::FLAG_ACK | parsers::tcp::FLAG_PSH);
+        segment.set_window(512);
+        segment.mut_payload_ref().copy_from_slice(b"data");
+
+        let parsed = parsers::tcp::TcpSegment::new_with_validation(&buffer).unwrap();
+        assert_eq!(parsed.source_port(), 40000);
+        assert_eq!(parsed.destination_port(), 80);
+        assert_eq!(parsed.seq_number(), 1000);
+        assert_eq!(parsed.ack_number(), 2000);
+        assert!(parsed.ack() && parsed.psh());
+        assert_eq!(parsed.window(), 512);
+        assert_eq!(parsed.payload(), b"data");
+    }
+
+    #[test]
+    fn set_ecn_flags() {
+        let mut buffer = [0u8; 20];
+        let mut segment = TcpSegment::new(&mut buffer);
+        segment.set_header_length(20);
+        segment.set_flags(parsers::tcp::FLAG_ACK);
+        segment.set_ece(true);
+        segment.set_cwr(true);
+        segment.set_ece(false);
+
+        let parsed = parsers::tcp::TcpSegment::new(&buffer);
+        assert!(parsed.ack() && parsed.cwr() && !parsed.ece());
+    }
+
+    #[test]
+    fn fill_checksum_matches_known_value() {
+        // Header-only ACK 10.0.0.1:1234 -> 10.0.0.2:80
+        let mut buffer = [0u8; 20];
+        let mut segment = TcpSegment::new(&mut buffer);
+        segment.set_source_port(1234);
+        segment.set_destination_port(80);
+        segment.set_seq_number(1);
+        segment.set_ack_number(1);
+        segment.set_header_length(20);
+        segment.set_flags(parsers::tcp::FLAG_ACK);
+        segment.set_window(1024);
+        segment.fill_checksum(IPv4::new(10, 0, 0, 1), IPv4::new(10, 0, 0, 2));
+        assert_eq!(&buffer[16..18], &[0x92, 0xae]);
+    }
+}
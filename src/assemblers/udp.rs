@@ -0,0 +1,80 @@
+// src/assemblers/udp
+
+use crate::parsers::PseudoHeader;
+use crate::parsers::udp::PROTOCOL_UDP;
+use crate::utils::checksum;
+
+pub struct UdpDatagram<'a> {
+    buffer: &'a mut [u8],
+}
+
+impl<'a> UdpDatagram<'a> {
+    // Source Port, Destination Port, Length and Checksum
+    const HEADER_LEN: usize = 8;
+
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        UdpDatagram { buffer }
+    }
+
+    /// Set the source port
+    pub fn set_source_port(&mut self, port: u16) {
+        self.buffer[0..2].copy_from_slice(&port.to_be_bytes());
+    }
+
+    /// Set the destination port
+    pub fn set_destination_port(&mut self, port: u16) {
+        self.buffer[2..4].copy_from_slice(&port.to_be_bytes());
+    }
+
+    /// Set the length of header and data
+    pub fn set_length(&mut self, length: u16) {
+        self.buffer[4..6].copy_from_slice(&length.to_be_bytes());
+    }
+
+    /// Compute the checksum over the pseudo-header and the whole datagram,
+    /// and write it into the header.
+    ///
+    /// A computed checksum of zero is sent as all ones, since zero means
+    /// "no checksum". Call this last, after the header and payload are in
+    /// place.
+    pub fn fill_checksum<A: PseudoHeader>(&mut self, source: &A, destination: &A) {
+        self.buffer[6..8].copy_from_slice(&[0, 0]);
+        let pseudo_header = A::pseudo_header_checksum(source, destination, PROTOCOL_UDP, self.buffer.len() as u32);
+        let sum = match checksum::checksum(self.buffer, pseudo_header) {
+            0 => 0xFFFF,
+            sum => sum,
+        };
+        self.buffer[6..8].copy_from_slice(&sum.to_be_bytes());
+    }
+
+    /// Return a mutable reference to the payload
+    pub fn mut_payload_ref(&mut self) -> &mut [u8] {
+        &mut self.buffer[Self::HEADER_LEN..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::ipv4::IPv4;
+    use crate::parsers;
+
+    #[test]
+    fn construct_udp_datagram() {
+        let (source, destination) = (IPv4::new(192, 168, 1, 10), IPv4::new(8, 8, 8, 8));
+        let mut buffer = [0u8; 12];
+        let mut datagram = UdpDatagram::new(&mut buffer);
+        datagram.set_source_port(53000);
+        datagram.set_destination_port(53);
+        datagram.set_length(12);
+        datagram.mut_payload_ref().copy_from_slice(b"ping");
+        datagram.fill_checksum(&source, &destination);
+
+        let parsed = parsers::udp::UdpDatagram::new_with_validation(&buffer).unwrap();
+        assert_eq!(parsed.source_port(), 53000);
+        assert_eq!(parsed.dest_port(), 53);
+        assert_eq!(parsed.payload(), b"ping");
+        assert_ne!(parsed.checksum(), 0);
+        assert!(parsed.verify_checksum(&source, &destination));
+    }
+}
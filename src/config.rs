@@ -1,12 +1,54 @@
 // src/config.rs
+use std::fmt;
+use std::str::FromStr;
+
+use log::LevelFilter;
+
+use crate::address::mac::{self, Mac};
+
+// Smallest frame that can hold an Ethernet header
+const MIN_FRAME_LEN: usize = 14;
+
+// Smallest MTU every IPv4 link must support (RFC 791)
+const MIN_MTU: usize = 68;
+
+// Locally administered default address
+const DEFAULT_MAC: Mac = Mac::new(0x02, 0x00, 0x00, 0x77, 0x77, 0x77);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    InvalidLogLevel(String),
+    InvalidMacAddress(String),
+    InvalidFrameLength(usize),
+    InvalidMtu(usize),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::InvalidLogLevel(level) => write!(f, "Invalid log level: {}", level),
+            ConfigError::InvalidMacAddress(addr) => write!(f, "Invalid MAC address: {}", addr),
+            ConfigError::InvalidFrameLength(len) => write!(f, "Invalid maximum frame length: {}", len),
+            ConfigError::InvalidMtu(mtu) => write!(f, "Invalid MTU: {}", mtu),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub log_level: String,
     pub frame_rx_max_len: usize,
-    pub mac_address: String,
+    /// Largest payload, excluding the Ethernet header, of a transmitted frame.
+    pub mtu: usize,
+    pub mac_address: Mac,
     pub ipv6_support: bool,
     pub ipv4_support: bool,
+    /// TTL used by the IPv4 assembler when none is set explicitly.
+    pub default_ttl: u8,
+    /// Hop limit used by the IPv6 assembler when none is set explicitly.
+    pub default_hop_limit: u8,
 }
 
 impl Config {
@@ -14,13 +56,216 @@ impl Config {
         Config {
             log_level: "info".to_string(),
             frame_rx_max_len: 2048,
+            mtu: 1500,
             ipv6_support: true,
             ipv4_support: true,
-            mac_address: "02:00:00:77:77:77".to_string(),
+            mac_address: DEFAULT_MAC,
+            default_ttl: 64,
+            default_hop_limit: 64,
         }
     }
 
-    pub fn apply(&self) {
-        // Implementation to apply the config
+    /// Start building a config from the defaults.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Check that every field holds a usable value.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.level_filter()?;
+        if self.frame_rx_max_len < MIN_FRAME_LEN {
+            return Err(ConfigError::InvalidFrameLength(self.frame_rx_max_len));
+        }
+        if self.mtu < MIN_MTU {
+            return Err(ConfigError::InvalidMtu(self.mtu));
+        }
+        Ok(())
+    }
+
+    /// Validate the config and apply its process-wide settings, currently
+    /// the log level. With the `log` feature this also installs a stderr
+    /// logger unless the application has installed its own. Nothing is
+    /// changed if validation fails.
+    pub fn apply(&self) -> Result<(), ConfigError> {
+        self.validate()?;
+        let level = self.level_filter()?;
+        #[cfg(feature = "log")]
+        crate::utils::logger::init(level);
+        #[cfg(not(feature = "log"))]
+        log::set_max_level(level);
+        Ok(())
+    }
+
+    /// Parse `log_level` ("off", "error", "warn", "info", "debug" or
+    /// "trace", in any case).
+    pub fn level_filter(&self) -> Result<LevelFilter, ConfigError> {
+        LevelFilter::from_str(&self.log_level).map_err(|_| ConfigError::InvalidLogLevel(self.log_level.clone()))
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds a `Config`, validating every field in `build`.
+///
+/// ```
+/// use thunda::Config;
+///
+/// let config = Config::builder()
+///     .log_level("debug")
+///     .mac_address("02:00:00:00:00:01")
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.mac_address.to_string(), "02:00:00:00:00:01");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+    // Parsed in `build` so that a bad address is reported there
+    mac_address: Option<String>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self { config: Config::new(), mac_address: None }
+    }
+
+    pub fn log_level(mut self, level: &str) -> Self {
+        self.config.log_level = level.to_string();
+        self
+    }
+
+    pub fn frame_rx_max_len(mut self, len: usize) -> Self {
+        self.config.frame_rx_max_len = len;
+        self
+    }
+
+    pub fn mtu(mut self, mtu: usize) -> Self {
+        self.config.mtu = mtu;
+        self
+    }
+
+    /// Set the MAC address in colon-separated hex notation.
+    pub fn mac_address(mut self, address: &str) -> Self {
+        self.mac_address = Some(address.to_string());
+        self
+    }
+
+    pub fn ipv4_support(mut self, enabled: bool) -> Self {
+        self.config.ipv4_support = enabled;
+        self
+    }
+
+    pub fn ipv6_support(mut self, enabled: bool) -> Self {
+        self.config.ipv6_support = enabled;
+        self
+    }
+
+    pub fn default_ttl(mut self, ttl: u8) -> Self {
+        self.config.default_ttl = ttl;
+        self
+    }
+
+    pub fn default_hop_limit(mut self, hop_limit: u8) -> Self {
+        self.config.default_hop_limit = hop_limit;
+        self
+    }
+
+    /// Parse and validate the fields, returning the first invalid one.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let mut config = self.config;
+        if let Some(address) = self.mac_address {
+            config.mac_address = mac::from_string(&address)
+                .map_err(|_| ConfigError::InvalidMacAddress(address))?;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_valid() {
+        assert_eq!(Config::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let mut config = Config::new();
+        config.log_level = "loud".to_string();
+        assert_eq!(config.validate(), Err(ConfigError::InvalidLogLevel("loud".to_string())));
+
+        let mut config = Config::new();
+        config.frame_rx_max_len = 10;
+        assert_eq!(config.validate(), Err(ConfigError::InvalidFrameLength(10)));
+    }
+
+    #[test]
+    fn test_builder() {
+        let config = Config::builder()
+            .frame_rx_max_len(9000)
+            .mac_address("02:00:00:00:00:01")
+            .ipv6_support(false)
+            .default_ttl(32)
+            .build()
+            .unwrap();
+        assert_eq!(config.frame_rx_max_len, 9000);
+        assert_eq!(config.mac_address, Mac::new(0x02, 0, 0, 0, 0, 0x01));
+        assert!(!config.ipv6_support && config.ipv4_support);
+        assert_eq!(config.default_ttl, 32);
+        assert_eq!(Config::builder().build().unwrap().mac_address, DEFAULT_MAC);
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_mac() {
+        let result = Config::builder().mac_address("02:00:00").build();
+        assert_eq!(result.err(), Some(ConfigError::InvalidMacAddress("02:00:00".to_string())));
+    }
+
+    #[test]
+    fn test_builder_rejects_small_frame_length() {
+        let result = Config::builder().frame_rx_max_len(1).build();
+        assert_eq!(result.err(), Some(ConfigError::InvalidFrameLength(1)));
+    }
+
+    #[test]
+    fn test_builder_rejects_small_mtu() {
+        let result = Config::builder().mtu(40).build();
+        assert_eq!(result.err(), Some(ConfigError::InvalidMtu(40)));
+        assert_eq!(Config::builder().mtu(9000).build().unwrap().mtu, 9000);
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_log_level() {
+        let result = Config::builder().log_level("chatty").build();
+        assert_eq!(result.err(), Some(ConfigError::InvalidLogLevel("chatty".to_string())));
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let mut config = Config::new();
+        config.log_level = "debug".to_string();
+        assert_eq!(config.level_filter(), Ok(LevelFilter::Debug));
+        config.log_level = "TRACE".to_string();
+        assert_eq!(config.level_filter(), Ok(LevelFilter::Trace));
+    }
+
+    #[test]
+    fn test_apply_rejects_unknown_level() {
+        let mut config = Config::new();
+        config.log_level = "chatty".to_string();
+        assert_eq!(config.apply(), Err(ConfigError::InvalidLogLevel("chatty".to_string())));
     }
 }
@@ -1,2 +1,4 @@
 pub mod tap;
+#[cfg(target_os = "linux")]
+pub mod raw;
 pub mod af_xdp;
@@ -0,0 +1,218 @@
+//! # Raw Socket Module
+//!
+//! This module provides `RawSocket`, a `NicInterface` that exchanges full Ethernet
+//! frames with a physical (or any other) Linux interface through an `AF_PACKET`
+//! socket, so Thunda can run without a TAP device.
+//!
+//! Opening the socket needs `CAP_NET_RAW`; without it `RawSocket::open` returns
+//! a `PermissionDenied` error.
+
+use std::ffi::CString;
+use std::future::Future;
+use std::io::{self, Result as IoResult};
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::io::unix::AsyncFd;
+
+use crate::io::nic_interface::NicInterface;
+use crate::iface::tap::validate_interface_name;
+use crate::Config;
+
+/// Protocol number matching every Ethertype, from <linux/if_ether.h>
+pub const ETH_P_ALL: u16 = 0x0003;
+
+/// Which frames the socket receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Every frame on the interface
+    All,
+    /// Only frames carrying this Ethertype
+    Ethertype(u16),
+}
+
+impl Protocol {
+    // Protocol number in network byte order, as socket() and bind() expect
+    fn to_network(self) -> u16 {
+        match self {
+            Protocol::All => ETH_P_ALL,
+            Protocol::Ethertype(ethertype) => ethertype,
+        }
+        .to_be()
+    }
+}
+
+/// An `AF_PACKET`/`SOCK_RAW` socket bound to a single interface
+pub struct RawSocket {
+    fd: Arc<AsyncFd<OwnedFd>>,
+    // Kernel index of the bound interface
+    ifindex: i32,
+    // Size of the buffer each read fills
+    frame_rx_max_len: usize,
+}
+
+impl RawSocket {
+    /// Open a non-blocking raw socket on the interface `name`, receiving
+    /// the frames selected by `protocol`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn open(name: &str, protocol: Protocol) -> IoResult<Self> {
+        validate_interface_name(name)?;
+        if name.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Interface name is required"));
+        }
+        let fd = packet_socket(protocol)?;
+        let ifindex = interface_index(name)?;
+        bind(&fd, ifindex, protocol)?;
+        Ok(Self {
+            fd: Arc::new(AsyncFd::new(fd)?),
+            ifindex,
+            frame_rx_max_len: Config::new().frame_rx_max_len,
+        })
+    }
+
+    /// Return the kernel index of the bound interface.
+    pub fn ifindex(&self) -> i32 {
+        self.ifindex
+    }
+}
+
+// socket(AF_PACKET, SOCK_RAW) in non-blocking mode
+#[allow(unsafe_code)]
+fn packet_socket(protocol: Protocol) -> IoResult<OwnedFd> {
+    let flags = libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC;
+    let fd = unsafe { libc::socket(libc::AF_PACKET, flags, protocol.to_network() as libc::c_int) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fd` is a freshly created descriptor owned by nothing else
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+#[allow(unsafe_code)]
+fn interface_index(name: &str) -> IoResult<i32> {
+    // validate_interface_name has already rejected interior NULs
+    let name = CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+    if index == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(index as i32)
+}
+
+#[allow(unsafe_code)]
+fn bind(fd: &OwnedFd, ifindex: i32, protocol: Protocol) -> IoResult<()> {
+    // SAFETY: sockaddr_ll is plain old data, valid when zeroed
+    let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+    addr.sll_family = libc::AF_PACKET as libc::c_ushort;
+    addr.sll_protocol = protocol.to_network();
+    addr.sll_ifindex = ifindex;
+    let ret = unsafe {
+        libc::bind(
+            fd.as_raw_fd(),
+            &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+        )
+    };
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[allow(unsafe_code)]
+fn recv(fd: &OwnedFd, buf: &mut [u8]) -> IoResult<usize> {
+    let n = unsafe { libc::recv(fd.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+    if n == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(n as usize)
+}
+
+#[allow(unsafe_code)]
+fn send(fd: &OwnedFd, buf: &[u8]) -> IoResult<usize> {
+    let n = unsafe { libc::send(fd.as_raw_fd(), buf.as_ptr() as *const libc::c_void, buf.len(), 0) };
+    if n == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(n as usize)
+}
+
+impl NicInterface for RawSocket {
+    /// Wait for the next frame on the interface.
+    fn read_packet(&self) -> Pin<Box<dyn Future<Output = IoResult<Vec<u8>>> + Send>> {
+        let fd = self.fd.clone();
+        let frame_rx_max_len = self.frame_rx_max_len;
+
+        Box::pin(async move {
+            let mut buf = vec![0u8; frame_rx_max_len];
+            loop {
+                let mut guard = fd.readable().await?;
+                if let Ok(result) = guard.try_io(|inner| recv(inner.get_ref(), &mut buf)) {
+                    let n = result?;
+                    buf.truncate(n);
+                    return Ok(buf);
+                }
+            }
+        })
+    }
+
+    /// Send `data` as a single frame. The frame must include its Ethernet
+    /// header.
+    fn write_packet(&self, data: Vec<u8>) -> Pin<Box<dyn Future<Output = IoResult<()>> + Send>> {
+        let fd = self.fd.clone();
+
+        Box::pin(async move {
+            loop {
+                let mut guard = fd.writable().await?;
+                if let Ok(result) = guard.try_io(|inner| send(inner.get_ref(), &data)) {
+                    let n = result?;
+                    if n != data.len() {
+                        return Err(io::Error::new(io::ErrorKind::WriteZero, "Frame was truncated"));
+                    }
+                    return Ok(());
+                }
+            }
+        })
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.frame_rx_max_len = config.frame_rx_max_len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_byte_order() {
+        assert_eq!(Protocol::All.to_network(), ETH_P_ALL.to_be());
+        assert_eq!(Protocol::Ethertype(0x0806).to_network(), 0x0806u16.to_be());
+    }
+
+    #[actix_rt::test]
+    async fn test_rejects_bad_names() {
+        for name in ["", "way-too-long-interface", "eth/0"] {
+            let err = RawSocket::open(name, Protocol::All).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{:?}", name);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_open_loopback() {
+        // Needs CAP_NET_RAW; without it the open must fail cleanly
+        match RawSocket::open("lo", Protocol::All) {
+            Ok(socket) => assert!(socket.ifindex() > 0),
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::PermissionDenied, "{}", e),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_open_missing_interface() {
+        match RawSocket::open("thunda-none0", Protocol::Ethertype(0x0800)) {
+            Ok(_) => panic!("Interface should not exist"),
+            Err(e) => assert_ne!(e.kind(), io::ErrorKind::InvalidInput, "{}", e),
+        }
+    }
+}
@@ -7,42 +7,121 @@
 //! ## Features
 //!
 //! - Asynchronously open a TAP device with configurable read/write permissions.
+//! - Open the device in TAP (Ethernet) or TUN (IP-only) mode under a chosen interface name.
 //! - Perform non-blocking reads and writes to the TAP device.
+//! - Read several frames in one message with `ReadBatchMessage`.
 //! - Act as an implementation of the `NicInterface`, allowing for integration into the broader
 //!   network stack of Thunda.
 
 use actix::prelude::*;
-use std::sync::{Arc, Mutex};
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use std::io::{self, Error};
-use std::os::unix::io::AsRawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd};
 use tokio::fs::OpenOptions as TokioOpenOptions;
 use futures::Future;
 use std::pin::Pin;
 use std::io::Result as IoResult;
 use crate::io::nic_interface::NicInterface;
+use crate::io::network_io::Reconfigure;
+use crate::{Config, ConfigError};
+
+// Constants from <linux/if_tun.h> and <net/if.h>
+const TUNSETIFF: libc::c_ulong = 0x400454ca;
+const IFF_TUN: libc::c_short = 0x0001;
+const IFF_TAP: libc::c_short = 0x0002;
+const IFF_NO_PI: libc::c_short = 0x1000;
+const IFNAMSIZ: usize = 16;
+
+/// Layer at which a device exchanges packets with the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceMode {
+    /// Ethernet frames (layer 2)
+    Tap,
+    /// IP packets without a link-layer header (layer 3)
+    Tun,
+}
+
+impl DeviceMode {
+    // Flags for TUNSETIFF. IFF_NO_PI drops the 4-byte packet information
+    // prefix, so reads and writes carry just the frame or packet.
+    fn flags(self) -> libc::c_short {
+        match self {
+            DeviceMode::Tap => IFF_TAP | IFF_NO_PI,
+            DeviceMode::Tun => IFF_TUN | IFF_NO_PI,
+        }
+    }
+}
+
+/// Check that `name` can be requested as a network interface name: at
+/// most `IFNAMSIZ - 1` (15) bytes, and free of the characters the kernel
+/// refuses (NUL, `/`, `:` and whitespace). An empty name is accepted and
+/// lets the kernel choose one.
+pub fn validate_interface_name(name: &str) -> io::Result<()> {
+    let invalid = |reason: &str| {
+        Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid interface name {:?}: {}", name, reason)))
+    };
+    if name.len() > IFNAMSIZ - 1 {
+        return invalid("longer than 15 bytes");
+    }
+    if name == "." || name == ".." {
+        return invalid("reserved name");
+    }
+    if name.chars().any(|c| c == '\0' || c == '/' || c == ':' || c.is_whitespace()) {
+        return invalid("contains a forbidden character");
+    }
+    Ok(())
+}
+
+// The leading fields of `struct ifreq` used by TUNSETIFF, padded to its full size
+#[repr(C)]
+struct IfReq {
+    name: [u8; IFNAMSIZ],
+    flags: libc::c_short,
+    _pad: [u8; 22],
+}
+
+impl IfReq {
+    // An empty name lets the kernel pick one (tap0, tun0, ...)
+    fn new(name: &str, mode: DeviceMode) -> io::Result<Self> {
+        validate_interface_name(name)?;
+        let bytes = name.as_bytes();
+        let mut req = IfReq { name: [0; IFNAMSIZ], flags: mode.flags(), _pad: [0; 22] };
+        req.name[..bytes.len()].copy_from_slice(bytes);
+        Ok(req)
+    }
+}
 
 /// Options for opening a TAP device.
 struct OpenOptions {
     read: bool,
     write: bool,
+    mode: DeviceMode,
+    name: String,
 }
 
 impl OpenOptions {
-    fn new() -> Self {
-        Self { read: true, write: true }
+    fn new(mode: DeviceMode, name: &str) -> Self {
+        Self { read: true, write: true, mode, name: name.to_string() }
     }
 
-    /// Opens a TAP device with the specified options asynchronously.
+    /// Opens a TAP or TUN device with the specified options asynchronously
+    /// and attaches it to the requested interface name.
+    ///
+    /// The name is validated before any system call is made.
     #[allow(unsafe_code)]
     async fn open(&self) -> io::Result<File> {
+        let mut req = IfReq::new(&self.name, self.mode)?;
         let path = "/dev/net/tun";
         let file = TokioOpenOptions::new()
             .read(self.read)
             .write(self.write)
             .open(path)
             .await?;
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), TUNSETIFF as _, &mut req as *mut IfReq) };
+        if result == -1 {
+            return Err(io::Error::last_os_error());
+        }
         Ok(file)
     }
 }
@@ -52,13 +131,45 @@ pub trait DeviceOpener {
     fn open(&self) -> Pin<Box<dyn Future<Output = io::Result<File>> + Send>>;
 }
 
-struct Device;
+/// Opens `/dev/net/tun` as a named TAP or TUN interface.
+pub struct Device {
+    name: String,
+    mode: DeviceMode,
+}
+
+impl Device {
+    /// A device in `mode` attached to the interface `name`. An empty name
+    /// lets the kernel choose one.
+    pub fn new(name: &str, mode: DeviceMode) -> Self {
+        Self { name: name.to_string(), mode }
+    }
+
+    /// A TAP (Ethernet) device.
+    pub fn tap(name: &str) -> Self {
+        Self::new(name, DeviceMode::Tap)
+    }
+
+    /// A TUN (IP) device.
+    pub fn tun(name: &str) -> Self {
+        Self::new(name, DeviceMode::Tun)
+    }
+
+    pub fn mode(&self) -> DeviceMode {
+        self.mode
+    }
+
+    /// Return the requested interface name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
 
 impl DeviceOpener for Device {
     #[allow(unsafe_code)]
     fn open(&self) -> Pin<Box<dyn Future<Output = io::Result<File>> + Send>> {
+        let options = OpenOptions::new(self.mode, &self.name);
         Box::pin(async move {
-            match OpenOptions::new().open().await {
+            match options.open().await {
                 Ok(file) => {
                     // Set non-blocking mode
                     let fd = file.as_raw_fd();
@@ -75,8 +186,14 @@ impl DeviceOpener for Device {
 }
 
 // Tap actor for handling TAP device operations
+//
+// The open device is owned by the actor itself. Reads and writes run on a
+// duplicate of its descriptor, so they never need to borrow the actor state
+// across an await point and concurrent reads and writes do not serialize.
 pub struct Tap {
-    device: Arc<Mutex<Option<File>>>
+    device: Option<File>,
+    // Size of the buffer each read fills
+    frame_rx_max_len: usize,
 }
 
 impl Actor for Tap {
@@ -84,31 +201,127 @@ impl Actor for Tap {
 }
 
 impl Tap {
-    fn new() -> Self {
+    pub fn new() -> Self {
+        Self::with_config(&Config::new())
+    }
+
+    /// Creates a `Tap` whose reads are sized by `config.frame_rx_max_len`.
+    pub fn with_config(config: &Config) -> Self {
         Self {
-            device: Arc::new(Mutex::new(None)),
+            device: None,
+            frame_rx_max_len: config.frame_rx_max_len,
          }
     }
 
-    // Todo
-    // Use these mthds to remove mutex
-    // fn set_device(&mut self, device: File) {
-    //     self.device = Some(device);
-    // }
+    /// Returns the size of the buffer each read fills.
+    pub fn frame_rx_max_len(&self) -> usize {
+        self.frame_rx_max_len
+    }
+
+    fn set_device(&mut self, device: File) {
+        self.device = Some(device);
+    }
+
+    // Duplicate the open device's file descriptor for a single read or write
+    #[allow(unsafe_code)]
+    fn clone_device(&self) -> IoResult<File> {
+        let file = self.device.as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Device not found"))?;
+        let fd = unsafe { libc::dup(file.as_raw_fd()) };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a freshly duplicated descriptor owned by nothing else
+        Ok(File::from_std(unsafe { std::fs::File::from_raw_fd(fd) }))
+    }
+
+    // Reads up to `max` frames, one per read. Waits for the first frame
+    // only; the batch ends early once no further frame is ready or the
+    // device reaches end of file.
+    fn read_batch(&self, max: usize) -> ResponseFuture<IoResult<Vec<Vec<u8>>>> {
+        let device = self.clone_device();
+        let frame_rx_max_len = self.frame_rx_max_len;
+
+        Box::pin(async move {
+            let mut frames = Vec::new();
+            if max == 0 {
+                return Ok(frames);
+            }
+            let mut file = device?;
+            while frames.len() < max {
+                if !frames.is_empty() && !readable(&file)? {
+                    break;
+                }
+                let mut buf = vec![0u8; frame_rx_max_len];
+                let n = file.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                buf.truncate(n);
+                frames.push(buf);
+            }
+            Ok(frames)
+        })
+    }
+}
+
+// Whether a read on `file` would return without blocking
+#[allow(unsafe_code)]
+fn readable(file: &File) -> IoResult<bool> {
+    let mut fds = libc::pollfd { fd: file.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+    // SAFETY: `fds` is a single valid pollfd for the duration of the call
+    let n = unsafe { libc::poll(&mut fds, 1, 0) };
+    if n == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(n > 0 && fds.revents & libc::POLLIN != 0)
+}
 
-    // fn clear_device(&mut self) {
-    //     self.device = None;
-    // }
+impl Default for Tap {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Implementation of NicInterface for Tap
 impl NicInterface for Tap {
     fn read_packet(&self) -> Pin<Box<dyn Future<Output = IoResult<Vec<u8>>> + Send>> {
-        todo!();
+        let device = self.clone_device();
+        let frame_rx_max_len = self.frame_rx_max_len;
+
+        Box::pin(async move {
+            let mut file = device?;
+            let mut buf = vec![0u8; frame_rx_max_len];
+            let n = file.read(&mut buf).await?;
+            buf.truncate(n);
+            Ok(buf)
+        })
     }
 
     fn write_packet(&self, data: Vec<u8>) -> Pin<Box<dyn Future<Output = IoResult<()>> + Send>> {
-        todo!();
+        let device = self.clone_device();
+
+        Box::pin(async move {
+            let mut file = device?;
+            file.write_all(&data).await?;
+            file.flush().await
+        })
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.frame_rx_max_len = config.frame_rx_max_len;
+    }
+}
+
+impl Handler<Reconfigure> for Tap {
+    type Result = Result<(), ConfigError>;
+
+    /// Re-validates the config and picks up the new maximum frame length.
+    /// The open device is left untouched.
+    fn handle(&mut self, msg: Reconfigure, _: &mut Context<Self>) -> Self::Result {
+        msg.0.validate()?;
+        NicInterface::reconfigure(self, &msg.0);
+        Ok(())
     }
 }
 
@@ -124,41 +337,15 @@ impl Message for OpenTap {
 }
 
 impl Handler<OpenTap> for Tap {
-    type Result = ResponseFuture<IoResult<()>>;
+    type Result = ResponseActFuture<Self, IoResult<()>>;
 
     /// Handles the OpenTap message to open a TAP device asynchronously.
-    fn handle(&mut self, msg: OpenTap, ctx: &mut Context<Self>) -> Self::Result {
-        let device_future = msg.opener.open();
-        let addr: Addr<Tap> = ctx.address(); // Get actor's address
-        Box::pin(async move {
-            match device_future.await {
-                Ok(file) => {
-                    addr.do_send(UpdateDevice { device: file });
-                    Ok(())
-                },
-                Err(e) => Err(e),
-            }
-        })
-    }
-}
-
-
-pub struct UpdateDevice {
-    device: File,
-}
-
-impl Message for UpdateDevice {
-    type Result = IoResult<()>;
-}
-
-impl Handler<UpdateDevice> for Tap {
-    type Result = IoResult<()>;
-
-    /// Updates the internal state with the newly opened TAP device.
-    fn handle(&mut self, msg: UpdateDevice, _: &mut Context<Self>) -> Self::Result {
-        let mut device = self.device.lock().map_err(|_| io::Error::new(io::ErrorKind::Other, "Mutex lock poisoned"))?;
-        *device = Some(msg.device);
-        Ok(())
+    /// The device is stored in the actor before the reply is sent.
+    fn handle(&mut self, msg: OpenTap, _: &mut Context<Self>) -> Self::Result {
+        Box::pin(msg.opener.open().into_actor(self).map(|result, act, _| {
+            act.set_device(result?);
+            Ok(())
+        }))
     }
 }
 
@@ -175,21 +362,7 @@ impl Handler<WriteMessage> for Tap {
     type Result = ResponseFuture<IoResult<()>>;
 
     fn handle(&mut self, msg: WriteMessage, _: &mut Context<Self>) -> Self::Result {
-        let device = self.device.clone();
-
-        Box::pin(async move {
-            let device_lock = device.lock().map_err(|_| io::Error::new(io::ErrorKind::Other, "Mutex lock poisoned"));
-            match device_lock {
-                Ok(mut device) => {
-                    if let Some(file) = device.as_mut() {
-                        file.write_all(&msg.data).await.map_err(|e| e.into())
-                    } else {
-                        Err(io::Error::new(io::ErrorKind::NotFound, "Device not found"))
-                    }
-                },
-                Err(e) => Err(e),
-            }
-        })
+        self.write_packet(msg.data)
     }
 }
 
@@ -207,24 +380,24 @@ impl Handler<ReadMessage> for Tap {
     type Result = ResponseFuture<Result<Vec<u8>, io::Error>>;
 
     fn handle(&mut self, _: ReadMessage, _: &mut Context<Self>) -> Self::Result {
-        let device = self.device.clone();
+        self.read_packet()
+    }
+}
 
-        Box::pin(async move {
-            let device_lock = device.lock().map_err(|_| io::Error::new(io::ErrorKind::Other, "Mutex lock poisoned"));
-            match device_lock {
-                Ok(mut device) => {
-                    if let Some(file) = device.as_mut() {
-                        let mut buf = vec![0u8; 4096];
-                        let n = file.read(&mut buf).await?;
-                        buf.truncate(n);
-                        Ok(buf)
-                    } else {
-                        Err(io::Error::new(io::ErrorKind::NotFound, "Device not found"))
-                    }
-                },
-                Err(e) => Err(e),
-            }
-        })
+// Message to read up to the given number of frames in one go. The
+// reply may hold fewer frames, but at least one unless the device is at
+// end of file.
+pub struct ReadBatchMessage(pub usize);
+
+impl Message for ReadBatchMessage {
+    type Result = Result<Vec<Vec<u8>>, std::io::Error>;
+}
+
+impl Handler<ReadBatchMessage> for Tap {
+    type Result = ResponseFuture<Result<Vec<Vec<u8>>, io::Error>>;
+
+    fn handle(&mut self, msg: ReadBatchMessage, _: &mut Context<Self>) -> Self::Result {
+        self.read_batch(msg.0)
     }
 }
 #[cfg(test)]
@@ -235,7 +408,8 @@ mod tests {
         fn open(&self) -> Pin<Box<dyn Future<Output = io::Result<File>> + Send>> {
             Box::pin(async {
                 // Simulate successful device opening
-                Ok(File::from_std(std::fs::File::open("/dev/null").unwrap()))
+                let null = std::fs::OpenOptions::new().read(true).write(true).open("/dev/null").unwrap();
+                Ok(File::from_std(null))
             })
         }
     }
@@ -245,7 +419,7 @@ mod tests {
         fn open(&self) -> Pin<Box<dyn Future<Output = io::Result<File>> + Send>> {
             Box::pin(async {
                 // Simulate an I/O error using io::Error
-                Err(io::Error::new(io::ErrorKind::Other, "Mocked device open failure"))
+                Err(io::Error::other("Mocked device open failure"))
             })
         }
     }
@@ -258,6 +432,23 @@ mod tests {
         assert!(open_result.is_ok(), "The OpenTap message should be handled without errors");
     }
 
+    #[actix_rt::test]
+    async fn test_open_then_write() {
+        // The device is in place as soon as OpenTap resolves
+        let tap_actor = Tap::new().start();
+        tap_actor.send(OpenTap { opener: Box::new(MockDevice) }).await.unwrap().unwrap();
+        let result = tap_actor.send(WriteMessage { data: vec![0xde, 0xad] }).await.unwrap();
+        assert!(result.is_ok(), "Writing to an open device should succeed");
+    }
+
+    #[actix_rt::test]
+    async fn test_open_then_read() {
+        let tap_actor = Tap::new().start();
+        tap_actor.send(OpenTap { opener: Box::new(MockDevice) }).await.unwrap().unwrap();
+        let packet = tap_actor.send(ReadMessage).await.unwrap().unwrap();
+        assert!(packet.is_empty(), "/dev/null reads end of file");
+    }
+
     #[actix_rt::test]
     async fn test_open_failure() {
         let tap_actor = Tap::new().start();
@@ -293,6 +484,128 @@ mod tests {
         assert!(result.unwrap().is_err(), "Read operation should fail when no device is open");
     }
 
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_ifreq_packing() {
+        assert_eq!(std::mem::size_of::<IfReq>(), 40);
+
+        let req = IfReq::new("thunda0", DeviceMode::Tun).unwrap();
+        assert_eq!(&req.name[..8], b"thunda0\0");
+        assert!(req.name[8..].iter().all(|b| *b == 0));
+        assert_eq!(req.flags, IFF_TUN | IFF_NO_PI);
+
+        // The name sits at the start of the struct, the flags right after it.
+        let bytes: &[u8] = unsafe {
+            std::slice::from_raw_parts(&req as *const IfReq as *const u8, std::mem::size_of::<IfReq>())
+        };
+        assert_eq!(&bytes[..7], b"thunda0");
+        assert_eq!(&bytes[16..18], &(IFF_TUN | IFF_NO_PI).to_ne_bytes());
+
+        let req = IfReq::new("", DeviceMode::Tap).unwrap();
+        assert_eq!(req.name, [0; IFNAMSIZ]);
+        assert_eq!(req.flags, IFF_TAP | IFF_NO_PI);
+    }
+
+    #[test]
+    fn test_ifreq_rejects_bad_names() {
+        assert!(IfReq::new("fifteen-chars-x", DeviceMode::Tap).is_ok());
+        assert!(IfReq::new("sixteen-chars-xx", DeviceMode::Tap).is_err());
+        assert!(IfReq::new("nul\0name", DeviceMode::Tap).is_err());
+    }
+
+    #[test]
+    fn test_validate_interface_name() {
+        assert!(validate_interface_name("thunda0").is_ok());
+        assert!(validate_interface_name("").is_ok());
+        assert!(validate_interface_name(&"x".repeat(IFNAMSIZ - 1)).is_ok());
+        for bad in ["x".repeat(IFNAMSIZ), "eth/0".to_string(), "eth 0".to_string(), "a:b".to_string(), "..".to_string()] {
+            let err = validate_interface_name(&bad).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{}", bad);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_long_name_rejected_before_open() {
+        // The name check fails with InvalidInput before /dev/net/tun is
+        // touched, so this holds whether or not the device is available.
+        let device = Device::tap("thunda-interface0");
+        assert_eq!(device.name(), "thunda-interface0");
+        let err = device.open().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_device_modes() {
+        assert_eq!(Device::tap("tap9").mode(), DeviceMode::Tap);
+        assert_eq!(Device::tun("tun9").mode(), DeviceMode::Tun);
+    }
+
+    // Path of a scratch file unique to this process and test
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("thunda-tap-{}-{}", std::process::id(), name))
+    }
+
+    #[actix_rt::test]
+    async fn test_nic_interface_read() {
+        let path = scratch_path("read");
+        std::fs::write(&path, [0xde, 0xad, 0xbe, 0xef]).unwrap();
+
+        let mut tap = Tap::new();
+        tap.set_device(File::open(&path).await.unwrap());
+        let packet = tap.read_packet().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(packet, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[actix_rt::test]
+    async fn test_read_bounded_by_frame_rx_max_len() {
+        let path = scratch_path("bounded");
+        std::fs::write(&path, [0xab; 100]).unwrap();
+
+        let config = Config::builder().frame_rx_max_len(64).build().unwrap();
+        let mut tap = Tap::with_config(&config);
+        assert_eq!(tap.frame_rx_max_len(), 64);
+        tap.set_device(File::open(&path).await.unwrap());
+        let tap_actor = tap.start();
+        let first = tap_actor.send(ReadMessage).await.unwrap().unwrap();
+        let rest = tap_actor.send(ReadMessage).await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((first.len(), rest.len()), (64, 36));
+    }
+
+    #[actix_rt::test]
+    async fn test_nic_interface_write() {
+        let path = scratch_path("write");
+        let mut tap = Tap::new();
+        tap.set_device(File::create(&path).await.unwrap());
+        tap.write_packet(vec![0xca, 0xfe]).await.unwrap();
+
+        let written = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, vec![0xca, 0xfe]);
+    }
+
+    #[actix_rt::test]
+    async fn test_nic_interface_no_device() {
+        let tap = Tap::new();
+        assert_eq!(tap.read_packet().await.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(tap.write_packet(vec![0x00]).await.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[actix_rt::test]
+    async fn test_reconfigure() {
+        let tap_actor = Tap::new().start();
+        let mut config = Config::new();
+        config.frame_rx_max_len = 9000;
+        assert_eq!(tap_actor.send(Reconfigure(config.clone())).await.unwrap(), Ok(()));
+
+        config.frame_rx_max_len = 4;
+        assert_eq!(
+            tap_actor.send(Reconfigure(config)).await.unwrap(),
+            Err(ConfigError::InvalidFrameLength(4))
+        );
+    }
+
     #[actix_rt::test]
     async fn test_write_with_no_device() {
         let tap_actor = Tap::new().start();
@@ -302,4 +615,33 @@ mod tests {
 
         assert!(result.unwrap().is_err(), "Write operation should fail when no device is open");
     }
+
+    #[actix_rt::test]
+    async fn test_read_batch_respects_max() {
+        // Five 64 byte frames, read one frame per read
+        let path = scratch_path("batch");
+        std::fs::write(&path, [0x5a; 5 * 64]).unwrap();
+
+        let config = Config::builder().frame_rx_max_len(64).build().unwrap();
+        let mut tap = Tap::with_config(&config);
+        tap.set_device(File::open(&path).await.unwrap());
+        let tap_actor = tap.start();
+        let first = tap_actor.send(ReadBatchMessage(3)).await.unwrap().unwrap();
+        let rest = tap_actor.send(ReadBatchMessage(10)).await.unwrap().unwrap();
+        let empty = tap_actor.send(ReadBatchMessage(0)).await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(first.len(), 3);
+        assert!(first.iter().all(|frame| frame.len() == 64));
+        // The device runs out before the batch is full
+        assert_eq!(rest.len(), 2);
+        assert!(empty.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_read_batch_with_no_device() {
+        let tap_actor = Tap::new().start();
+        let result = tap_actor.send(ReadBatchMessage(4)).await.unwrap();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
 }
@@ -0,0 +1,53 @@
+// src/io/loopback.rs
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{self, Result as IoResult};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use crate::io::nic_interface::NicInterface;
+
+/// In-memory NIC that hands every written packet back to the reader.
+///
+/// Useful for tests and for wiring actors together without a device.
+#[derive(Clone, Default)]
+pub struct LoopbackNic {
+    queue: Arc<Mutex<VecDeque<Vec<u8>>>>,
+}
+
+impl LoopbackNic {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NicInterface for LoopbackNic {
+    /// Return the oldest queued packet, or a `WouldBlock` error if none is
+    /// waiting.
+    fn read_packet(&self) -> Pin<Box<dyn Future<Output = IoResult<Vec<u8>>> + Send>> {
+        let packet = self.queue.lock().unwrap().pop_front();
+        Box::pin(async move {
+            packet.ok_or_else(|| io::Error::from(io::ErrorKind::WouldBlock))
+        })
+    }
+
+    fn write_packet(&self, data: Vec<u8>) -> Pin<Box<dyn Future<Output = IoResult<()>> + Send>> {
+        self.queue.lock().unwrap().push_back(data);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_loopback_order() {
+        let nic = LoopbackNic::new();
+        nic.write_packet(vec![1]).await.unwrap();
+        nic.write_packet(vec![2]).await.unwrap();
+        assert_eq!(nic.read_packet().await.unwrap(), vec![1]);
+        assert_eq!(nic.read_packet().await.unwrap(), vec![2]);
+        assert_eq!(nic.read_packet().await.unwrap_err().kind(), io::ErrorKind::WouldBlock);
+    }
+}
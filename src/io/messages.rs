@@ -1,9 +1,35 @@
+// src/io/messages.rs
 use actix::Message;
+use crate::address::ipv4::IPv4;
+use crate::address::mac::Mac;
 
+/// A packet read from one of `NetworkIO`'s NICs, tagged with the index of
+/// the NIC it arrived on.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PacketReceived {
-    // Packet data
+    /// Index of the ingress NIC in the order the NICs were given
+    pub nic_index: usize,
+    /// Packet data
+    pub data: Vec<u8>,
 }
 
 impl Message for PacketReceived {
-    type Result = Result<(), ()>;
+    type Result = ();
+}
+
+/// A received frame handed downstream for parsing and processing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessPacket(pub Vec<u8>);
+
+impl Message for ProcessPacket {
+    type Result = ();
+}
+
+/// Request for the MAC address of an on-link IPv4 neighbor, e.g. from an
+/// ARP cache. `None` means the address is not known yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolve(pub IPv4);
+
+impl Message for Resolve {
+    type Result = Option<Mac>;
 }
@@ -1,2 +1,7 @@
 pub mod network_io;
 pub mod nic_interface;
+pub mod messages;
+pub mod loopback;
+pub mod pcap;
+pub mod tee;
+pub mod udp_socket;
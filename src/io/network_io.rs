@@ -1,66 +1,247 @@
 // src/io/network_io.rs
 
 // use actix::prelude::*;
-use actix::{Actor, Addr, AsyncContext, Context, Handler, Message};
-use std::io::Result as IoResult; // Same as Result<T, std::io::Error>
+use actix::{
+    Actor, ActorContext, ActorFutureExt, Context, Handler, Message, MessageResult,
+    Recipient, ResponseActFuture, ResponseFuture, WrapFuture,
+};
+use std::io::{self, Result as IoResult}; // Same as Result<T, std::io::Error>
+use crate::io::messages::{PacketReceived, ProcessPacket};
 use crate::io::nic_interface::NicInterface;
+use crate::parsers::ethernet::ETHER_MIN_LENGTH;
+use crate::{Config, ConfigError};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time::{self, Duration};
-use log::{error, debug};
+use log::{error, debug, info};
+
+// Bounds of the delay between retries after a failed read
+const READ_BACKOFF_INITIAL: Duration = Duration::from_millis(100);
+const READ_BACKOFF_MAX: Duration = Duration::from_secs(1);
+
+// Exponential backoff between read retries
+struct Backoff {
+    delay: Duration,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Self { delay: READ_BACKOFF_INITIAL }
+    }
+
+    // Return the delay to wait now and double the next one, up to the cap
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(READ_BACKOFF_MAX);
+        delay
+    }
+
+    fn reset(&mut self) {
+        self.delay = READ_BACKOFF_INITIAL;
+    }
+}
+
+// Errors after which the NIC will never produce another packet
+fn is_fatal(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::BrokenPipe | io::ErrorKind::UnexpectedEof
+    )
+}
+
+/// Traffic counters of a `NetworkIO`, summed over all its NICs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub frames_received: u64,
+    pub frames_sent: u64,
+    /// Failed reads, not counting reads that found nothing to read
+    pub read_errors: u64,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+}
+
+// Counters shared with the listen loops and send tasks. They are only
+// ever summed, so relaxed ordering is enough.
+#[derive(Default)]
+struct StatsCounters {
+    frames_received: AtomicU64,
+    frames_sent: AtomicU64,
+    read_errors: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+}
+
+impl StatsCounters {
+    fn received(&self, len: usize) {
+        self.frames_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    fn sent(&self, len: usize) {
+        self.frames_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    fn read_error(&self) {
+        self.read_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> Stats {
+        Stats {
+            frames_received: self.frames_received.load(Ordering::Relaxed),
+            frames_sent: self.frames_sent.load(Ordering::Relaxed),
+            read_errors: self.read_errors.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A shared handle to a NIC driven by `NetworkIO`
+pub type SharedNic = Arc<Mutex<dyn NicInterface + Send>>;
 
 pub struct NetworkIO {
-    nic: Arc<Mutex<dyn NicInterface + Send>>,
+    // NICs in index order; each gets its own listen loop
+    nics: Vec<SharedNic>,
+    config: Config,
+    // Where received packets are forwarded, tagged with their ingress NIC
+    receiver: Option<Recipient<PacketReceived>>,
+    // Downstream processor, e.g. the `parsers::packet::Packet` actor
+    processor: Option<Recipient<ProcessPacket>>,
+    // Set to true to make the listen loops exit; dropping it does the same.
+    // The loops hold no address of the actor, so dropping every `Addr`
+    // stops it and, through `stopped`, them.
+    shutdown: watch::Sender<bool>,
+    // The running listen loops, joined on `Shutdown`
+    listeners: Vec<JoinHandle<()>>,
+    stats: Arc<StatsCounters>,
 }
 
 impl NetworkIO {
     /// Creates a new `NetworkIO` actor with the specified network interface controller (NIC).
-    pub fn new(nic: Arc<Mutex<dyn NicInterface + Send>> ) -> Self {
-        Self { nic }
+    pub fn new(nic: SharedNic) -> Self {
+        Self::with_config(nic, Config::new())
+    }
+
+    /// Creates a new `NetworkIO` actor running with `config`.
+    pub fn with_config(nic: SharedNic, config: Config) -> Self {
+        Self::with_nics(vec![nic], config)
+    }
+
+    /// Creates a new `NetworkIO` actor driving several NICs. A NIC's index
+    /// in `nics` identifies it in `SendPacket` and `PacketReceived`.
+    pub fn with_nics(nics: Vec<SharedNic>, config: Config) -> Self {
+        let (shutdown, _) = watch::channel(false);
+        Self { nics, config, receiver: None, processor: None, shutdown, listeners: Vec::new(), stats: Arc::default() }
+    }
+
+    /// Forward every received packet to `receiver`.
+    pub fn with_receiver(mut self, receiver: Recipient<PacketReceived>) -> Self {
+        self.receiver = Some(receiver);
+        self
+    }
+
+    /// Hand every received packet to `processor` for processing.
+    pub fn with_processor(mut self, processor: Recipient<ProcessPacket>) -> Self {
+        self.processor = Some(processor);
+        self
+    }
+
+    /// Returns the config currently in effect.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the number of NICs driven by this actor.
+    pub fn nic_count(&self) -> usize {
+        self.nics.len()
     }
 
     /// Sends a packet through the NIC.
-    async fn send_packet(nic: Arc<Mutex<dyn NicInterface + Send>>, data: Vec<u8>) -> IoResult<()> {
-        let nic_lock = nic.lock().await;
-        nic_lock.write_packet(data).await.map_err(|e| {
+    async fn send_packet(nic: SharedNic, data: Vec<u8>, stats: Arc<StatsCounters>) -> IoResult<()> {
+        let len = data.len();
+        // Only hold the lock to start the write, so reads are not held up
+        let write = nic.lock().await.write_packet(data);
+        write.await.map_err(|e| {
             error!("Error sending packet: {}", e);
             e
-        })
+        })?;
+        stats.sent(len);
+        Ok(())
     }
 
-    /// Initiates packet listening.
-    async fn start_listening(nic: Arc<Mutex<dyn NicInterface + Send>>, _addr: Addr<NetworkIO>) {
-        debug!("Start listening for incoming packets.");
+    /// Initiates packet listening on the NIC at `nic_index`, until
+    /// `shutdown` turns true or its sender is dropped.
+    async fn start_listening(
+        nic_index: usize,
+        nic: SharedNic,
+        receiver: Option<Recipient<PacketReceived>>,
+        processor: Option<Recipient<ProcessPacket>>,
+        mut shutdown: watch::Receiver<bool>,
+        stats: Arc<StatsCounters>,
+    ) {
+        debug!("Start listening for incoming packets on NIC {}.", nic_index);
 
         // Interval timer to introduce delay in each iteration.
         // Helps in preventing the loop from consuming 100% CPU in a tight loop
         // when there are no packets to process.
         let mut interval = time::interval(Duration::from_millis(100));
+        let mut backoff = Backoff::new();
         loop {
             // Await next tick of the interval.
             // This pauses the loop, yielding control back to the Tokio runtime until the interval elapses.
             // Simple way to prevent constant polling for packets
             // and allows the CPU to do other tasks or enter a low-power state.
-            interval.tick().await;
-            let result = {
-                let lock = nic.lock().await;
-                lock.read_packet().await
+            // A pending read or delay is abandoned on shutdown
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+            let result = tokio::select! {
+                // The lock is released before awaiting the read, so a read
+                // waiting for traffic does not block sends on the same NIC
+                result = async {
+                    let read = nic.lock().await.read_packet();
+                    read.await
+                } => result,
+                _ = shutdown.changed() => break,
             };
 
             match result {
                 Ok(packet) => {
+                    backoff.reset();
+                    stats.received(packet.len());
                     // Forward the packet for further processing
-                    debug!("Packet received: {:?}", packet);
-                    // addr.do_send(ProcessPacket(packet));
+                    debug!("Packet received on NIC {}: {:?}", nic_index, packet);
+                    if let Some(receiver) = &receiver {
+                        receiver.do_send(PacketReceived { nic_index, data: packet.clone() });
+                    }
+                    if let Some(processor) = &processor {
+                        processor.do_send(ProcessPacket(packet));
+                    }
 
                 },
+                // Nothing to read yet; not a failure
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) if is_fatal(&e) => {
+                    stats.read_error();
+                    error!("Fatal error reading packet on NIC {}: {}", nic_index, e);
+                    break;
+                }
                 Err(e) => {
-                    error!("Error reading packet: {}", e);
-                    break; // To Do: Use backoff
+                    stats.read_error();
+                    let delay = backoff.next_delay();
+                    error!("Error reading packet on NIC {}: {}; retrying in {:?}", nic_index, e, delay);
+                    tokio::select! {
+                        _ = time::sleep(delay) => {}
+                        _ = shutdown.changed() => break,
+                    }
                 }
             }
         }
-        debug!("Stopped listening for incoming packets.");
+        debug!("Stopped listening for incoming packets on NIC {}.", nic_index);
     }
 
 }
@@ -73,15 +254,67 @@ impl NetworkIO {
 impl Actor for NetworkIO {
     type Context = Context<Self>;
 
-    fn started(&mut self, ctx: &mut Self::Context) {
+    fn started(&mut self, _ctx: &mut Self::Context) {
         debug!("NetworkIO Actor started, initiating packet listening.");
-        let nic = self.nic.clone();
-        tokio::spawn(Self::start_listening(nic, ctx.address()));
+        for (index, nic) in self.nics.iter().enumerate() {
+            self.listeners.push(tokio::spawn(Self::start_listening(
+                index,
+                nic.clone(),
+                self.receiver.clone(),
+                self.processor.clone(),
+                self.shutdown.subscribe(),
+                self.stats.clone(),
+            )));
+        }
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        self.shutdown.send_replace(true);
+    }
+}
+
+/// Message to stop every listen loop and then the actor itself.
+///
+/// Resolves once all loops have exited, so no NIC is read afterwards.
+pub struct Shutdown;
+
+impl Message for Shutdown {
+    type Result = ();
+}
+
+impl Handler<Shutdown> for NetworkIO {
+    type Result = ResponseActFuture<Self, ()>;
+
+    fn handle(&mut self, _msg: Shutdown, _ctx: &mut Context<Self>) -> Self::Result {
+        info!("Shutting down NetworkIO");
+        self.shutdown.send_replace(true);
+        let listeners = std::mem::take(&mut self.listeners);
+        Box::pin(
+            async move {
+                for listener in listeners {
+                    if let Err(e) = listener.await {
+                        error!("Listen loop failed: {}", e);
+                    }
+                }
+            }
+            .into_actor(self)
+            .map(|_, _, ctx| ctx.stop()),
+        )
     }
 }
 
-/// Message to request sending a packet through the network interface.
-pub struct SendPacket(pub Vec<u8>);
+/// Message to request sending a packet through the NIC at `nic_index`.
+pub struct SendPacket {
+    pub nic_index: usize,
+    pub data: Vec<u8>,
+}
+
+impl SendPacket {
+    /// Send `data` through the first NIC.
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { nic_index: 0, data }
+    }
+}
 
 impl Message for SendPacket {
     type Result = IoResult<()>;
@@ -91,8 +324,17 @@ impl Handler<SendPacket> for NetworkIO {
     type Result = IoResult<()>;
 
     fn handle(&mut self, msg: SendPacket, _ctx: &mut Context<Self>) -> Self::Result {
-        let nic = self.nic.clone();
-        let send_fut = Self::send_packet(nic, msg.0);
+        let nic = self.nics.get(msg.nic_index).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("No NIC at index {}", msg.nic_index))
+        })?;
+        let payload_len = msg.data.len().saturating_sub(ETHER_MIN_LENGTH);
+        if payload_len > self.config.mtu {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Frame payload of {} bytes exceeds the MTU of {}", payload_len, self.config.mtu),
+            ));
+        }
+        let send_fut = Self::send_packet(nic, msg.data, self.stats.clone());
 
         tokio::spawn(async move {
             let _ = send_fut.await;
@@ -102,6 +344,63 @@ impl Handler<SendPacket> for NetworkIO {
     }
 }
 
+/// Message to query a snapshot of the traffic counters.
+pub struct GetStats;
+
+impl Message for GetStats {
+    type Result = Stats;
+}
+
+impl Handler<GetStats> for NetworkIO {
+    type Result = MessageResult<GetStats>;
+
+    fn handle(&mut self, _msg: GetStats, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.stats.snapshot())
+    }
+}
+
+/// Message to apply a new `Config` to the running stack.
+///
+/// The config is validated first; an invalid config is rejected and the
+/// current one stays in effect.
+///
+/// Only some fields take effect at runtime:
+/// - `log_level`, applied process-wide through `Config::apply`
+/// - `mtu`, checked by every later `SendPacket`
+/// - `frame_rx_max_len`, passed to each NIC through
+///   `NicInterface::reconfigure`
+///
+/// The other fields, such as `mac_address`, `default_ttl` and
+/// `default_hop_limit`, are only read when a component or packet is built
+/// from a `Config`. Changing them here does not reach the processor or any
+/// socket already running; rebuild those with the new config instead.
+pub struct Reconfigure(pub Config);
+
+impl Message for Reconfigure {
+    type Result = Result<(), ConfigError>;
+}
+
+impl Handler<Reconfigure> for NetworkIO {
+    type Result = ResponseFuture<Result<(), ConfigError>>;
+
+    fn handle(&mut self, msg: Reconfigure, _ctx: &mut Context<Self>) -> Self::Result {
+        if let Err(e) = msg.0.apply() {
+            error!("Rejected new config: {}", e);
+            return Box::pin(async move { Err(e) });
+        }
+        info!("Applied new config");
+        self.config = msg.0.clone();
+
+        let nics = self.nics.clone();
+        Box::pin(async move {
+            for nic in nics {
+                nic.lock().await.reconfigure(&msg.0);
+            }
+            Ok(())
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +412,7 @@ mod tests {
     use std::sync::Arc;
     use futures::Future;
     use futures::future::{self};
+    use crate::io::loopback::LoopbackNic;
 
     struct MockNicInterface;
     impl NicInterface for MockNicInterface {
@@ -133,7 +433,322 @@ mod tests {
 
         // Simulate sending a packet
         let packet = vec![0xde, 0xad, 0xbe, 0xef];
-        let result = network_io.send(SendPacket(packet)).await;
+        let result = network_io.send(SendPacket::new(packet)).await;
         assert!(result.is_ok(), "SendPacket should succeed with mock NIC");
     }
+
+    // Collects every packet NetworkIO forwards
+    struct Collector(Arc<std::sync::Mutex<Vec<PacketReceived>>>);
+
+    impl Actor for Collector {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<PacketReceived> for Collector {
+        type Result = ();
+
+        fn handle(&mut self, msg: PacketReceived, _ctx: &mut Context<Self>) {
+            self.0.lock().unwrap().push(msg);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_multi_nic_ingress_index() {
+        let first = LoopbackNic::new();
+        let second = LoopbackNic::new();
+        first.write_packet(vec![0x01]).await.unwrap();
+        second.write_packet(vec![0x02]).await.unwrap();
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let collector = Collector(received.clone()).start();
+        let nics: Vec<SharedNic> = vec![Arc::new(Mutex::new(first)), Arc::new(Mutex::new(second))];
+        let network_io = NetworkIO::with_nics(nics, Config::new()).with_receiver(collector.recipient());
+        assert_eq!(network_io.nic_count(), 2);
+        let _network_io = network_io.start();
+
+        for _ in 0..50 {
+            if received.lock().unwrap().len() == 2 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        let mut received = received.lock().unwrap().clone();
+        received.sort_by_key(|p| p.nic_index);
+        assert_eq!(received, vec![
+            PacketReceived { nic_index: 0, data: vec![0x01] },
+            PacketReceived { nic_index: 1, data: vec![0x02] },
+        ]);
+    }
+
+    // Records the bytes of every packet handed downstream
+    struct MockProcessor(Arc<std::sync::Mutex<Vec<Vec<u8>>>>);
+
+    impl Actor for MockProcessor {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<ProcessPacket> for MockProcessor {
+        type Result = ();
+
+        fn handle(&mut self, msg: ProcessPacket, _ctx: &mut Context<Self>) {
+            self.0.lock().unwrap().push(msg.0);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_process_packet_delivered() {
+        let delivered = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MockProcessor(delivered.clone()).start();
+        let nic = Arc::new(Mutex::new(MockNicInterface));
+        let _network_io = NetworkIO::new(nic).with_processor(processor.recipient()).start();
+
+        for _ in 0..50 {
+            if !delivered.lock().unwrap().is_empty() {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(delivered.lock().unwrap().first(), Some(&vec![0xde, 0xad, 0xbe, 0xef]));
+    }
+
+    // Fails its first `failures` reads with `kind`, then returns packets
+    struct FlakyNic {
+        failures: usize,
+        kind: io::ErrorKind,
+        reads: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl NicInterface for FlakyNic {
+        fn write_packet(&self, _data: Vec<u8>) -> Pin<Box<dyn Future<Output = IoResult<()>> + Send>> {
+            Box::pin(future::ready(Ok(())))
+        }
+
+        fn read_packet(&self) -> Pin<Box<dyn Future<Output = IoResult<Vec<u8>>> + Send>> {
+            let read = self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if read < self.failures {
+                Box::pin(future::ready(Err(io::Error::from(self.kind))))
+            } else {
+                Box::pin(future::ready(Ok(vec![read as u8])))
+            }
+        }
+    }
+
+    // Never completes a read; counts writes
+    struct IdleNic {
+        writes: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl NicInterface for IdleNic {
+        fn write_packet(&self, _data: Vec<u8>) -> Pin<Box<dyn Future<Output = IoResult<()>> + Send>> {
+            self.writes.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(future::ready(Ok(())))
+        }
+
+        fn read_packet(&self) -> Pin<Box<dyn Future<Output = IoResult<Vec<u8>>> + Send>> {
+            Box::pin(future::pending())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_send_while_read_pending() {
+        let writes = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = IdleNic { writes: writes.clone() };
+        let network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).start();
+
+        // Let the listen loop start its read
+        time::sleep(Duration::from_millis(150)).await;
+        network_io.send(SendPacket::new(vec![0u8; 60])).await.unwrap().unwrap();
+        for _ in 0..50 {
+            if writes.load(std::sync::atomic::Ordering::SeqCst) == 1 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(writes.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let mut backoff = Backoff::new();
+        let delays: Vec<_> = (0..6).map(|_| backoff.next_delay().as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), READ_BACKOFF_INITIAL);
+    }
+
+    #[actix_rt::test]
+    async fn test_read_loop_recovers_after_errors() {
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = FlakyNic { failures: 2, kind: io::ErrorKind::Other, reads: reads.clone() };
+        let delivered = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MockProcessor(delivered.clone()).start();
+        let _network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).with_processor(processor.recipient()).start();
+
+        for _ in 0..100 {
+            if !delivered.lock().unwrap().is_empty() {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(delivered.lock().unwrap().first(), Some(&vec![2]));
+    }
+
+    #[actix_rt::test]
+    async fn test_read_loop_stops_on_fatal_error() {
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = FlakyNic { failures: usize::MAX, kind: io::ErrorKind::NotFound, reads: reads.clone() };
+        let _network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).start();
+
+        time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[actix_rt::test]
+    async fn test_would_block_keeps_polling() {
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = FlakyNic { failures: usize::MAX, kind: io::ErrorKind::WouldBlock, reads: reads.clone() };
+        let _network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).start();
+
+        // Polled every 100ms tick, without any backoff delay
+        time::sleep(Duration::from_millis(250)).await;
+        assert!(reads.load(std::sync::atomic::Ordering::SeqCst) >= 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_send_packet_exceeding_mtu() {
+        let nic = Arc::new(Mutex::new(MockNicInterface));
+        let config = Config::builder().mtu(576).build().unwrap();
+        let network_io = NetworkIO::with_config(nic, config).start();
+
+        let fits = vec![0u8; ETHER_MIN_LENGTH + 576];
+        assert!(network_io.send(SendPacket::new(fits)).await.unwrap().is_ok());
+
+        let oversized = vec![0u8; ETHER_MIN_LENGTH + 577];
+        let err = network_io.send(SendPacket::new(oversized)).await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[actix_rt::test]
+    async fn test_send_packet_by_index() {
+        let first = LoopbackNic::new();
+        let second = LoopbackNic::new();
+        let nics: Vec<SharedNic> = vec![Arc::new(Mutex::new(first.clone())), Arc::new(Mutex::new(second.clone()))];
+        let mut network_io = NetworkIO::with_nics(nics, Config::new());
+        let mut ctx = Context::new();
+
+        network_io.handle(SendPacket { nic_index: 1, data: vec![0xaa] }, &mut ctx).unwrap();
+        let err = network_io.handle(SendPacket { nic_index: 2, data: vec![0xbb] }, &mut ctx).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // The write runs on a spawned task
+        tokio::task::yield_now().await;
+        time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(second.read_packet().await.unwrap(), vec![0xaa]);
+        assert!(first.read_packet().await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_reconfigure_log_level() {
+        let nic = Arc::new(Mutex::new(MockNicInterface));
+        let network_io = NetworkIO::new(nic).start();
+
+        let mut config = Config::new();
+        config.log_level = "trace".to_string();
+        assert_eq!(network_io.send(Reconfigure(config.clone())).await.unwrap(), Ok(()));
+        assert_eq!(log::max_level(), log::LevelFilter::Trace);
+
+        config.log_level = "warn".to_string();
+        assert_eq!(network_io.send(Reconfigure(config)).await.unwrap(), Ok(()));
+        assert_eq!(log::max_level(), log::LevelFilter::Warn);
+    }
+
+    #[actix_rt::test]
+    async fn test_reconfigure_mtu() {
+        let nic = Arc::new(Mutex::new(MockNicInterface));
+        let network_io = NetworkIO::new(nic).start();
+        let frame = vec![0u8; ETHER_MIN_LENGTH + 1000];
+        assert!(network_io.send(SendPacket::new(frame.clone())).await.unwrap().is_ok());
+
+        let config = Config::builder().mtu(576).build().unwrap();
+        assert_eq!(network_io.send(Reconfigure(config)).await.unwrap(), Ok(()));
+        let err = network_io.send(SendPacket::new(frame)).await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[actix_rt::test]
+    async fn test_reconfigure_rejects_invalid() {
+        let nic = Arc::new(Mutex::new(MockNicInterface));
+        let mut network_io = NetworkIO::new(nic);
+
+        let mut config = Config::new();
+        config.frame_rx_max_len = 0;
+        let mut ctx = Context::new();
+        let result = network_io.handle(Reconfigure(config), &mut ctx).await;
+        assert_eq!(result, Err(ConfigError::InvalidFrameLength(0)));
+        assert_eq!(network_io.config().frame_rx_max_len, Config::new().frame_rx_max_len);
+    }
+
+    #[actix_rt::test]
+    async fn test_shutdown_stops_reading() {
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = FlakyNic { failures: usize::MAX, kind: io::ErrorKind::WouldBlock, reads: reads.clone() };
+        let network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).start();
+
+        time::sleep(Duration::from_millis(150)).await;
+        network_io.send(Shutdown).await.unwrap();
+        let after_shutdown = reads.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(after_shutdown >= 1);
+
+        time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), after_shutdown);
+        assert!(!network_io.connected());
+    }
+
+    #[actix_rt::test]
+    async fn test_dropping_addr_stops_reading() {
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = FlakyNic { failures: usize::MAX, kind: io::ErrorKind::WouldBlock, reads: reads.clone() };
+        let network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).start();
+
+        time::sleep(Duration::from_millis(150)).await;
+        drop(network_io);
+        time::sleep(Duration::from_millis(50)).await;
+        let after_drop = reads.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(after_drop >= 1);
+
+        time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), after_drop);
+    }
+
+    #[actix_rt::test]
+    async fn test_stats_count_sent_packets() {
+        let nic = LoopbackNic::new();
+        let network_io = NetworkIO::new(Arc::new(Mutex::new(nic.clone()))).start();
+        for len in [60, 64, 100] {
+            network_io.send(SendPacket::new(vec![0u8; len])).await.unwrap().unwrap();
+        }
+
+        // Writes run on spawned tasks; the loop reads them back as they land
+        let mut stats = Stats::default();
+        for _ in 0..50 {
+            stats = network_io.send(GetStats).await.unwrap();
+            if stats.frames_sent == 3 && stats.frames_received == 3 {
+                break;
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!((stats.frames_sent, stats.bytes_sent), (3, 224));
+        assert_eq!((stats.frames_received, stats.bytes_received), (3, 224));
+    }
+
+    #[actix_rt::test]
+    async fn test_stats_count_read_errors() {
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let nic = FlakyNic { failures: usize::MAX, kind: io::ErrorKind::NotFound, reads };
+        let network_io = NetworkIO::new(Arc::new(Mutex::new(nic))).start();
+
+        time::sleep(Duration::from_millis(150)).await;
+        let stats = network_io.send(GetStats).await.unwrap();
+        assert_eq!(stats, Stats { read_errors: 1, ..Stats::default() });
+    }
 }
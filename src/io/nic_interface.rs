@@ -2,9 +2,13 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::io::Result as IoResult;
+use crate::Config;
 
 /// Trait defining common operations for network interfaces.
 pub trait NicInterface {
     fn read_packet(&self) -> Pin<Box<dyn Future<Output = IoResult<Vec<u8>>> + Send>>;
     fn write_packet(&self, data: Vec<u8>) -> Pin<Box<dyn Future<Output = IoResult<()>> + Send>>;
+
+    /// Apply an already validated config to the running interface.
+    fn reconfigure(&mut self, _config: &Config) {}
 }
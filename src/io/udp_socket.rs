@@ -0,0 +1,435 @@
+// src/io/udp_socket.rs
+//! Socket-like UDP over `NetworkIO`.
+//!
+//! A `UdpDispatcher` is registered as `NetworkIO`'s receiver and hands each
+//! valid IPv4 UDP datagram to the `UdpSocket` bound to its destination
+//! port. Sockets assemble Ethernet, IPv4 and UDP headers around the data
+//! they send.
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Result as IoResult};
+
+use actix::{Actor, Addr, Context, Handler, Message, Recipient, ResponseFuture};
+use log::debug;
+
+use crate::address::ipv4::{self, IPv4};
+use crate::address::mac::{self, Mac};
+use crate::assemblers;
+use crate::assemblers::ethernet::EtherType;
+use crate::io::messages::{PacketReceived, Resolve};
+use crate::io::network_io::SendPacket;
+use crate::parsers;
+use crate::parsers::udp::{self, PROTOCOL_UDP};
+use crate::Config;
+
+// Header lengths of the frames a socket sends
+const ETHERNET_HEADER_LEN: usize = 14;
+const IPV4_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+
+/// Largest payload that fits in one IPv4 UDP datagram
+pub const MAX_PAYLOAD_LEN: usize = u16::MAX as usize - IPV4_HEADER_LEN - UDP_HEADER_LEN;
+
+/// Default number of received datagrams a socket holds before dropping
+pub const DEFAULT_RECV_QUEUE_LEN: usize = 64;
+
+/// A datagram received for a bound port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datagram {
+    pub source: IPv4,
+    pub source_port: u16,
+    pub destination: IPv4,
+    pub data: Vec<u8>,
+}
+
+impl Message for Datagram {
+    type Result = ();
+}
+
+/// Demultiplexes inbound UDP datagrams to sockets by destination port.
+///
+/// Fragmented datagrams are dropped, since they are not reassembled here.
+#[derive(Default)]
+pub struct UdpDispatcher {
+    sockets: HashMap<u16, Recipient<Datagram>>,
+}
+
+impl UdpDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Actor for UdpDispatcher {
+    type Context = Context<Self>;
+}
+
+/// Message to bind `socket` to a local port. Fails with `AddrInUse` if
+/// another running socket holds the port. A port held by a socket that
+/// has stopped is taken over.
+pub struct Bind {
+    pub port: u16,
+    pub socket: Recipient<Datagram>,
+}
+
+impl Message for Bind {
+    type Result = IoResult<()>;
+}
+
+impl Handler<Bind> for UdpDispatcher {
+    type Result = IoResult<()>;
+
+    fn handle(&mut self, msg: Bind, _ctx: &mut Context<Self>) -> Self::Result {
+        if self.sockets.get(&msg.port).is_some_and(|socket| socket.connected()) {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("UDP port {} is already bound", msg.port),
+            ));
+        }
+        self.sockets.insert(msg.port, msg.socket);
+        Ok(())
+    }
+}
+
+/// Message to release a bound port.
+pub struct Unbind(pub u16);
+
+impl Message for Unbind {
+    type Result = ();
+}
+
+impl Handler<Unbind> for UdpDispatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: Unbind, _ctx: &mut Context<Self>) {
+        self.sockets.remove(&msg.0);
+    }
+}
+
+impl Handler<PacketReceived> for UdpDispatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: PacketReceived, _ctx: &mut Context<Self>) {
+        let Some((port, datagram)) = decode(&msg.data) else {
+            return;
+        };
+        match self.sockets.get(&port) {
+            Some(socket) => socket.do_send(datagram),
+            None => debug!("No socket bound to UDP port {}", port),
+        }
+    }
+}
+
+// Extract the destination port and datagram from an Ethernet frame carrying
+// an unfragmented IPv4 UDP datagram with a valid checksum
+fn decode(frame: &[u8]) -> Option<(u16, Datagram)> {
+    let frame = parsers::ethernet::EthernetFrame::new_with_validation(frame).ok()?;
+    if frame.ethertype() != parsers::ethernet::ETHERTYPE_IPV4 {
+        return None;
+    }
+    let packet = parsers::ipv4::IPv4Packet::new_with_validation(frame.payload()).ok()?;
+    if packet.protocol().ok()? != PROTOCOL_UDP
+        || packet.more_frags().ok()?
+        || packet.fragment_offset().ok()? != 0
+    {
+        return None;
+    }
+    let (source, destination) = (packet.source().ok()?, packet.destination().ok()?);
+    let datagram = udp::UdpDatagram::new_with_validation(packet.payload().ok()?).ok()?;
+    if !datagram.verify_checksum(&source, &destination) {
+        return None;
+    }
+    Some((
+        datagram.dest_port(),
+        Datagram {
+            source,
+            source_port: datagram.source_port(),
+            destination,
+            data: datagram.payload().to_vec(),
+        },
+    ))
+}
+
+/// A UDP socket bound to a local IPv4 address and port.
+///
+/// Received datagrams queue up until read with `RecvFrom`; once the queue
+/// is full, further datagrams are dropped. The destination
+/// MAC address of outgoing frames comes from the resolver, such as an ARP
+/// cache; without one, frames are sent to the broadcast MAC address.
+pub struct UdpSocket {
+    local: IPv4,
+    port: u16,
+    mac: Mac,
+    ttl: u8,
+    nic_index: usize,
+    network: Recipient<SendPacket>,
+    resolver: Option<Recipient<Resolve>>,
+    // Received datagrams waiting to be read, at most `queue_len` of them
+    queue: VecDeque<Datagram>,
+    queue_len: usize,
+    // IPv4 Identification of the next datagram sent
+    identification: u16,
+}
+
+impl UdpSocket {
+    /// Creates a socket for `local:port` sending through `network`, with
+    /// the MAC address and TTL from `config`.
+    pub fn new(local: IPv4, port: u16, network: Recipient<SendPacket>, config: &Config) -> Self {
+        Self {
+            local,
+            port,
+            mac: config.mac_address,
+            ttl: config.default_ttl,
+            nic_index: 0,
+            network,
+            resolver: None,
+            queue: VecDeque::new(),
+            queue_len: DEFAULT_RECV_QUEUE_LEN,
+            identification: 0,
+        }
+    }
+
+    /// Resolve destination MAC addresses with `resolver`.
+    pub fn with_resolver(mut self, resolver: Recipient<Resolve>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Send through the NIC at `nic_index` instead of the first.
+    pub fn with_nic_index(mut self, nic_index: usize) -> Self {
+        self.nic_index = nic_index;
+        self
+    }
+
+    /// Hold at most `queue_len` received datagrams instead of
+    /// `DEFAULT_RECV_QUEUE_LEN`.
+    pub fn with_queue_len(mut self, queue_len: usize) -> Self {
+        self.queue_len = queue_len;
+        self
+    }
+
+    /// Start the socket and bind its port on `dispatcher`.
+    pub async fn bind(self, dispatcher: &Addr<UdpDispatcher>) -> IoResult<Addr<UdpSocket>> {
+        let port = self.port;
+        let socket = self.start();
+        dispatcher
+            .send(Bind { port, socket: socket.clone().recipient() })
+            .await
+            .map_err(io::Error::other)??;
+        Ok(socket)
+    }
+
+    // Build an Ethernet frame carrying `data` to `destination:port`, with
+    // the destination MAC address left zeroed
+    fn build_frame(&mut self, data: &[u8], destination: IPv4, port: u16) -> IoResult<Vec<u8>> {
+        if data.len() > MAX_PAYLOAD_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("UDP payload of {} bytes exceeds {}", data.len(), MAX_PAYLOAD_LEN),
+            ));
+        }
+        let udp_len = UDP_HEADER_LEN + data.len();
+        let mut frame = vec![0u8; ETHERNET_HEADER_LEN + IPV4_HEADER_LEN + udp_len];
+
+        let mut ethernet = assemblers::ethernet::EthernetFrame::new(&mut frame);
+        ethernet.set_source(self.mac);
+        ethernet.set_ethertype(EtherType::Ipv4);
+
+        let (ip_header, udp_segment) = frame[ETHERNET_HEADER_LEN..].split_at_mut(IPV4_HEADER_LEN);
+        let mut datagram = assemblers::udp::UdpDatagram::new(udp_segment);
+        datagram.set_source_port(self.port);
+        datagram.set_destination_port(port);
+        datagram.set_length(udp_len as u16);
+        datagram.mut_payload_ref().copy_from_slice(data);
+        datagram.fill_checksum(&self.local, &destination);
+
+        let mut packet = assemblers::ipv4::IPv4Packet::new(ip_header);
+        packet.set_version(4);
+        packet.set_ihl(5);
+        packet.set_total_length((IPV4_HEADER_LEN + udp_len) as u16);
+        packet.set_identification(self.identification);
+        packet.set_ttl(self.ttl);
+        packet.set_protocol(PROTOCOL_UDP);
+        packet
+            .set_source(self.local)
+            .and_then(|_| packet.set_destination(destination))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        packet.fill_checksum();
+
+        self.identification = self.identification.wrapping_add(1);
+        Ok(frame)
+    }
+}
+
+impl Actor for UdpSocket {
+    type Context = Context<Self>;
+}
+
+impl Handler<Datagram> for UdpSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: Datagram, _ctx: &mut Context<Self>) {
+        // A socket bound to the unspecified address accepts any destination
+        if self.local != ipv4::UNSPECIFIED && msg.destination != self.local && !msg.destination.is_broadcast() {
+            return;
+        }
+        if self.queue.len() >= self.queue_len {
+            debug!("Receive queue of UDP port {} is full, dropping datagram", self.port);
+            return;
+        }
+        self.queue.push_back(msg);
+    }
+}
+
+/// Message to send `data` to `destination:port`.
+pub struct SendTo {
+    pub data: Vec<u8>,
+    pub destination: IPv4,
+    pub port: u16,
+}
+
+impl Message for SendTo {
+    type Result = IoResult<()>;
+}
+
+impl Handler<SendTo> for UdpSocket {
+    type Result = ResponseFuture<IoResult<()>>;
+
+    fn handle(&mut self, msg: SendTo, _ctx: &mut Context<Self>) -> Self::Result {
+        let frame = self.build_frame(&msg.data, msg.destination, msg.port);
+        let resolver = match msg.destination.is_broadcast() {
+            true => None,
+            false => self.resolver.clone(),
+        };
+        let network = self.network.clone();
+        let nic_index = self.nic_index;
+        Box::pin(async move {
+            let mut frame = frame?;
+            let mac = match resolver {
+                Some(resolver) => resolver
+                    .send(Resolve(msg.destination))
+                    .await
+                    .map_err(io::Error::other)?
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::HostUnreachable,
+                            format!("No MAC address known for {}", msg.destination),
+                        )
+                    })?,
+                None => mac::BROADCAST,
+            };
+            assemblers::ethernet::EthernetFrame::new(&mut frame).set_destination(mac);
+            network
+                .send(SendPacket { nic_index, data: frame })
+                .await
+                .map_err(io::Error::other)?
+        })
+    }
+}
+
+/// Message to take the oldest received datagram, if any.
+pub struct RecvFrom;
+
+impl Message for RecvFrom {
+    type Result = Option<Datagram>;
+}
+
+impl Handler<RecvFrom> for UdpSocket {
+    type Result = Option<Datagram>;
+
+    fn handle(&mut self, _msg: RecvFrom, _ctx: &mut Context<Self>) -> Self::Result {
+        self.queue.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::loopback::LoopbackNic;
+    use crate::io::network_io::NetworkIO;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    const LOCAL: IPv4 = IPv4::new(10, 0, 0, 1);
+
+    fn socket(port: u16) -> UdpSocket {
+        let network = NetworkIO::new(Arc::new(Mutex::new(LoopbackNic::new()))).start();
+        UdpSocket::new(LOCAL, port, network.recipient(), &Config::new())
+    }
+
+    #[actix_rt::test]
+    async fn test_built_frame_decodes() {
+        let mut socket = socket(4000);
+        let frame = socket.build_frame(b"hello", IPv4::new(10, 0, 0, 2), 53).unwrap();
+        let (port, datagram) = decode(&frame).unwrap();
+        assert_eq!(port, 53);
+        assert_eq!(datagram.source, LOCAL);
+        assert_eq!(datagram.source_port, 4000);
+        assert_eq!(datagram.data, b"hello");
+
+        // A corrupted payload fails the checksum
+        let mut corrupted = frame.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        assert!(decode(&corrupted).is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_bind_rejects_taken_port() {
+        let dispatcher = UdpDispatcher::new().start();
+        let _first = socket(4000).bind(&dispatcher).await.unwrap();
+        let error = socket(4000).bind(&dispatcher).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AddrInUse);
+    }
+
+    #[actix_rt::test]
+    async fn test_bind_takes_over_port_of_stopped_socket() {
+        use actix::ActorContext;
+
+        struct Stopped;
+        impl Actor for Stopped {
+            type Context = Context<Self>;
+            fn started(&mut self, ctx: &mut Context<Self>) {
+                ctx.stop();
+            }
+        }
+        impl Handler<Datagram> for Stopped {
+            type Result = ();
+            fn handle(&mut self, _msg: Datagram, _ctx: &mut Context<Self>) {}
+        }
+
+        let dispatcher = UdpDispatcher::new().start();
+        let stale = Stopped.start().recipient();
+        dispatcher.send(Bind { port: 4000, socket: stale }).await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(socket(4000).bind(&dispatcher).await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn test_full_queue_drops_datagrams() {
+        let socket = socket(4000).with_queue_len(2).start();
+        for byte in 0..3 {
+            let source = IPv4::new(10, 0, 0, 2);
+            socket.send(Datagram { source, source_port: 53, destination: LOCAL, data: vec![byte] }).await.unwrap();
+        }
+        assert_eq!(socket.send(RecvFrom).await.unwrap().unwrap().data, [0]);
+        assert_eq!(socket.send(RecvFrom).await.unwrap().unwrap().data, [1]);
+        assert_eq!(socket.send(RecvFrom).await.unwrap(), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_send_without_mac_fails() {
+        struct Unknown;
+        impl Actor for Unknown {
+            type Context = Context<Self>;
+        }
+        impl Handler<Resolve> for Unknown {
+            type Result = Option<Mac>;
+            fn handle(&mut self, _msg: Resolve, _ctx: &mut Context<Self>) -> Self::Result {
+                None
+            }
+        }
+
+        let socket = socket(4000).with_resolver(Unknown.start().recipient()).start();
+        let result = socket.send(SendTo { data: vec![1], destination: IPv4::new(10, 0, 0, 2), port: 53 }).await;
+        assert_eq!(result.unwrap().unwrap_err().kind(), io::ErrorKind::HostUnreachable);
+    }
+}
@@ -47,7 +47,7 @@
 //!
 //! # Minimum Supported Rust Version (MSRV)
 //!
-//! Thunda is guaranteed to compile on stable Rust 1.56 and up, ensuring compatibility with a wide
+//! Thunda is guaranteed to compile on stable Rust 1.83 and up, ensuring compatibility with a wide
 //! range of Rust versions and dependencies. It might compile with older versions, but this is not
 //! guaranteed in future patch releases.
 //!
@@ -59,13 +59,25 @@
 #[cfg(feature = "log")]
 extern crate log;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// Addresses and parsers build without std. Everything that needs the
+// actor runtime or the operating system requires the `std` feature.
+#[cfg(feature = "std")]
 mod config;
-pub use config::Config;
+#[cfg(feature = "std")]
+pub use config::{Config, ConfigBuilder, ConfigError};
 
 
+#[cfg(feature = "std")]
 pub mod iface;
+#[cfg(feature = "std")]
 pub mod io;
+#[cfg(feature = "std")]
 pub mod protocols;
 pub mod address;
 pub mod parsers;
+#[cfg(feature = "std")]
 pub mod assemblers;
+pub mod utils;
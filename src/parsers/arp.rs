@@ -1,16 +1,20 @@
 // src/parsers/arp.rs
-use crate::parsers::ParsingError;
+use crate::address::ipv4::IPv4;
+use crate::address::mac::Mac;
+use crate::parsers::{ParsingError, ValidationError};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Hardware {
     Ethernet = 1,
 }
 
-impl From<u16> for Hardware {
-    fn from(value: u16) -> Hardware {
+impl TryFrom<u16> for Hardware {
+    type Error = ParsingError;
+
+    fn try_from(value: u16) -> Result<Hardware, ParsingError> {
         match value {
-            1 => Hardware::Ethernet,
-            _ => panic!("Unsupported hardware type")
+            1 => Ok(Hardware::Ethernet),
+            _ => Err(ParsingError::UnsupportedHardwareType(value)),
         }
     }
 }
@@ -21,17 +25,23 @@ pub enum Operation {
     Reply = 2,
 }
 
-impl From<u16> for Operation {
-    fn from(value: u16) -> Self {
+impl TryFrom<u16> for Operation {
+    type Error = ParsingError;
+
+    fn try_from(value: u16) -> Result<Self, ParsingError> {
         match value {
-            1 => Operation::Request,
-            2 => Operation::Reply,
-            _ => panic!("Unsupported operation type"),
+            1 => Ok(Operation::Request),
+            2 => Ok(Operation::Reply),
+            _ => Err(ParsingError::UnsupportedArpOperation(value)),
         }
     }
 }
 
 /// Represents an ARP packet
+///
+/// Accessors never panic. On a packet built with `new` that is too short,
+/// missing fields read as zero and address slices come back short, while
+/// the typed address accessors return `BufferUnderflow`.
 pub struct ArpPacket<'a> {
     buffer: &'a [u8],
 }
@@ -54,70 +64,166 @@ impl<'a> ArpPacket<'a> {
 
     /// Return the hardware type
     pub fn hardware_type(&self) -> u16 {
-        u16::from_be_bytes([self.buffer[0], self.buffer[1]])
+        self.read_u16(0)
+    }
+
+    /// Return the hardware type, if it is one we support
+    pub fn hardware(&self) -> Result<Hardware, ParsingError> {
+        Hardware::try_from(self.hardware_type())
     }
 
     /// Return the protocol type
     pub fn protocol_type(&self) -> u16 {
-        u16::from_be_bytes([self.buffer[2], self.buffer[3]])
+        self.read_u16(2)
     }
 
     /// Returns the hardware address length.
     pub fn hardware_address_length(&self) -> u8 {
-        self.buffer[4]
+        self.buffer.get(4).copied().unwrap_or(0)
     }
 
     /// Returns the protocol address length.
     pub fn protocol_address_length(&self) -> u8 {
-        self.buffer[5]
+        self.buffer.get(5).copied().unwrap_or(0)
     }
 
     /// Returns the operation (1 for request, 2 for reply).
     pub fn operation(&self) -> u16 {
-        u16::from_be_bytes([self.buffer[6], self.buffer[7]])
+        self.read_u16(6)
+    }
+
+    /// Returns the operation, if it is a request or a reply.
+    pub fn operation_type(&self) -> Result<Operation, ParsingError> {
+        Operation::try_from(self.operation())
     }
 
     /// Returns the sender hardware address (MAC address).
     pub fn sender_hardware_address(&self) -> &[u8] {
-        &self.buffer[8..14]
+        self.field(8, 14)
     }
 
     /// Returns the sender protocol address (IP address).
     pub fn sender_protocol_address(&self) -> &[u8] {
-        &self.buffer[14..18]
+        self.field(14, 18)
     }
 
     /// Returns the target hardware address (MAC address).
     pub fn target_hardware_address(&self) -> &[u8] {
-        &self.buffer[18..24]
+        self.field(18, 24)
     }
 
     /// Returns the target protocol address (IP address).
     pub fn target_protocol_address(&self) -> &[u8] {
-        &self.buffer[24..28]
+        self.field(24, 28)
+    }
+
+    /// Returns the sender MAC address of an Ethernet/IPv4 packet.
+    pub fn sender_mac(&self) -> Result<Mac, ParsingError> {
+        self.check_ethernet_ipv4()?;
+        Ok(to_mac(self.sender_hardware_address()))
+    }
+
+    /// Returns the sender IPv4 address of an Ethernet/IPv4 packet.
+    pub fn sender_ip(&self) -> Result<IPv4, ParsingError> {
+        self.check_ethernet_ipv4()?;
+        Ok(to_ipv4(self.sender_protocol_address()))
+    }
+
+    /// Returns the target MAC address of an Ethernet/IPv4 packet.
+    pub fn target_mac(&self) -> Result<Mac, ParsingError> {
+        self.check_ethernet_ipv4()?;
+        Ok(to_mac(self.target_hardware_address()))
+    }
+
+    /// Returns the target IPv4 address of an Ethernet/IPv4 packet.
+    pub fn target_ip(&self) -> Result<IPv4, ParsingError> {
+        self.check_ethernet_ipv4()?;
+        Ok(to_ipv4(self.target_protocol_address()))
+    }
+
+    /// Returns true if the sender announces its own address, i.e. the
+    /// sender and target IP addresses are equal.
+    pub fn is_gratuitous(&self) -> bool {
+        match (self.sender_ip(), self.target_ip()) {
+            (Ok(sender), Ok(target)) => !sender.is_unspecified() && sender == target,
+            _ => false,
+        }
     }
+
+    /// Returns true for an ARP Probe (RFC 5227, section 2.1.1): a request
+    /// with an all-zero sender IP address asking about a valid target.
+    pub fn is_probe(&self) -> bool {
+        match (self.operation_type(), self.sender_ip(), self.target_ip()) {
+            (Ok(Operation::Request), Ok(sender), Ok(target)) => {
+                sender.is_unspecified() && !target.is_unspecified() && !target.is_broadcast()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true for an ARP Announcement (RFC 5227, section 2.3): a
+    /// gratuitous request.
+    pub fn is_announcement(&self) -> bool {
+        self.operation_type() == Ok(Operation::Request) && self.is_gratuitous()
+    }
+
+    // The fixed address offsets only hold for 6-byte hardware and 4-byte
+    // protocol addresses
+    fn check_ethernet_ipv4(&self) -> Result<(), ParsingError> {
+        if self.buffer.len() < 28 {
+            return Err(ParsingError::BufferUnderflow);
+        }
+        if self.hardware_address_length() != 6 || self.protocol_address_length() != 4 {
+            return Err(ValidationError::InvalidAddressLength.into());
+        }
+        Ok(())
+    }
+
+    // Read a big-endian u16 at `offset`, or zero past the end
+    fn read_u16(&self, offset: usize) -> u16 {
+        match self.buffer.get(offset..offset + 2) {
+            Some(&[high, low]) => u16::from_be_bytes([high, low]),
+            _ => 0,
+        }
+    }
+
+    // Return the bytes from `start` to `end`, cut short at the end of the
+    // buffer
+    fn field(&self, start: usize, end: usize) -> &[u8] {
+        self.buffer.get(start..end.min(self.buffer.len())).unwrap_or(&[])
+    }
+}
+
+fn to_mac(bytes: &[u8]) -> Mac {
+    let mut octets = [0u8; 6];
+    octets.copy_from_slice(bytes);
+    Mac(octets)
+}
+
+fn to_ipv4(bytes: &[u8]) -> IPv4 {
+    IPv4::new(bytes[0], bytes[1], bytes[2], bytes[3])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Example ARP request packet data (truncated for simplicity)
+    static REQUEST: [u8; 28] = [
+        0x00, 0x01, // Hardware type (Ethernet)
+        0x08, 0x00, // Protocol type (IPv4)
+        0x06,       // Hardware address length
+        0x04,       // Protocol address length
+        0x00, 0x01, // Operation (request)
+        0xde, 0xad, 0xbe, 0xef, 0xde, 0xad, // Sender hardware address
+        0xc0, 0xa8, 0x01, 0x01,             // Sender protocol address
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Target hardware address (unknown for request)
+        0xc0, 0xa8, 0x01, 0x02,             // Target protocol address
+    ];
+
     #[test]
     fn test_arp_packet_parsing() {
-        // Example ARP request packet data (truncated for simplicity)
-        let data: [u8; 28] = [
-            0x00, 0x01, // Hardware type (Ethernet)
-            0x08, 0x00, // Protocol type (IPv4)
-            0x06,       // Hardware address length
-            0x04,       // Protocol address length
-            0x00, 0x01, // Operation (request)
-            0xde, 0xad, 0xbe, 0xef, 0xde, 0xad, // Sender hardware address
-            0xc0, 0xa8, 0x01, 0x01,             // Sender protocol address
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Target hardware address (unknown for request)
-            0xc0, 0xa8, 0x01, 0x02,             // Target protocol address
-        ];
-
-        let packet = ArpPacket::new_with_validation(&data).unwrap();
+        let packet = ArpPacket::new_with_validation(&REQUEST).unwrap();
 
         assert_eq!(packet.hardware_type(), 0x0001);
         assert_eq!(packet.protocol_type(), 0x0800);
@@ -129,4 +235,83 @@ mod tests {
         assert_eq!(packet.target_hardware_address(), &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
         assert_eq!(packet.target_protocol_address(), &[0xc0, 0xa8, 0x01, 0x02]);
     }
+
+    #[test]
+    fn test_typed_operation_and_hardware() {
+        let packet = ArpPacket::new_with_validation(&REQUEST).unwrap();
+        assert_eq!(packet.operation_type(), Ok(Operation::Request));
+        assert_eq!(packet.hardware(), Ok(Hardware::Ethernet));
+    }
+
+    #[test]
+    fn test_unsupported_operation_is_an_error() {
+        let mut data = REQUEST;
+        data[7] = 5;
+        data[1] = 6; // IEEE 802
+        let packet = ArpPacket::new_with_validation(&data).unwrap();
+        assert_eq!(packet.operation_type(), Err(ParsingError::UnsupportedArpOperation(5)));
+        assert_eq!(packet.hardware(), Err(ParsingError::UnsupportedHardwareType(6)));
+        assert_eq!(Operation::try_from(5), Err(ParsingError::UnsupportedArpOperation(5)));
+    }
+
+    #[test]
+    fn test_typed_addresses() {
+        let packet = ArpPacket::new_with_validation(&REQUEST).unwrap();
+        assert_eq!(packet.sender_mac().unwrap().to_string(), "de:ad:be:ef:de:ad");
+        assert_eq!(packet.sender_ip().unwrap(), IPv4::new(192, 168, 1, 1));
+        assert_eq!(packet.target_mac().unwrap(), Mac([0; 6]));
+        assert_eq!(packet.target_ip().unwrap(), IPv4::new(192, 168, 1, 2));
+    }
+
+    #[test]
+    fn test_gratuitous_reply() {
+        let mut data = REQUEST;
+        data[7] = 2; // Reply
+        data[18..24].copy_from_slice(&[0xff; 6]);
+        data[24..28].copy_from_slice(&[0xc0, 0xa8, 0x01, 0x01]);
+        let packet = ArpPacket::new_with_validation(&data).unwrap();
+        assert!(packet.is_gratuitous());
+        assert!(!packet.is_announcement());
+        assert!(!packet.is_probe());
+
+        data[7] = 1; // The same as a request is an announcement
+        let packet = ArpPacket::new_with_validation(&data).unwrap();
+        assert!(packet.is_announcement());
+    }
+
+    #[test]
+    fn test_probe() {
+        let mut data = REQUEST;
+        data[14..18].copy_from_slice(&[0, 0, 0, 0]);
+        let packet = ArpPacket::new_with_validation(&data).unwrap();
+        assert!(packet.is_probe());
+        assert!(!packet.is_gratuitous());
+        assert!(!packet.is_announcement());
+    }
+
+    #[test]
+    fn test_ordinary_request() {
+        let packet = ArpPacket::new_with_validation(&REQUEST).unwrap();
+        assert!(!packet.is_gratuitous() && !packet.is_probe() && !packet.is_announcement());
+    }
+
+    #[test]
+    fn test_typed_addresses_reject_other_lengths() {
+        let mut data = REQUEST;
+        data[5] = 16; // IPv6-sized protocol addresses
+        let packet = ArpPacket::new(&data);
+        let expected = Err(ParsingError::ValidationError(ValidationError::InvalidAddressLength));
+        assert_eq!(packet.sender_ip(), expected);
+        assert_eq!(packet.sender_mac().err(), expected.err());
+    }
+
+    #[test]
+    fn test_short_packet_does_not_panic() {
+        let packet = ArpPacket::new(&REQUEST[..10]);
+        assert_eq!(packet.operation(), 1);
+        assert_eq!(packet.sender_hardware_address(), &[0xde, 0xad]);
+        assert_eq!(packet.target_protocol_address(), &[] as &[u8]);
+        assert_eq!(packet.sender_mac(), Err(ParsingError::BufferUnderflow));
+        assert_eq!(ArpPacket::new(&[]).hardware_type(), 0);
+    }
 }
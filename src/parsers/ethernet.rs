@@ -1,17 +1,84 @@
 // src/parsers/ethernet.rs
+use crate::address::mac::Mac;
 use crate::parsers::ParsingError;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 /// EtherType
 ///
 /// https://en.wikipedia.org/wiki/EtherType
 pub const ETHERTYPE_IPV4: u16 = 0x0800;
 pub const ETHERTYPE_ARP: u16 = 0x0806;
 pub const ETHERTYPE_IPV6: u16 = 0x86DD;
+/// IEEE 802.1Q VLAN tag
+pub const ETHERTYPE_VLAN: u16 = 0x8100;
+/// IEEE 802.1ad (QinQ) service VLAN tag
+pub const ETHERTYPE_QINQ: u16 = 0x88A8;
 
 
 pub const ETHER_MIN_LENGTH: usize = 14;
 
+/// Represents the Ethernet frame Ethertype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EtherType {
+    Ipv4 = ETHERTYPE_IPV4 as isize,
+    Arp = ETHERTYPE_ARP as isize,
+    Ipv6 = ETHERTYPE_IPV6 as isize,
+}
+
+impl TryFrom<u16> for EtherType {
+    type Error = ParsingError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            ETHERTYPE_IPV4 => Ok(EtherType::Ipv4),
+            ETHERTYPE_ARP => Ok(EtherType::Arp),
+            ETHERTYPE_IPV6 => Ok(EtherType::Ipv6),
+            _ => Err(ParsingError::UnsupportedEthertype),
+        }
+    }
+}
+
+/// Largest value of the type field that is an 802.3 payload length
+/// rather than an Ethertype.
+pub const ETHER_MAX_LENGTH_FIELD: u16 = 1500;
+
+/// Classification of a frame by its outer type field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EtherClass {
+    Ipv4,
+    Ipv6,
+    Arp,
+    /// 802.1Q or 802.1ad tagged, with the VLAN identifier of the outer tag
+    Vlan(u16),
+    /// IEEE 802.3 frame, with the payload length from the type field
+    Length(u16),
+    /// Any other Ethertype
+    Unknown(u16),
+}
+
+// Length of the preamble (7 x 0x55) plus the Start Frame Delimiter
+const PREAMBLE_LEN: usize = 8;
+const SFD: u8 = 0xD5;
+
+/// Return `buf` past a leading preamble and Start Frame Delimiter, or
+/// `buf` unchanged if it does not start with one.
+///
+/// Some capture formats include the preamble, which would otherwise be
+/// misread as the destination and source addresses.
+pub fn strip_preamble(buf: &[u8]) -> &[u8] {
+    match buf.get(..PREAMBLE_LEN) {
+        Some([0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, SFD]) => &buf[PREAMBLE_LEN..],
+        _ => buf,
+    }
+}
+
 /// Represents an Ethernet Frame
+///
+/// Accessors never panic. On a frame built with `new` that is too short,
+/// address slices come back short and missing fields read as zero; use
+/// `new_with_validation` to reject such frames up front.
 pub struct EthernetFrame<'a> {
     buffer: &'a [u8],
 }
@@ -19,9 +86,11 @@ pub struct EthernetFrame<'a> {
 impl<'a> EthernetFrame<'a> {
     // Constant representing the minimum Ethernet frame size
     const MIN_FRAME_SIZE: usize = 14; // 6 (Dest) + 6 (Source) + 2 (Ethertype)
+    // Length of an 802.1Q tag (TPID + TCI)
+    const VLAN_TAG_SIZE: usize = 4;
 
     /// Constructs a new `EthernetFrame` from a raw octect buffer
-    pub fn new(buffer: &'a [u8]) -> EthernetFrame {
+    pub fn new(buffer: &'a [u8]) -> EthernetFrame<'a> {
         EthernetFrame { buffer }
     }
 
@@ -29,30 +98,150 @@ impl<'a> EthernetFrame<'a> {
     // Constructor with validation
     pub fn new_with_validation(buffer: &'a [u8]) -> Result<EthernetFrame<'a>, ParsingError> {
         if buffer.len() < Self::MIN_FRAME_SIZE {
-            Err(ParsingError::BufferUnderflow)
-        } else {
-            Ok(EthernetFrame { buffer })
+            return Err(ParsingError::BufferUnderflow);
         }
+        let frame = EthernetFrame { buffer };
+        if buffer.len() < frame.payload_offset() {
+            return Err(ParsingError::BufferUnderflow);
+        }
+        Ok(frame)
     }
 
     // Return the destination MAC address
     pub fn destination(&self) -> &[u8] {
-        &self.buffer[0..6]
+        self.field(0..6)
     }
 
     // Return the source MAC address
     pub fn source(&self) -> &[u8] {
-        &self.buffer[6..12]
+        self.field(6..12)
+    }
+
+    /// Return the destination MAC address as a `Mac`
+    pub fn destination_mac(&self) -> Mac {
+        Self::to_mac(self.destination())
     }
 
-    // Return the Ethertype
+    /// Return the source MAC address as a `Mac`
+    pub fn source_mac(&self) -> Mac {
+        Self::to_mac(self.source())
+    }
+
+    // Return the Ethertype, looking past any VLAN tags
     pub fn ethertype(&self) -> u16 {
-        u16::from_be_bytes([self.buffer[12], self.buffer[13]])
+        self.read_u16(self.payload_offset() - 2).unwrap_or(0)
+    }
+
+    /// Return the Ethertype as an `EtherType`, or `None` if it is not one
+    /// we support.
+    pub fn ethertype_parsed(&self) -> Option<EtherType> {
+        EtherType::try_from(self.ethertype()).ok()
+    }
+
+    /// Classify the frame by its outer type field, so a dispatcher can
+    /// match on a single value. Tagged frames report `Vlan`; use
+    /// `ethertype()` to see past the tags.
+    pub fn classify(&self) -> EtherClass {
+        let outer = self.read_u16(12).unwrap_or(0);
+        match outer {
+            ETHERTYPE_VLAN | ETHERTYPE_QINQ => EtherClass::Vlan(self.vlan_id().unwrap_or(0)),
+            other => Self::class_of(other),
+        }
+    }
+
+    /// Classify the frame by the Ethertype past any VLAN tags. Never
+    /// returns `Vlan`.
+    pub fn inner_class(&self) -> EtherClass {
+        Self::class_of(self.ethertype())
+    }
+
+    // Classify an untagged type field
+    fn class_of(type_field: u16) -> EtherClass {
+        match type_field {
+            ETHERTYPE_IPV4 => EtherClass::Ipv4,
+            ETHERTYPE_IPV6 => EtherClass::Ipv6,
+            ETHERTYPE_ARP => EtherClass::Arp,
+            len if len <= ETHER_MAX_LENGTH_FIELD => EtherClass::Length(len),
+            other => EtherClass::Unknown(other),
+        }
+    }
+
+    // Query if the frame carries at least one 802.1Q or 802.1ad tag
+    pub fn is_vlan_tagged(&self) -> bool {
+        self.tag_count() > 0
+    }
+
+    /// Return the 12-bit VLAN identifier of the outermost tag.
+    pub fn vlan_id(&self) -> Option<u16> {
+        self.tci().map(|tci| tci & 0x0FFF)
+    }
+
+    /// Return the 3-bit Priority Code Point of the outermost tag.
+    pub fn vlan_priority(&self) -> Option<u8> {
+        self.tci().map(|tci| (tci >> 13) as u8)
+    }
+
+    /// Return the VLAN identifiers of all tags, from outer to inner.
+    #[cfg(feature = "alloc")]
+    pub fn vlan_stack(&self) -> Vec<u16> {
+        (0..self.tag_count())
+            .map(|i| self.read_u16(14 + i * Self::VLAN_TAG_SIZE).unwrap_or(0) & 0x0FFF)
+            .collect()
+    }
+
+    // Return the Tag Control Information of the outermost tag
+    fn tci(&self) -> Option<u16> {
+        if self.is_vlan_tagged() {
+            self.read_u16(14)
+        } else {
+            None
+        }
+    }
+
+    // Return the bytes in `range`, cut short at the end of the buffer
+    fn field(&self, range: core::ops::Range<usize>) -> &[u8] {
+        let end = range.end.min(self.buffer.len());
+        self.buffer.get(range.start..end).unwrap_or(&[])
+    }
+
+    // Read a big-endian u16 at `offset`, if the buffer holds it
+    fn read_u16(&self, offset: usize) -> Option<u16> {
+        match self.buffer.get(offset..offset + 2) {
+            Some(&[high, low]) => Some(u16::from_be_bytes([high, low])),
+            _ => None,
+        }
+    }
+
+    // Build a `Mac` from up to six bytes, zero-filling any missing ones
+    fn to_mac(bytes: &[u8]) -> Mac {
+        let mut octets = [0u8; 6];
+        octets[..bytes.len()].copy_from_slice(bytes);
+        Mac(octets)
+    }
+
+    // Count the VLAN tags preceding the Ethertype
+    fn tag_count(&self) -> usize {
+        let mut count = 0;
+        let mut offset = 12;
+        while offset + 2 <= self.buffer.len() {
+            let tpid = u16::from_be_bytes([self.buffer[offset], self.buffer[offset + 1]]);
+            if tpid != ETHERTYPE_VLAN && tpid != ETHERTYPE_QINQ {
+                break;
+            }
+            count += 1;
+            offset += Self::VLAN_TAG_SIZE;
+        }
+        count
     }
 
     // Return a reference to the frame's payload.
     pub fn payload(&self) -> &'a [u8] {
-        &self.buffer[Self::header_length()..]
+        self.buffer.get(self.payload_offset()..).unwrap_or(&[])
+    }
+
+    // Offset of the payload, accounting for any VLAN tags
+    fn payload_offset(&self) -> usize {
+        Self::header_length() + self.tag_count() * Self::VLAN_TAG_SIZE
     }
 
     // Return the header length
@@ -89,6 +278,147 @@ mod tests {
         assert_eq!(frame.source(), &[0x11, 0x12, 0x13, 0x14, 0x15, 0x16]);
         assert_eq!(frame.ethertype(), 0x0800); // IPv4 in hex
         assert_eq!(frame.payload(), &FRAME_BYTES[14..64]); // Payload comparison
+        assert!(!frame.is_vlan_tagged());
+        assert_eq!(frame.vlan_id(), None);
+        assert_eq!(frame.vlan_priority(), None);
+    }
+
+    #[test]
+    fn test_typed_mac_accessors() {
+        let frame = EthernetFrame::new_with_validation(&FRAME_BYTES).expect("Valid frame");
+        assert_eq!(frame.source_mac(), Mac::new(0x11, 0x12, 0x13, 0x14, 0x15, 0x16));
+        assert_eq!(frame.destination_mac(), Mac::new(0x01, 0x02, 0x03, 0x04, 0x05, 0x06));
+    }
+
+    #[test]
+    fn test_ethertype_try_from() {
+        assert_eq!(EtherType::try_from(0x0800), Ok(EtherType::Ipv4));
+        assert_eq!(EtherType::try_from(0x0806), Ok(EtherType::Arp));
+        assert_eq!(EtherType::try_from(0x86DD), Ok(EtherType::Ipv6));
+        assert_eq!(EtherType::try_from(0x1234), Err(ParsingError::UnsupportedEthertype));
+        assert_eq!(EtherType::Ipv6 as u16, ETHERTYPE_IPV6);
+    }
+
+    #[test]
+    fn test_ethertype_parsed() {
+        assert_eq!(EthernetFrame::new(&FRAME_BYTES).ethertype_parsed(), Some(EtherType::Ipv4));
+        let mut unknown = FRAME_BYTES;
+        unknown[12..14].copy_from_slice(&[0x12, 0x34]);
+        assert_eq!(EthernetFrame::new(&unknown).ethertype_parsed(), None);
+    }
+
+    #[test]
+    fn test_strip_preamble() {
+        let mut captured = vec![0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0xd5];
+        captured.extend_from_slice(&FRAME_BYTES);
+        let frame = EthernetFrame::new_with_validation(strip_preamble(&captured)).expect("Valid frame");
+        assert_eq!(frame.destination(), &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(frame.ethertype(), ETHERTYPE_IPV4);
+    }
+
+    #[test]
+    fn test_strip_preamble_absent() {
+        assert_eq!(strip_preamble(&FRAME_BYTES), &FRAME_BYTES[..]);
+        // Preamble without a Start Frame Delimiter is left alone
+        let no_sfd = [0x55u8; 8];
+        assert_eq!(strip_preamble(&no_sfd), &no_sfd[..]);
+        assert_eq!(strip_preamble(&[0x55, 0xd5]), &[0x55, 0xd5]);
+    }
+
+    static TAGGED_FRAME_BYTES: [u8; 22] = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, // Destination MAC
+        0x11, 0x12, 0x13, 0x14, 0x15, 0x16, // Source MAC
+        0x81, 0x00, // TPID (802.1Q)
+        0xa0, 0x64, // TCI: PCP 5, DEI 0, VID 100
+        0x08, 0x00, // Ethertype (IPv4)
+        0x45, 0x00, 0x00, 0x14, // Start of payload
+    ];
+
+    #[test]
+    fn test_deconstruct_vlan_tagged() {
+        let frame = EthernetFrame::new_with_validation(&TAGGED_FRAME_BYTES).expect("Valid frame");
+        assert!(frame.is_vlan_tagged());
+        assert_eq!(frame.vlan_id(), Some(100));
+        assert_eq!(frame.vlan_priority(), Some(5));
+        assert_eq!(frame.ethertype(), ETHERTYPE_IPV4);
+        assert_eq!(frame.payload(), &TAGGED_FRAME_BYTES[18..]);
+        assert_eq!(frame.source(), &[0x11, 0x12, 0x13, 0x14, 0x15, 0x16]);
+    }
+
+    #[test]
+    fn test_truncated_vlan_tag() {
+        assert_eq!(
+            EthernetFrame::new_with_validation(&TAGGED_FRAME_BYTES[..16]).err(),
+            Some(ParsingError::BufferUnderflow)
+        );
+    }
+
+    static QINQ_FRAME_BYTES: [u8; 26] = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, // Destination MAC
+        0x11, 0x12, 0x13, 0x14, 0x15, 0x16, // Source MAC
+        0x88, 0xa8, // TPID (802.1ad)
+        0x00, 0x64, // TCI: VID 100
+        0x81, 0x00, // TPID (802.1Q)
+        0x00, 0xc8, // TCI: VID 200
+        0x86, 0xdd, // Ethertype (IPv6)
+        0x60, 0x00, 0x00, 0x00, // Start of payload
+    ];
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_deconstruct_qinq() {
+        let frame = EthernetFrame::new_with_validation(&QINQ_FRAME_BYTES).expect("Valid frame");
+        assert_eq!(frame.vlan_stack(), vec![100, 200]);
+        assert_eq!(frame.vlan_id(), Some(100));
+        assert_eq!(frame.ethertype(), ETHERTYPE_IPV6);
+        assert_eq!(frame.payload(), &QINQ_FRAME_BYTES[22..]);
+    }
+
+    #[test]
+    fn test_classify() {
+        let with_type = |ethertype: u16| {
+            let mut bytes = FRAME_BYTES;
+            bytes[12..14].copy_from_slice(&ethertype.to_be_bytes());
+            EthernetFrame::new(&bytes).classify()
+        };
+        assert_eq!(with_type(ETHERTYPE_IPV4), EtherClass::Ipv4);
+        assert_eq!(with_type(ETHERTYPE_IPV6), EtherClass::Ipv6);
+        assert_eq!(with_type(ETHERTYPE_ARP), EtherClass::Arp);
+        // 802.3 frame carrying 50 bytes of LLC payload
+        assert_eq!(with_type(50), EtherClass::Length(50));
+        assert_eq!(with_type(1500), EtherClass::Length(1500));
+        assert_eq!(with_type(1501), EtherClass::Unknown(1501));
+        assert_eq!(with_type(0x88cc), EtherClass::Unknown(0x88cc));
+    }
+
+    #[test]
+    fn test_classify_tagged() {
+        assert_eq!(EthernetFrame::new(&TAGGED_FRAME_BYTES).classify(), EtherClass::Vlan(100));
+        assert_eq!(EthernetFrame::new(&QINQ_FRAME_BYTES).classify(), EtherClass::Vlan(100));
+        assert_eq!(EthernetFrame::new(&TAGGED_FRAME_BYTES).inner_class(), EtherClass::Ipv4);
+        assert_eq!(EthernetFrame::new(&QINQ_FRAME_BYTES).inner_class(), EtherClass::Ipv6);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_vlan_stack_single_and_untagged() {
+        assert_eq!(EthernetFrame::new(&TAGGED_FRAME_BYTES).vlan_stack(), vec![100]);
+        assert!(EthernetFrame::new(&FRAME_BYTES).vlan_stack().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_short_frame_does_not_panic() {
+        let frame = EthernetFrame::new(&FRAME_BYTES[..9]);
+        assert_eq!(frame.source(), &[0x11, 0x12, 0x13]);
+        assert_eq!(frame.source_mac(), Mac([0x11, 0x12, 0x13, 0, 0, 0]));
+        assert_eq!(frame.ethertype(), 0);
+        assert!(frame.payload().is_empty());
+
+        // A VLAN TPID with its TCI cut off
+        let tagged = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0x81, 0x00, 0x00];
+        let frame = EthernetFrame::new(&tagged);
+        assert_eq!(frame.vlan_id(), None);
+        assert_eq!(frame.vlan_stack(), vec![0]);
+    }
 }
@@ -0,0 +1,166 @@
+// src/parsers/icmp4.rs
+use crate::utils::checksum;
+use super::ipv4::IPv4Packet;
+use super::ParsingError;
+
+/// IPv4 Protocol value identifying ICMP
+pub const PROTOCOL_ICMP: u8 = 1;
+
+/// ICMP message types
+pub const TYPE_ECHO_REPLY: u8 = 0;
+pub const TYPE_DEST_UNREACHABLE: u8 = 3;
+pub const TYPE_ECHO_REQUEST: u8 = 8;
+
+/// Internet Control Message Protocol
+///
+/// [RFC 792]: https://datatracker.ietf.org/doc/html/rfc792
+
+//  0                   1                   2                   3
+//  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |     Type      |     Code      |          Checksum             |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                    Rest of Header (type specific)             |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |     Data ...
+// +-+-+-+-+-
+#[derive(Debug, Clone)]
+pub struct IcmpPacket<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> IcmpPacket<'a> {
+    // Type, Code, Checksum and the type-specific word
+    const HEADER_LEN: usize = 8;
+
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    pub fn new_with_validation(buffer: &'a [u8]) -> Result<Self, ParsingError> {
+        if buffer.len() < Self::HEADER_LEN {
+            Err(ParsingError::BufferUnderflow)
+        } else {
+            Ok(Self { buffer })
+        }
+    }
+
+    /// Return the Type
+    pub fn msg_type(&self) -> u8 {
+        self.buffer[0]
+    }
+
+    /// Return the Code
+    pub fn code(&self) -> u8 {
+        self.buffer[1]
+    }
+
+    /// Return the Checksum
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[2], self.buffer[3]])
+    }
+
+    /// Query if the message is an Echo Request or Echo Reply
+    pub fn is_echo(&self) -> bool {
+        matches!(self.msg_type(), TYPE_ECHO_REQUEST | TYPE_ECHO_REPLY)
+    }
+
+    /// Return the Identifier of an Echo Request or Reply
+    pub fn identifier(&self) -> Option<u16> {
+        self.is_echo().then(|| u16::from_be_bytes([self.buffer[4], self.buffer[5]]))
+    }
+
+    /// Return the Sequence Number of an Echo Request or Reply
+    pub fn sequence(&self) -> Option<u16> {
+        self.is_echo().then(|| u16::from_be_bytes([self.buffer[6], self.buffer[7]]))
+    }
+
+    /// Return the data of an Echo Request or Reply
+    pub fn echo_payload(&self) -> Option<&'a [u8]> {
+        self.is_echo().then(|| &self.buffer[Self::HEADER_LEN..])
+    }
+
+    /// Return the header of the datagram that could not be delivered,
+    /// carried in a Destination Unreachable message along with the first
+    /// 8 octets of its payload.
+    ///
+    /// The embedded datagram is truncated by design, so only its header
+    /// length is checked, not its Total Length.
+    pub fn unreachable_header(&self) -> Option<IPv4Packet<'a>> {
+        if self.msg_type() != TYPE_DEST_UNREACHABLE {
+            return None;
+        }
+        let embedded = &self.buffer[Self::HEADER_LEN..];
+        if embedded.len() < 20 {
+            return None;
+        }
+        let packet = IPv4Packet::new(embedded);
+        let ihl = packet.ihl().ok()? as usize;
+        if ihl < 20 || ihl > embedded.len() {
+            return None;
+        }
+        Some(packet)
+    }
+
+    /// Verify the checksum over the whole ICMP message
+    pub fn verify_checksum(&self) -> bool {
+        checksum::checksum(self.buffer, 0) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::ipv4::IPv4;
+
+    // Echo Request, id 0x1234, seq 1, data "ping"
+    static ECHO_REQUEST: [u8; 12] = [
+        0x08, 0x00, 0x06, 0xfa, // Type, Code, Checksum
+        0x12, 0x34, 0x00, 0x01, // Identifier, Sequence Number
+        b'p', b'i', b'n', b'g', // Data
+    ];
+
+    #[test]
+    fn test_parse_echo_request() {
+        let packet = IcmpPacket::new_with_validation(&ECHO_REQUEST).unwrap();
+        assert_eq!(packet.msg_type(), TYPE_ECHO_REQUEST);
+        assert_eq!(packet.code(), 0);
+        assert_eq!(packet.checksum(), 0x06fa);
+        assert_eq!(packet.identifier(), Some(0x1234));
+        assert_eq!(packet.sequence(), Some(1));
+        assert_eq!(packet.echo_payload(), Some(&b"ping"[..]));
+        assert!(packet.unreachable_header().is_none());
+        assert!(packet.verify_checksum());
+    }
+
+    #[test]
+    fn test_incorrect_checksum() {
+        let mut bytes = ECHO_REQUEST;
+        bytes[11] ^= 0x01;
+        assert!(!IcmpPacket::new(&bytes).verify_checksum());
+    }
+
+    #[test]
+    fn test_destination_unreachable() {
+        let bytes = [
+            0x03, 0x03, 0x00, 0x00, // Port Unreachable
+            0x00, 0x00, 0x00, 0x00, // Unused
+            0x45, 0x00, 0x00, 0x24, 0x00, 0x01, 0x00, 0x00, // Original IPv4 header
+            0x40, 0x11, 0x00, 0x00, 10, 0, 0, 1, 10, 0, 0, 2,
+            0x9c, 0x40, 0x00, 0x35, 0x00, 0x10, 0x00, 0x00, // First 8 octets of the UDP datagram
+        ];
+        let packet = IcmpPacket::new_with_validation(&bytes).unwrap();
+        assert_eq!(packet.identifier(), None);
+        let original = packet.unreachable_header().unwrap();
+        assert_eq!(original.destination().unwrap(), IPv4::new(10, 0, 0, 2));
+        assert_eq!(original.protocol().unwrap(), 17);
+    }
+
+    #[test]
+    fn test_too_short() {
+        assert_eq!(
+            IcmpPacket::new_with_validation(&ECHO_REQUEST[..7]).err(),
+            Some(ParsingError::BufferUnderflow)
+        );
+    }
+}
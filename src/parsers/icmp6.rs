@@ -0,0 +1,146 @@
+// src/parsers/icmp6.rs
+use crate::utils::checksum;
+use super::ParsingError;
+
+/// IPv6 Next Header value identifying ICMPv6
+pub const NEXT_HEADER_ICMPV6: u8 = 58;
+
+/// Internet Control Message Protocol for IPv6
+///
+/// [RFC 4443]: https://datatracker.ietf.org/doc/html/rfc4443
+
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |     Type      |     Code      |          Checksum             |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                                                               |
+// +                         Message Body                          +
+// |                                                               |
+#[derive(Debug, Clone)]
+pub struct Icmpv6Packet<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Icmpv6Packet<'a> {
+    // Type, Code and Checksum
+    const HEADER_LEN: usize = 4;
+
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    pub fn new_with_validation(buffer: &'a [u8]) -> Result<Self, ParsingError> {
+        if buffer.len() < Self::HEADER_LEN {
+            Err(ParsingError::BufferUnderflow)
+        } else {
+            Ok(Self { buffer })
+        }
+    }
+
+    /// Return the Type
+    pub fn msg_type(&self) -> Result<u8, ParsingError> {
+        self.read_u8(0)
+    }
+
+    /// Return the Code
+    pub fn code(&self) -> Result<u8, ParsingError> {
+        self.read_u8(1)
+    }
+
+    /// Return the Checksum
+    pub fn checksum(&self) -> Result<u16, ParsingError> {
+        Ok(u16::from_be_bytes([self.read_u8(2)?, self.read_u8(3)?]))
+    }
+
+    /// Return the message body following the checksum
+    pub fn body(&self) -> Result<&'a [u8], ParsingError> {
+        self.buffer.get(Self::HEADER_LEN..).ok_or(ParsingError::BufferUnderflow)
+    }
+
+    /// Verify the checksum against the caller-supplied IPv6 pseudo-header
+    /// partial sum (see [`pseudo_header_checksum`]).
+    ///
+    /// The ICMPv6 checksum is mandatory, so a zero checksum field is
+    /// always rejected.
+    ///
+    /// [`pseudo_header_checksum`]: super::ipv6::pseudo_header_checksum
+    pub fn verify_checksum(&self, pseudo_header_sum: u32) -> Result<bool, ParsingError> {
+        if self.checksum()? == 0 {
+            return Ok(false);
+        }
+        Ok(checksum::checksum(self.buffer, pseudo_header_sum) == 0)
+    }
+
+    fn read_u8(&self, index: usize) -> Result<u8, ParsingError> {
+        self.buffer.get(index).copied().ok_or(ParsingError::BufferUnderflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::ipv6;
+    use crate::parsers::ipv6::pseudo_header_checksum;
+
+    // Echo Request fe80::1 -> fe80::2, id 0x1234, seq 1, data "ping"
+    static ECHO_REQUEST: [u8; 12] = [
+        0x80, 0x00, 0x91, 0xae, // Type, Code, Checksum
+        0x12, 0x34, 0x00, 0x01, // Identifier, Sequence Number
+        b'p', b'i', b'n', b'g', // Data
+    ];
+
+    fn pseudo_header(len: usize) -> u32 {
+        let src = ipv6::from_string("fe80::1").unwrap();
+        let dst = ipv6::from_string("fe80::2").unwrap();
+        pseudo_header_checksum(&src, &dst, NEXT_HEADER_ICMPV6, len as u32)
+    }
+
+    #[test]
+    fn test_deconstruct() {
+        let packet = Icmpv6Packet::new_with_validation(&ECHO_REQUEST).unwrap();
+        assert_eq!(packet.msg_type().unwrap(), 128);
+        assert_eq!(packet.code().unwrap(), 0);
+        assert_eq!(packet.checksum().unwrap(), 0x91ae);
+        assert_eq!(packet.body().unwrap(), &ECHO_REQUEST[4..]);
+    }
+
+    #[test]
+    fn test_valid_checksum() {
+        let packet = Icmpv6Packet::new(&ECHO_REQUEST);
+        assert!(packet.verify_checksum(pseudo_header(ECHO_REQUEST.len())).unwrap());
+    }
+
+    #[test]
+    fn test_incorrect_checksum() {
+        let mut bytes = ECHO_REQUEST;
+        bytes[3] ^= 0x01;
+        let packet = Icmpv6Packet::new(&bytes);
+        assert!(!packet.verify_checksum(pseudo_header(bytes.len())).unwrap());
+    }
+
+    #[test]
+    fn test_zero_checksum_rejected() {
+        let mut bytes = ECHO_REQUEST;
+        bytes[2] = 0;
+        bytes[3] = 0;
+        let packet = Icmpv6Packet::new(&bytes);
+        assert!(!packet.verify_checksum(pseudo_header(bytes.len())).unwrap());
+    }
+
+    #[test]
+    fn test_wrong_pseudo_header_rejected() {
+        let packet = Icmpv6Packet::new(&ECHO_REQUEST);
+        assert!(!packet.verify_checksum(0).unwrap());
+    }
+
+    #[test]
+    fn test_too_short() {
+        assert_eq!(
+            Icmpv6Packet::new_with_validation(&ECHO_REQUEST[..3]).err(),
+            Some(ParsingError::BufferUnderflow)
+        );
+        let packet = Icmpv6Packet::new(&ECHO_REQUEST[..3]);
+        assert_eq!(packet.code(), Ok(0));
+        assert_eq!(packet.checksum(), Err(ParsingError::BufferUnderflow));
+        assert_eq!(packet.body(), Err(ParsingError::BufferUnderflow));
+    }
+}
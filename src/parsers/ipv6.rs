@@ -1,9 +1,39 @@
 use crate::address::{self, ipv6::IPv6};
+use crate::utils::checksum;
 
 // src/parsers/ipv6.rs
 use super::{ParsingError, ValidationError};
 
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
 
+/// Next Header values of the IPv6 extension headers, from the
+/// [IPv6 Extension Header Types] registry
+///
+/// [IPv6 Extension Header Types]: https://www.iana.org/assignments/ipv6-parameters
+pub const NEXT_HEADER_HOP_BY_HOP: u8 = 0;
+pub const NEXT_HEADER_ROUTING: u8 = 43;
+pub const NEXT_HEADER_FRAGMENT: u8 = 44;
+pub const NEXT_HEADER_ESP: u8 = 50;
+pub const NEXT_HEADER_AUTH: u8 = 51;
+pub const NEXT_HEADER_DEST_OPTS: u8 = 60;
+pub const NEXT_HEADER_MOBILITY: u8 = 135;
+
+/// Return the one's complement sum of the IPv6 pseudo-header used by
+/// upper-layer checksums, folded to 16 bits but not complemented.
+///
+/// `length` is the 32-bit Upper-Layer Packet Length and `next_header` the
+/// upper-layer protocol, not any extension header, as in [RFC 8200].
+///
+/// [RFC 8200]: https://datatracker.ietf.org/doc/html/rfc8200#section-8.1
+pub fn pseudo_header_checksum(source: &IPv6, destination: &IPv6, next_header: u8, length: u32) -> u32 {
+    let mut sum: u32 = 0;
+    for word in source.to_bytes().chunks(2).chain(destination.to_bytes().chunks(2)) {
+        sum += u16::from_be_bytes([word[0], word[1]]) as u32;
+    }
+    sum += (length >> 16) + (length & 0xFFFF) + next_header as u32;
+    checksum::fold(sum) as u32
+}
 
 /// Internet protocol version 6 packet
 ///
@@ -50,6 +80,9 @@ impl<'a> IPv6Packet<'a> {
         let len = self.buffer.len();
         if len < 40 || len < self.total_length()? {
             Err(ValidationError::InvalidPacketLength.into())
+        } else if self.version()? != 6 {
+            // An IPv4 buffer must not be read as IPv6.
+            Err(ValidationError::InvalidVersion.into())
         } else {
             Ok(())
         }
@@ -59,6 +92,26 @@ impl<'a> IPv6Packet<'a> {
         40 // Fixed for IPv6
     }
 
+    /// Query if a Next Header value identifies an extension header rather
+    /// than an upper-layer protocol.
+    pub fn is_extension_header(next_header: u8) -> bool {
+        matches!(
+            next_header,
+            NEXT_HEADER_HOP_BY_HOP
+                | NEXT_HEADER_ROUTING
+                | NEXT_HEADER_FRAGMENT
+                | NEXT_HEADER_ESP
+                | NEXT_HEADER_AUTH
+                | NEXT_HEADER_DEST_OPTS
+                | NEXT_HEADER_MOBILITY
+        )
+    }
+
+    /// Reads a single byte from the packet.
+    fn read_u8(&self, index: usize) -> Result<u8, ParsingError> {
+        self.buffer.get(index).copied().ok_or(ParsingError::BufferUnderflow)
+    }
+
     /// Reads a 2-byte field from the packet and returns it as u16.
     fn read_u16(&self, start: usize) -> Result<u16, ParsingError> {
         if self.buffer.len() < start + 2 {
@@ -72,20 +125,20 @@ impl<'a> IPv6Packet<'a> {
     }
 
     /// Return the Version
-    pub fn version(&self) -> u8 {
-        self.buffer[0] >> 4
+    pub fn version(&self) -> Result<u8, ParsingError> {
+        Ok(self.read_u8(0)? >> 4)
     }
 
     /// Return the Traffic Class
-    pub fn traffic_class(&self) -> u8 {
-        ((self.buffer[0] & 0x0f) << 4) | (self.buffer[1] >> 4)
+    pub fn traffic_class(&self) -> Result<u8, ParsingError> {
+        Ok(((self.read_u8(0)? & 0x0f) << 4) | (self.read_u8(1)? >> 4))
     }
 
     /// Return the Flow Label
-    pub fn flow_label(&self) -> u32 {
+    pub fn flow_label(&self) -> Result<u32, ParsingError> {
         // Last 4 bits of the first byte
         // all of the second and third bytes
-        ((self.buffer[1] as u32 & 0x0f) << 16) | (self.buffer[2] as u32) << 8 | self.buffer[3] as u32
+        Ok(((self.read_u8(1)? as u32 & 0x0f) << 16) | (self.read_u8(2)? as u32) << 8 | self.read_u8(3)? as u32)
     }
 
     /// Return the Payload Length
@@ -99,25 +152,45 @@ impl<'a> IPv6Packet<'a> {
     }
 
     /// Return the Next Header
-    pub fn next_header(&self) -> u8 {
-        self.buffer[6]
+    pub fn next_header(&self) -> Result<u8, ParsingError> {
+        self.read_u8(6)
     }
     /// Return the Hop Limit
-    pub fn hop_limit(&self) -> u8 {
-        self.buffer[7]
+    pub fn hop_limit(&self) -> Result<u8, ParsingError> {
+        self.read_u8(7)
     }
 
     /// Return the (16 bytes) Source address
     pub fn source(&self) -> Result<IPv6, ParsingError> {
+        if self.buffer.len() < 24 {
+            return Err(ParsingError::BufferUnderflow);
+        }
         address::ipv6::from_bytes(&self.buffer[8..24])
         .map_err(ParsingError::from)
     }
     /// Return the (16 bytes) Destination address
     pub fn destination(&self) -> Result<IPv6, ParsingError> {
+        if self.buffer.len() < 40 {
+            return Err(ParsingError::BufferUnderflow);
+        }
         address::ipv6::from_bytes(&self.buffer[24..40])
         .map_err(ParsingError::from)
     }
 
+    /// Return a one-line summary of the packet for logging, e.g.
+    /// `IPv6 fe80::1 -> ff02::1 next=58 len=32 hlim=255`.
+    #[cfg(feature = "alloc")]
+    pub fn summary(&self) -> Result<String, ParsingError> {
+        Ok(format!(
+            "IPv6 {} -> {} next={} len={} hlim={}",
+            self.source()?,
+            self.destination()?,
+            self.next_header()?,
+            self.payload_length()?,
+            self.hop_limit()?
+        ))
+    }
+
     /// Return a reference to the payload of the IPv6 packet.
     pub fn payload(&self) -> Result<&[u8], ParsingError> {
         if self.buffer.len() < self.total_length()?{
@@ -125,6 +198,337 @@ impl<'a> IPv6Packet<'a> {
         }
         Ok(&self.buffer[40..])
     }
+
+    /// Return the pseudo-header sum for the upper-layer payload, using the
+    /// protocol and length found past any extension headers.
+    pub fn pseudo_header_checksum(&self) -> Result<u32, ParsingError> {
+        let (next_header, payload) = self.upper_layer_protocol()?;
+        Ok(pseudo_header_checksum(&self.source()?, &self.destination()?, next_header, payload.len() as u32))
+    }
+
+    /// Walk the extension header chain and return the upper-layer protocol
+    /// number together with its payload.
+    ///
+    /// ESP is returned as the upper-layer protocol since the headers after
+    /// it are encrypted.
+    pub fn upper_layer_protocol(&self) -> Result<(u8, &'a [u8]), ParsingError> {
+        self.walk_extension_headers(|_, _| {})
+    }
+
+    /// Return the Fragment Offset, in 8-octet units, of the Fragment header
+    /// in the extension header chain, or 0 if the packet is not a fragment.
+    /// Only the fragment at offset 0 carries the upper-layer header.
+    pub fn fragment_offset(&self) -> Result<u16, ParsingError> {
+        let mut offset = 0;
+        self.walk_extension_headers(|next_header, header| {
+            if next_header == NEXT_HEADER_FRAGMENT {
+                offset = u16::from_be_bytes([header[2], header[3]]) >> 3;
+            }
+        })?;
+        Ok(offset)
+    }
+
+    // Walk the extension header chain, passing each header with its type
+    // to `visit`, and return the upper-layer protocol and payload
+    fn walk_extension_headers(&self, mut visit: impl FnMut(u8, &'a [u8])) -> Result<(u8, &'a [u8]), ParsingError> {
+        if self.buffer.len() < self.total_length()? {
+            return Err(ValidationError::InvalidPacketLength.into());
+        }
+        let mut next_header = self.next_header()?;
+        let mut rest = &self.buffer[40..self.total_length()?];
+
+        while Self::is_extension_header(next_header) && next_header != NEXT_HEADER_ESP {
+            if rest.len() < 2 {
+                return Err(ParsingError::BufferUnderflow);
+            }
+            let hdr_ext_len = rest[1] as usize;
+            let len = match next_header {
+                NEXT_HEADER_FRAGMENT => 8,
+                // Authentication Header length is in 4-octet units, minus 2
+                NEXT_HEADER_AUTH => (hdr_ext_len + 2) * 4,
+                _ => (hdr_ext_len + 1) * 8,
+            };
+            // Every header is at least 8 bytes, so the walk always advances.
+            if rest.len() < len {
+                return Err(ParsingError::BufferUnderflow);
+            }
+            visit(next_header, &rest[..len]);
+            next_header = rest[0];
+            rest = &rest[len..];
+        }
+        Ok((next_header, rest))
+    }
+}
+
+/// Routing Type values of the Routing header
+pub const ROUTING_TYPE_SOURCE_ROUTE: u8 = 0;
+pub const ROUTING_TYPE_SEGMENT_ROUTING: u8 = 4;
+
+/// A Routing extension header
+///
+/// Type 0 ([RFC 2460], deprecated by RFC 5095) and Segment Routing type 4
+/// ([RFC 8754]) both carry a list of addresses starting at offset 8:
+///
+/// ```text
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  Next Header  |  Hdr Ext Len  | Routing Type  | Segments Left |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  Last Entry   |     Flags     |              Tag              |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |            Segment List[0] .. Segment List[n] (128 bits each)  |
+/// ```
+///
+/// In a type 0 header the second word is reserved.
+///
+/// [RFC 2460]: https://datatracker.ietf.org/doc/html/rfc2460#section-4.4
+/// [RFC 8754]: https://datatracker.ietf.org/doc/html/rfc8754#section-2
+pub struct RoutingHeader<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> RoutingHeader<'a> {
+    // Fixed part before the address list
+    const FIXED_LEN: usize = 8;
+
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    /// Wrap the header at the start of `buffer`, checking that the whole
+    /// header is present and, for types 0 and 4, that the address list
+    /// fits within Hdr Ext Len and Segments Left indexes into it.
+    pub fn new_with_validation(buffer: &'a [u8]) -> Result<Self, ParsingError> {
+        let header = Self { buffer };
+        if buffer.len() < Self::FIXED_LEN || buffer.len() < header.header_length()? {
+            return Err(ParsingError::BufferUnderflow);
+        }
+        // Hdr Ext Len counts 8-octet units, two per address
+        let room = buffer[1] as usize / 2;
+        let segments_left = header.segments_left()? as usize;
+        let valid = match header.routing_type()? {
+            ROUTING_TYPE_SOURCE_ROUTE => buffer[1] & 1 == 0 && segments_left <= room,
+            ROUTING_TYPE_SEGMENT_ROUTING => {
+                let count = buffer[4] as usize + 1;
+                count <= room && segments_left < count
+            }
+            _ => true,
+        };
+        if !valid {
+            return Err(ValidationError::InvalidHeaderLength.into());
+        }
+        Ok(header)
+    }
+
+    /// Return the Next Header
+    pub fn next_header(&self) -> Result<u8, ParsingError> {
+        self.read_u8(0)
+    }
+
+    /// Return the length of the header in bytes
+    pub fn header_length(&self) -> Result<usize, ParsingError> {
+        Ok((self.read_u8(1)? as usize + 1) * 8)
+    }
+
+    /// Return the Routing Type
+    pub fn routing_type(&self) -> Result<u8, ParsingError> {
+        self.read_u8(2)
+    }
+
+    /// Return the number of route segments still to be visited
+    pub fn segments_left(&self) -> Result<u8, ParsingError> {
+        self.read_u8(3)
+    }
+
+    /// Return an iterator over the address list of a type 0 or type 4
+    /// header, in the order it is stored. SRv6 stores the final segment
+    /// first. Other routing types, and headers too short to tell, yield
+    /// no addresses.
+    pub fn segments(&self) -> Segments<'a> {
+        let count = match (self.routing_type(), self.read_u8(1), self.read_u8(4)) {
+            (Ok(ROUTING_TYPE_SOURCE_ROUTE), Ok(hdr_ext_len), _) => hdr_ext_len as usize / 2,
+            (Ok(ROUTING_TYPE_SEGMENT_ROUTING), _, Ok(last_entry)) => last_entry as usize + 1,
+            _ => 0,
+        };
+        let end = (Self::FIXED_LEN + count * 16).min(self.buffer.len());
+        let list = self.buffer.get(Self::FIXED_LEN..end).unwrap_or(&[]);
+        Segments { chunks: list.chunks_exact(16) }
+    }
+
+    /// Return the address the packet is currently routed to, or `None`
+    /// once a type 0 route is complete or for other routing types.
+    pub fn active_segment(&self) -> Option<IPv6> {
+        let segments_left = self.segments_left().ok()? as usize;
+        match self.routing_type().ok()? {
+            ROUTING_TYPE_SEGMENT_ROUTING => self.segments().nth(segments_left),
+            ROUTING_TYPE_SOURCE_ROUTE if segments_left > 0 => {
+                let count = self.segments().len();
+                self.segments().nth(count.checked_sub(segments_left)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn read_u8(&self, index: usize) -> Result<u8, ParsingError> {
+        self.buffer.get(index).copied().ok_or(ParsingError::BufferUnderflow)
+    }
+}
+
+/// Iterator over the addresses of a [`RoutingHeader`]
+pub struct Segments<'a> {
+    chunks: core::slice::ChunksExact<'a, u8>,
+}
+
+impl Iterator for Segments<'_> {
+    type Item = IPv6;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(chunk);
+        Some(IPv6::from(octets))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Segments<'_> {}
+
+/// Option Type values found in Hop-by-Hop and Destination Options headers
+pub const OPTION_PAD1: u8 = 0;
+pub const OPTION_PADN: u8 = 1;
+pub const OPTION_ROUTER_ALERT: u8 = 5;
+
+/// What a node must do with an option it does not recognize, taken from
+/// the two high-order bits of the Option Type as defined in [RFC 8200].
+///
+/// [RFC 8200]: https://datatracker.ietf.org/doc/html/rfc8200#section-4.2
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OptionAction {
+    /// Skip over the option and continue processing the header
+    Skip,
+    /// Discard the packet
+    Discard,
+    /// Discard the packet and send an ICMP Parameter Problem
+    DiscardAndReport,
+    /// Discard the packet and send an ICMP Parameter Problem unless the
+    /// destination is multicast
+    DiscardAndReportUnicast,
+}
+
+impl OptionAction {
+    pub fn from_option_type(option_type: u8) -> Self {
+        match option_type >> 6 {
+            0 => OptionAction::Skip,
+            1 => OptionAction::Discard,
+            2 => OptionAction::DiscardAndReport,
+            _ => OptionAction::DiscardAndReportUnicast,
+        }
+    }
+}
+
+/// A single option of a Hop-by-Hop or Destination Options header
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ipv6Option<'a> {
+    Pad1,
+    /// Padding of the given number of data octets
+    PadN(u8),
+    /// Router Alert with its value, e.g. 0 for MLD
+    RouterAlert(u16),
+    Unknown { option_type: u8, data: &'a [u8] },
+}
+
+impl Ipv6Option<'_> {
+    /// Return the Option Type
+    pub fn option_type(&self) -> u8 {
+        match self {
+            Ipv6Option::Pad1 => OPTION_PAD1,
+            Ipv6Option::PadN(_) => OPTION_PADN,
+            Ipv6Option::RouterAlert(_) => OPTION_ROUTER_ALERT,
+            Ipv6Option::Unknown { option_type, .. } => *option_type,
+        }
+    }
+
+    /// Return the action required if the option is not recognized
+    pub fn action(&self) -> OptionAction {
+        OptionAction::from_option_type(self.option_type())
+    }
+}
+
+/// A Hop-by-Hop or Destination Options extension header
+///
+/// Both share one layout: Next Header, Hdr Ext Len in 8-octet units not
+/// counting the first, then TLV-encoded options.
+pub struct OptionsHeader<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> OptionsHeader<'a> {
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    /// Wrap the header at the start of `buffer`, checking that the whole
+    /// header declared by Hdr Ext Len is present.
+    pub fn new_with_validation(buffer: &'a [u8]) -> Result<Self, ParsingError> {
+        let header = Self { buffer };
+        if buffer.len() < 2 || buffer.len() < header.header_length() {
+            return Err(ParsingError::BufferUnderflow);
+        }
+        Ok(header)
+    }
+
+    /// Return the Next Header
+    pub fn next_header(&self) -> Result<u8, ParsingError> {
+        self.buffer.first().copied().ok_or(ParsingError::BufferUnderflow)
+    }
+
+    /// Return the length of the header in bytes
+    pub fn header_length(&self) -> usize {
+        (self.buffer.get(1).copied().unwrap_or(0) as usize + 1) * 8
+    }
+
+    /// Return an iterator over the options. Options never extend past the
+    /// header or the buffer, whichever ends first.
+    pub fn options_iter(&self) -> Ipv6Options<'a> {
+        let end = self.header_length().min(self.buffer.len());
+        Ipv6Options { buffer: self.buffer.get(2..end).unwrap_or(&[]) }
+    }
+}
+
+/// Iterator over the options of a Hop-by-Hop or Destination Options header.
+///
+/// Iteration stops at the end of the header, or at the first option whose
+/// length runs past it.
+pub struct Ipv6Options<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Iterator for Ipv6Options<'a> {
+    type Item = Ipv6Option<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let option_type = *self.buffer.first()?;
+        if option_type == OPTION_PAD1 {
+            self.buffer = &self.buffer[1..];
+            return Some(Ipv6Option::Pad1);
+        }
+        let length = match self.buffer.get(1) {
+            Some(&length) if 2 + length as usize <= self.buffer.len() => length as usize,
+            _ => {
+                self.buffer = &[];
+                return None;
+            }
+        };
+        let data = &self.buffer[2..2 + length];
+        self.buffer = &self.buffer[2 + length..];
+        Some(match (option_type, data) {
+            (OPTION_PADN, _) => Ipv6Option::PadN(length as u8),
+            (OPTION_ROUTER_ALERT, &[high, low]) => Ipv6Option::RouterAlert(u16::from_be_bytes([high, low])),
+            _ => Ipv6Option::Unknown { option_type, data },
+        })
+    }
 }
 
 
@@ -147,6 +551,93 @@ mod tests {
         buffer
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_summary() {
+        let mut buffer = generate_valid_ipv6_buffer();
+        buffer[8..24].copy_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        buffer[24..40].copy_from_slice(&[0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        let packet = IPv6Packet::new(&buffer);
+        assert_eq!(packet.summary().unwrap(), "IPv6 fe80::1 -> ff02::1 next=59 len=0 hlim=255");
+    }
+
+    // Append extension headers and a payload to the fixed header
+    fn with_payload(next_header: u8, payload: &[u8]) -> Vec<u8> {
+        let mut buffer = generate_valid_ipv6_buffer();
+        buffer[6] = next_header;
+        buffer[4..6].copy_from_slice(&(payload.len() as u16).to_be_bytes());
+        buffer.extend_from_slice(payload);
+        buffer
+    }
+
+    #[test]
+    fn test_upper_layer_protocol_hop_by_hop_tcp() {
+        let mut payload = vec![
+            6, 0, // Next Header (TCP), Hdr Ext Len (8 bytes)
+            0x05, 0x02, 0x00, 0x00, // Router Alert option
+            0x01, 0x00, // PadN
+        ];
+        payload.extend_from_slice(&[0xaa; 20]); // TCP header
+        let buffer = with_payload(NEXT_HEADER_HOP_BY_HOP, &payload);
+
+        let packet = IPv6Packet::new_with_validation(&buffer).unwrap();
+        let (protocol, upper) = packet.upper_layer_protocol().unwrap();
+        assert_eq!(protocol, 6);
+        assert_eq!(upper, &[0xaa; 20]);
+    }
+
+    #[test]
+    fn test_upper_layer_protocol_fragment_and_no_extensions() {
+        let mut payload = vec![17, 0, 0x00, 0x01, 0x12, 0x34, 0x56, 0x78]; // Fragment header
+        payload.extend_from_slice(&[0xbb; 8]);
+        let buffer = with_payload(NEXT_HEADER_FRAGMENT, &payload);
+        let packet = IPv6Packet::new(&buffer);
+        assert_eq!(packet.upper_layer_protocol().unwrap(), (17, &[0xbb; 8][..]));
+        assert_eq!(packet.fragment_offset(), Ok(0));
+
+        payload[2..4].copy_from_slice(&[0x05, 0xa9]); // Offset 181, more fragments
+        let buffer = with_payload(NEXT_HEADER_FRAGMENT, &payload);
+        assert_eq!(IPv6Packet::new(&buffer).fragment_offset(), Ok(181));
+
+        let buffer = with_payload(58, &[0xcc; 4]);
+        assert_eq!(IPv6Packet::new(&buffer).upper_layer_protocol().unwrap(), (58, &[0xcc; 4][..]));
+    }
+
+    #[test]
+    fn test_pseudo_header_checksum_icmpv6_after_hop_by_hop() {
+        // Echo Request fe80::1 -> fe80::2 behind a Hop-by-Hop header
+        let echo = [0x80, 0x00, 0x91, 0xae, 0x12, 0x34, 0x00, 0x01, b'p', b'i', b'n', b'g'];
+        let mut payload = vec![58, 0, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00];
+        payload.extend_from_slice(&echo);
+        let mut buffer = with_payload(NEXT_HEADER_HOP_BY_HOP, &payload);
+        buffer[8..24].copy_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        buffer[24..40].copy_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02]);
+
+        let packet = IPv6Packet::new(&buffer);
+        let pseudo = packet.pseudo_header_checksum().unwrap();
+        assert_eq!(
+            pseudo,
+            pseudo_header_checksum(&packet.source().unwrap(), &packet.destination().unwrap(), 58, 12)
+        );
+        assert!(crate::parsers::icmp6::Icmpv6Packet::new(&echo).verify_checksum(pseudo).unwrap());
+    }
+
+    #[test]
+    fn test_upper_layer_protocol_malformed() {
+        // Hdr Ext Len claims 16 bytes with only 8 present
+        let buffer = with_payload(NEXT_HEADER_DEST_OPTS, &[6, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            IPv6Packet::new(&buffer).upper_layer_protocol(),
+            Err(ParsingError::BufferUnderflow)
+        );
+        // Chain of routing headers running off the end
+        let buffer = with_payload(NEXT_HEADER_ROUTING, &[NEXT_HEADER_ROUTING, 0, 0, 0, 0, 0, 0, 0, 43]);
+        assert_eq!(
+            IPv6Packet::new(&buffer).upper_layer_protocol(),
+            Err(ParsingError::BufferUnderflow)
+        );
+    }
+
     #[test]
     fn test_new_with_valid_buffer() {
         let buffer = generate_valid_ipv6_buffer();
@@ -198,15 +689,22 @@ mod tests {
     fn test_version() {
         let buffer = generate_valid_ipv6_buffer();
         let packet = IPv6Packet::new(&buffer);
-        assert_eq!(packet.version(), 6);
+        assert_eq!(packet.version().unwrap(), 6);
     }
 
-    // #[test]
-    // fn test_traffic_class() {
-    //     let buffer = generate_valid_ipv6_buffer();
-    //     let packet = IPv6Packet::new(&buffer);
-    //     assert_eq!(packet.traffic_class(), 0);
-    // }
+    #[test]
+    fn test_traffic_class() {
+        let mut buffer = generate_valid_ipv6_buffer();
+        let packet = IPv6Packet::new(&buffer);
+        assert_eq!(packet.traffic_class().unwrap(), 0);
+
+        // Traffic Class 0x99 straddles the first two bytes
+        buffer[0] = 0x69;
+        buffer[1] = 0x95;
+        let packet = IPv6Packet::new(&buffer);
+        assert_eq!(packet.traffic_class().unwrap(), 0x99);
+        assert_eq!(packet.flow_label().unwrap(), 0x50000);
+    }
 
     #[test]
     fn test_flow_label() {
@@ -216,7 +714,7 @@ mod tests {
         buffer[2] = 0xff;
         buffer[3] = 0xff;
         let packet = IPv6Packet::new(&buffer);
-        assert_eq!(packet.flow_label(), 0x000fffff);
+        assert_eq!(packet.flow_label().unwrap(), 0x000fffff);
     }
 
     #[test]
@@ -243,14 +741,14 @@ mod tests {
     fn test_next_header() {
         let buffer = generate_valid_ipv6_buffer();
         let packet = IPv6Packet::new(&buffer);
-        assert_eq!(packet.next_header(), 59); // No Next Header
+        assert_eq!(packet.next_header().unwrap(), 59); // No Next Header
     }
 
     #[test]
     fn test_hop_limit() {
         let buffer = generate_valid_ipv6_buffer();
         let packet = IPv6Packet::new(&buffer);
-        assert_eq!(packet.hop_limit(), 255);
+        assert_eq!(packet.hop_limit().unwrap(), 255);
     }
 
     #[test]
@@ -277,15 +775,45 @@ mod tests {
     #[test]
     fn test_insufficient_buffer_length() {
         let buffer = vec![0u8; 20]; // Less than the minimum IPv6 header size
-        assert!(matches!(IPv6Packet::new_with_validation(&buffer), Err(_)));
+        assert!(IPv6Packet::new_with_validation(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_invalid_version() {
+        let mut buffer = generate_valid_ipv6_buffer();
+        buffer[0] = 0x50; // Version set to 5 instead of 6
+        assert!(matches!(
+            IPv6Packet::new_with_validation(&buffer),
+            Err(ParsingError::ValidationError(ValidationError::InvalidVersion))
+        ));
     }
 
-    // #[test]
-    // fn test_invalid_version() {
-    //     let mut buffer = generate_valid_ipv6_buffer();
-    //     buffer[0] = 0x50; // Version set to 5 instead of 6
-    //     assert!(matches!(IPv6Packet::new_with_validation(&buffer), Err(ParsingError::InvalidVersion)));
-    // }
+    #[test]
+    fn test_short_buffers_do_not_panic() {
+        let buffer = generate_valid_ipv6_buffer();
+        for len in 0..40 {
+            let packet = IPv6Packet::new(&buffer[..len]);
+            assert_eq!(packet.version().is_ok(), len >= 1);
+            assert_eq!(packet.traffic_class().is_ok(), len >= 2);
+            assert_eq!(packet.flow_label().is_ok(), len >= 4);
+            assert_eq!(packet.payload_length().is_ok(), len >= 6);
+            assert_eq!(packet.next_header().is_ok(), len >= 7);
+            assert_eq!(packet.hop_limit().is_ok(), len >= 8);
+            assert_eq!(packet.source().is_ok(), len >= 24);
+            assert!(packet.destination().is_err());
+            assert!(packet.payload().is_err());
+            assert!(packet.upper_layer_protocol().is_err());
+            assert!(IPv6Packet::new_with_validation(&buffer[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_addresses_on_short_buffer() {
+        let buffer = generate_valid_ipv6_buffer();
+        let packet = IPv6Packet::new(&buffer[..20]);
+        assert_eq!(packet.source(), Err(ParsingError::BufferUnderflow));
+        assert_eq!(packet.destination(), Err(ParsingError::BufferUnderflow));
+    }
 
     #[test]
     fn test_invalid_payload_length() {
@@ -293,7 +821,7 @@ mod tests {
         // Set an invalid payload length that exceeds the actual buffer size
         buffer[4] = 0xFF;
         buffer[5] = 0xFF; // Payload length set to 65535
-        assert!(matches!(IPv6Packet::new_with_validation(&buffer), Err(_)));
+        assert!(IPv6Packet::new_with_validation(&buffer).is_err());
     }
 
     #[test]
@@ -316,8 +844,8 @@ mod tests {
         buffer[2] = 0xFF;
         buffer[3] = 0xFF; // Flow Label continues
         let packet = IPv6Packet::new(&buffer);
-        assert_eq!(packet.traffic_class(), 0xFF);
-        assert_eq!(packet.flow_label(), 0x0FFFFF);
+        assert_eq!(packet.traffic_class().unwrap(), 0xFF);
+        assert_eq!(packet.flow_label().unwrap(), 0x0FFFFF);
     }
 
     #[test]
@@ -325,7 +853,19 @@ mod tests {
         let mut buffer = generate_valid_ipv6_buffer();
         buffer[6] = 0xFF; // Unrecognized Next Header value
         let packet = IPv6Packet::new(&buffer);
-        assert_eq!(packet.next_header(), 0xFF);
+        assert_eq!(packet.next_header().unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn test_is_extension_header() {
+        assert!(IPv6Packet::is_extension_header(0)); // Hop-by-Hop
+        assert!(IPv6Packet::is_extension_header(43)); // Routing
+        assert!(IPv6Packet::is_extension_header(44)); // Fragment
+        assert!(IPv6Packet::is_extension_header(60)); // Destination Options
+        assert!(!IPv6Packet::is_extension_header(6)); // TCP
+        assert!(!IPv6Packet::is_extension_header(17)); // UDP
+        assert!(!IPv6Packet::is_extension_header(58)); // ICMPv6
+        assert!(!IPv6Packet::is_extension_header(59)); // No Next Header
     }
 
     #[test]
@@ -336,4 +876,127 @@ mod tests {
         assert_eq!(packet.payload_length().unwrap(), 0);
         assert!(packet.payload().unwrap().is_empty());
     }
+
+    #[test]
+    fn test_options_router_alert_and_padn() {
+        // Hop-by-Hop carrying Router Alert (MLD) and a two byte PadN
+        let buffer = [58, 0, 0x05, 0x02, 0x00, 0x00, 0x01, 0x00];
+        let header = OptionsHeader::new_with_validation(&buffer).unwrap();
+        assert_eq!(header.next_header(), Ok(58));
+        assert_eq!(header.header_length(), 8);
+        assert_eq!(OptionsHeader::new(&[]).next_header(), Err(ParsingError::BufferUnderflow));
+
+        let options: Vec<_> = header.options_iter().collect();
+        assert_eq!(options, vec![Ipv6Option::RouterAlert(0), Ipv6Option::PadN(0)]);
+        assert_eq!(options[0].action(), OptionAction::Skip);
+    }
+
+    #[test]
+    fn test_options_pad1_and_unknown_action() {
+        // Destination Options: Pad1, unknown type 0xc2 with 3 data bytes, Pad1
+        let buffer = [6, 0, 0x00, 0xc2, 0x03, 0xaa, 0xbb, 0xcc];
+        let header = OptionsHeader::new_with_validation(&buffer).unwrap();
+        let options: Vec<_> = header.options_iter().collect();
+        assert_eq!(options, vec![
+            Ipv6Option::Pad1,
+            Ipv6Option::Unknown { option_type: 0xc2, data: &[0xaa, 0xbb, 0xcc] },
+        ]);
+        assert_eq!(options[1].action(), OptionAction::DiscardAndReportUnicast);
+        assert_eq!(OptionAction::from_option_type(0x40), OptionAction::Discard);
+        assert_eq!(OptionAction::from_option_type(0x80), OptionAction::DiscardAndReport);
+    }
+
+    #[test]
+    fn test_options_length_overrun() {
+        // PadN claims 10 data bytes in an 8 byte header
+        let buffer = [6, 0, 0x05, 0x02, 0x00, 0x00, 0x01, 0x0a, 0xff, 0xff];
+        let header = OptionsHeader::new_with_validation(&buffer).unwrap();
+        assert_eq!(header.options_iter().collect::<Vec<_>>(), vec![Ipv6Option::RouterAlert(0)]);
+
+        // Hdr Ext Len claims 16 bytes with only 8 present
+        assert!(matches!(
+            OptionsHeader::new_with_validation(&[6, 1, 0, 0, 0, 0, 0, 0]),
+            Err(ParsingError::BufferUnderflow)
+        ));
+        assert_eq!(OptionsHeader::new(&[6, 1, 0x01, 0x20]).options_iter().count(), 0);
+    }
+
+    // A type 4 routing header listing `segments`, final segment first
+    fn srh(segments: &[&[u8]], segments_left: u8) -> Vec<u8> {
+        let mut buffer = vec![
+            6, (segments.len() * 2) as u8, // Next Header (TCP), Hdr Ext Len
+            ROUTING_TYPE_SEGMENT_ROUTING, segments_left,
+            segments.len() as u8 - 1, 0, 0, 0, // Last Entry, Flags, Tag
+        ];
+        for segment in segments {
+            buffer.extend_from_slice(segment);
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_routing_header_srv6() {
+        let first = "2001:db8::1".parse::<IPv6>().unwrap();
+        let last = "2001:db8::2".parse::<IPv6>().unwrap();
+        let mut buffer = srh(&[last.to_bytes(), first.to_bytes()], 1);
+
+        let header = RoutingHeader::new_with_validation(&buffer).unwrap();
+        assert_eq!(header.next_header(), Ok(6));
+        assert_eq!(header.header_length(), Ok(40));
+        assert_eq!(header.routing_type(), Ok(ROUTING_TYPE_SEGMENT_ROUTING));
+        assert_eq!(header.segments_left(), Ok(1));
+        assert_eq!(header.segments().collect::<Vec<_>>(), vec![last, first]);
+        assert_eq!(header.active_segment(), Some(first));
+
+        // The first segment endpoint decrements Segments Left
+        buffer[3] -= 1;
+        let header = RoutingHeader::new_with_validation(&buffer).unwrap();
+        assert_eq!(header.segments_left(), Ok(0));
+        assert_eq!(header.active_segment(), Some(last));
+    }
+
+    #[test]
+    fn test_routing_header_type0() {
+        let mut buffer = vec![17, 4, ROUTING_TYPE_SOURCE_ROUTE, 2, 0, 0, 0, 0];
+        buffer.extend_from_slice(&[0x11; 16]);
+        buffer.extend_from_slice(&[0x22; 16]);
+        let header = RoutingHeader::new_with_validation(&buffer).unwrap();
+        assert_eq!(header.segments().len(), 2);
+        assert_eq!(header.active_segment(), Some(IPv6::from([0x11; 16])));
+
+        buffer[3] = 0;
+        assert_eq!(RoutingHeader::new(&buffer).active_segment(), None);
+    }
+
+    #[test]
+    fn test_routing_header_validation() {
+        let segment = [0x20; 16];
+        // Segments Left past the end of the list
+        let buffer = srh(&[&segment, &segment], 2);
+        assert_eq!(
+            RoutingHeader::new_with_validation(&buffer).err(),
+            Some(ValidationError::InvalidHeaderLength.into())
+        );
+
+        // Last Entry claims more addresses than Hdr Ext Len leaves room for
+        let mut buffer = srh(&[&segment], 0);
+        buffer[4] = 1;
+        assert_eq!(
+            RoutingHeader::new_with_validation(&buffer).err(),
+            Some(ValidationError::InvalidHeaderLength.into())
+        );
+
+        // Hdr Ext Len runs past the buffer
+        let buffer = srh(&[&segment, &segment], 0);
+        assert_eq!(
+            RoutingHeader::new_with_validation(&buffer[..30]).err(),
+            Some(ParsingError::BufferUnderflow)
+        );
+        assert_eq!(RoutingHeader::new_with_validation(&[6, 0, 4]).err(), Some(ParsingError::BufferUnderflow));
+
+        // Short headers built without validation report the missing fields
+        assert_eq!(RoutingHeader::new(&[]).next_header(), Err(ParsingError::BufferUnderflow));
+        assert_eq!(RoutingHeader::new(&[0, 0, 4, 0]).segments().count(), 0);
+        assert_eq!(RoutingHeader::new(&[0, 0, 4]).active_segment(), None);
+    }
 }
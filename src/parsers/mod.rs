@@ -3,10 +3,47 @@ pub mod ethernet;
 pub mod ipv4;
 pub mod ipv6;
 pub mod arp;
+pub mod icmp4;
+pub mod icmp6;
+pub mod tcp;
+pub mod udp;
+#[cfg(feature = "std")]
 pub mod packet;
+#[cfg(feature = "std")]
+pub mod report;
 
-use crate::address::ipv4::IPv4AddressError;
-use crate::address::ipv6::IPv6AddressError;
+#[cfg(feature = "std")]
+pub use report::{parse_all, ParseReport};
+
+use crate::address::ipv4::{IPv4, IPv4AddressError};
+use crate::address::ipv6::{IPv6, IPv6AddressError};
+
+/// Addresses that can form the pseudo-header of a transport checksum.
+pub trait PseudoHeader {
+    /// Whether a UDP checksum of zero means "not computed". True for IPv4,
+    /// false for IPv6 where the UDP checksum is mandatory.
+    const UDP_ZERO_CHECKSUM_ALLOWED: bool;
+
+    /// Return the folded pseudo-header sum for `length` bytes of
+    /// `protocol` payload sent from `source` to `destination`.
+    fn pseudo_header_checksum(source: &Self, destination: &Self, protocol: u8, length: u32) -> u32;
+}
+
+impl PseudoHeader for IPv4 {
+    const UDP_ZERO_CHECKSUM_ALLOWED: bool = true;
+
+    fn pseudo_header_checksum(source: &Self, destination: &Self, protocol: u8, length: u32) -> u32 {
+        ipv4::pseudo_header_checksum(source, destination, protocol, length as u16)
+    }
+}
+
+impl PseudoHeader for IPv6 {
+    const UDP_ZERO_CHECKSUM_ALLOWED: bool = false;
+
+    fn pseudo_header_checksum(source: &Self, destination: &Self, protocol: u8, length: u32) -> u32 {
+        ipv6::pseudo_header_checksum(source, destination, protocol, length)
+    }
+}
 
 
 #[derive(Debug, PartialEq)]
@@ -14,18 +51,22 @@ pub enum ParsingError {
     BufferUnderflow,
     UnsupportedEthertype,
     InvalidPacketLength,
+    UnsupportedHardwareType(u16),
+    UnsupportedArpOperation(u16),
     IPv4AddressError(IPv4AddressError),
     IPv6AddressError(IPv6AddressError),
     ValidationError(ValidationError),
     Default
 }
 
-impl std::fmt::Display for ParsingError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             ParsingError::BufferUnderflow => write!(f, "The data buffer is too short to contain a valid packet"),
             ParsingError::UnsupportedEthertype => write!(f, "The ethertype is not supported"),
             ParsingError::InvalidPacketLength => write!(f, "The packet length is invalid"),
+            ParsingError::UnsupportedHardwareType(value) => write!(f, "Unsupported ARP hardware type {}", value),
+            ParsingError::UnsupportedArpOperation(value) => write!(f, "Unsupported ARP operation {}", value),
             ParsingError::IPv4AddressError(e) => write!(f, "{}", e), // Delegate to IPv4AddressError's Display impl
             ParsingError::IPv6AddressError(e) => write!(f, "{}", e), // Delegate to IPv6AddressError's Display impl
             ParsingError::ValidationError(e) => write!(f, "{}", e),
@@ -34,6 +75,7 @@ impl std::fmt::Display for ParsingError {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ParsingError {}
 
 
@@ -41,27 +83,36 @@ impl std::error::Error for ParsingError {}
 pub enum ValidationError {
     BufferTooShort,
     InvalidHeaderLength,
+    InvalidVersion,
     HeaderLengthExceedsTotalLength,
     TotalLengthExceedsBufferLength,
     InvalidPacketLength,
     InvalidPayloadLength,
+    InvalidSourceAddress,
+    InvalidDestinationAddress,
+    InvalidAddressLength,
     Default
 }
 
-impl std::fmt::Display for ValidationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             ValidationError::BufferTooShort => write!(f, "Buffer too short"),
             ValidationError::InvalidHeaderLength => write!(f, "Invalid header length"),
+            ValidationError::InvalidVersion => write!(f, "Invalid IP version"),
             ValidationError::HeaderLengthExceedsTotalLength => write!(f, "Header length exceeds total length"),
             ValidationError::TotalLengthExceedsBufferLength => write!(f, "Total length exceeds buffer length"),
             ValidationError::InvalidPacketLength => write!(f, "The packet length is invalid"),
             ValidationError::InvalidPayloadLength => write!(f, "The payload length is invalid"),
+            ValidationError::InvalidSourceAddress => write!(f, "The source address is not valid for sending"),
+            ValidationError::InvalidDestinationAddress => write!(f, "The destination address is not valid for sending"),
+            ValidationError::InvalidAddressLength => write!(f, "The address length is not supported"),
             ValidationError::Default => write!(f, "Validation error!"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ValidationError {}
 
 impl From<IPv4AddressError> for ParsingError {
@@ -1,10 +1,334 @@
 // src/parsers/packet.rs
 use actix::prelude::*;
 use bytes::BytesMut;
+use log::debug;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem;
 use std::result::Result;
-use crate::parsers::{ethernet, ipv4, arp, ipv6};
 
-pub struct Packet;
+use crate::io::messages::ProcessPacket;
+use crate::parsers::{arp, ethernet, icmp4, icmp6, ipv4, ipv6, tcp, udp, ParsingError};
+use crate::parsers::ethernet::EtherClass;
+use crate::parsers::report::Layer;
+
+// Minimum lengths of the headers the dispatcher hands frames to
+const IPV4_MIN_LENGTH: usize = 20;
+const IPV6_MIN_LENGTH: usize = 40;
+const ARP_MIN_LENGTH: usize = 28;
+
+/// Outcome of dispatching a frame. Parsed variants own the whole frame.
+#[derive(Debug, PartialEq)]
+pub enum ParsedPacket {
+    Ipv4(BytesMut),
+    Ipv6(BytesMut),
+    Arp(BytesMut),
+    /// A well-formed frame whose Ethertype we do not handle.
+    Unsupported { ethertype: u16, frame_len: usize },
+    /// The frame was discarded without being parsed.
+    Dropped { reason: &'static str },
+}
+
+impl ParsedPacket {
+    /// Return the whole Ethernet frame of a parsed packet
+    pub fn frame(&self) -> Option<&[u8]> {
+        match self {
+            ParsedPacket::Ipv4(frame) | ParsedPacket::Ipv6(frame) | ParsedPacket::Arp(frame) => Some(frame),
+            _ => None,
+        }
+    }
+
+    /// Return a view of the IPv4 packet
+    pub fn ipv4(&self) -> Option<ipv4::IPv4Packet<'_>> {
+        match self {
+            ParsedPacket::Ipv4(frame) => Some(ipv4::IPv4Packet::new(ethernet::EthernetFrame::new(frame).payload())),
+            _ => None,
+        }
+    }
+
+    /// Return a view of the IPv6 packet
+    pub fn ipv6(&self) -> Option<ipv6::IPv6Packet<'_>> {
+        match self {
+            ParsedPacket::Ipv6(frame) => Some(ipv6::IPv6Packet::new(ethernet::EthernetFrame::new(frame).payload())),
+            _ => None,
+        }
+    }
+
+    /// Return a view of the ARP packet
+    pub fn arp(&self) -> Option<arp::ArpPacket<'_>> {
+        match self {
+            ParsedPacket::Arp(frame) => Some(arp::ArpPacket::new(ethernet::EthernetFrame::new(frame).payload())),
+            _ => None,
+        }
+    }
+}
+
+/// One-line summary, e.g.
+/// `IPv4 192.168.1.1 -> 192.168.1.2 proto=6 len=40 ttl=64` or
+/// `ARP who-has 10.0.0.2 tell 10.0.0.1`.
+impl fmt::Display for ParsedPacket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let summary = match self {
+            // IP packets share the summary used for logging
+            ParsedPacket::Ipv4(_) => self.ipv4().map(|p| p.summary().map(|s| f.write_str(&s))),
+            ParsedPacket::Ipv6(_) => self.ipv6().map(|p| p.summary().map(|s| f.write_str(&s))),
+            ParsedPacket::Arp(_) => self.arp().map(|p| write_arp(&p, f)),
+            ParsedPacket::Unsupported { ethertype, frame_len } => {
+                return write!(f, "Ethernet type={:#06x} len={}", ethertype, frame_len);
+            }
+            ParsedPacket::Dropped { reason } => return write!(f, "Dropped ({})", reason),
+        };
+        match summary {
+            Some(Ok(result)) => result,
+            // Parsed packets were validated, so this is not expected
+            _ => write!(f, "Malformed packet"),
+        }
+    }
+}
+
+// Returns the formatting result, or the error of a field that could not
+// be read
+fn write_arp(packet: &arp::ArpPacket, f: &mut fmt::Formatter) -> Result<fmt::Result, ParsingError> {
+    Ok(match packet.operation_type()? {
+        arp::Operation::Request => write!(f, "ARP who-has {} tell {}", packet.target_ip()?, packet.sender_ip()?),
+        arp::Operation::Reply => write!(f, "ARP reply {} is-at {}", packet.sender_ip()?, packet.sender_mac()?),
+    })
+}
+
+/// A borrowed view of one layer yielded by [`PacketStack`]
+pub enum LayerView<'a> {
+    Ethernet(ethernet::EthernetFrame<'a>),
+    Ipv4(ipv4::IPv4Packet<'a>),
+    Ipv6(ipv6::IPv6Packet<'a>),
+    Arp(arp::ArpPacket<'a>),
+    Tcp(tcp::TcpSegment<'a>),
+    Udp(udp::UdpDatagram<'a>),
+    Icmp(icmp4::IcmpPacket<'a>),
+    Icmpv6(icmp6::Icmpv6Packet<'a>),
+}
+
+impl LayerView<'_> {
+    /// Return which protocol layer this is
+    pub fn layer(&self) -> Layer {
+        match self {
+            LayerView::Ethernet(_) => Layer::Ethernet,
+            LayerView::Ipv4(_) => Layer::Ipv4,
+            LayerView::Ipv6(_) => Layer::Ipv6,
+            LayerView::Arp(_) => Layer::Arp,
+            LayerView::Tcp(_) => Layer::Tcp,
+            LayerView::Udp(_) => Layer::Udp,
+            LayerView::Icmp(_) | LayerView::Icmpv6(_) => Layer::Icmp,
+        }
+    }
+}
+
+/// A layer of a [`PacketStack`] that failed to parse
+#[derive(Debug, PartialEq)]
+pub struct LayerError {
+    pub layer: Layer,
+    pub error: ParsingError,
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} layer: {}", self.layer, self.error)
+    }
+}
+
+impl std::error::Error for LayerError {}
+
+// The next layer to decode and the bytes it starts at
+enum NextLayer<'a> {
+    Ethernet(&'a [u8]),
+    Network(u16, &'a [u8]),
+    // Protocol number, the network layer carrying it and its payload
+    Transport(u8, Layer, &'a [u8]),
+    Done,
+}
+
+/// Walks a frame layer by layer without copying
+///
+/// Yields the Ethernet frame, then the IPv4, IPv6 or ARP packet, then
+/// the TCP, UDP or ICMP header, each borrowing the original buffer. The
+/// walk ends at the first layer it does not support, or after yielding
+/// the error of the first layer that fails to parse.
+pub struct PacketStack<'a> {
+    next: NextLayer<'a>,
+}
+
+impl<'a> PacketStack<'a> {
+    pub fn parse(frame: &'a [u8]) -> Self {
+        Self { next: NextLayer::Ethernet(frame) }
+    }
+
+    fn decode(next: NextLayer<'a>) -> Option<Result<(LayerView<'a>, NextLayer<'a>), LayerError>> {
+        let at = |layer| move |error| LayerError { layer, error };
+
+        Some(match next {
+            NextLayer::Ethernet(buffer) => Self::ethernet(buffer).map_err(at(Layer::Ethernet)),
+            NextLayer::Network(ethernet::ETHERTYPE_IPV4, buffer) => Self::ipv4(buffer).map_err(at(Layer::Ipv4)),
+            NextLayer::Network(ethernet::ETHERTYPE_IPV6, buffer) => Self::ipv6(buffer).map_err(at(Layer::Ipv6)),
+            NextLayer::Network(ethernet::ETHERTYPE_ARP, buffer) => arp::ArpPacket::new_with_validation(buffer)
+                .map(|packet| (LayerView::Arp(packet), NextLayer::Done))
+                .map_err(at(Layer::Arp)),
+            NextLayer::Transport(tcp::PROTOCOL_TCP, _, buffer) => tcp::TcpSegment::new_with_validation(buffer)
+                .map(|segment| (LayerView::Tcp(segment), NextLayer::Done))
+                .map_err(at(Layer::Tcp)),
+            NextLayer::Transport(udp::PROTOCOL_UDP, _, buffer) => udp::UdpDatagram::new_with_validation(buffer)
+                .map(|datagram| (LayerView::Udp(datagram), NextLayer::Done))
+                .map_err(at(Layer::Udp)),
+            NextLayer::Transport(icmp4::PROTOCOL_ICMP, Layer::Ipv4, buffer) => icmp4::IcmpPacket::new_with_validation(buffer)
+                .map(|packet| (LayerView::Icmp(packet), NextLayer::Done))
+                .map_err(at(Layer::Icmp)),
+            NextLayer::Transport(icmp6::NEXT_HEADER_ICMPV6, Layer::Ipv6, buffer) => {
+                icmp6::Icmpv6Packet::new_with_validation(buffer)
+                    .map(|packet| (LayerView::Icmpv6(packet), NextLayer::Done))
+                    .map_err(at(Layer::Icmp))
+            }
+            _ => return None,
+        })
+    }
+
+    fn ethernet(buffer: &'a [u8]) -> Result<(LayerView<'a>, NextLayer<'a>), ParsingError> {
+        let frame = ethernet::EthernetFrame::new_with_validation(buffer)?;
+        let next = NextLayer::Network(frame.ethertype(), frame.payload());
+        Ok((LayerView::Ethernet(frame), next))
+    }
+
+    fn ipv4(buffer: &'a [u8]) -> Result<(LayerView<'a>, NextLayer<'a>), ParsingError> {
+        let packet = ipv4::IPv4Packet::new_with_validation(buffer)?;
+        // Only the first fragment carries the transport header
+        let next = if packet.fragment_offset()? == 0 {
+            NextLayer::Transport(packet.protocol()?, Layer::Ipv4, packet.payload()?)
+        } else {
+            NextLayer::Done
+        };
+        Ok((LayerView::Ipv4(packet), next))
+    }
+
+    fn ipv6(buffer: &'a [u8]) -> Result<(LayerView<'a>, NextLayer<'a>), ParsingError> {
+        let packet = ipv6::IPv6Packet::new_with_validation(buffer)?;
+        // As with IPv4, later fragments start mid-way through the payload
+        let next = if packet.fragment_offset()? == 0 {
+            let (protocol, payload) = packet.upper_layer_protocol()?;
+            NextLayer::Transport(protocol, Layer::Ipv6, payload)
+        } else {
+            NextLayer::Done
+        };
+        Ok((LayerView::Ipv6(packet), next))
+    }
+}
+
+impl<'a> Iterator for PacketStack<'a> {
+    type Item = Result<LayerView<'a>, LayerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match Self::decode(mem::replace(&mut self.next, NextLayer::Done))? {
+            Ok((view, next)) => {
+                self.next = next;
+                Some(Ok(view))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl FusedIterator for PacketStack<'_> {}
+
+/// Per-protocol frame counts kept by the `Packet` dispatcher
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacketCounters {
+    pub ipv4: u64,
+    pub ipv6: u64,
+    pub arp: u64,
+    /// Well-formed frames with an ethertype we do not handle, or 802.3
+    /// frames carrying a length instead
+    pub dropped: u64,
+    /// Frames too short for the Ethernet header or their declared protocol
+    pub runt: u64,
+    /// Frames that failed to parse
+    pub errors: u64,
+}
+
+#[derive(Default)]
+pub struct Packet {
+    counters: PacketCounters,
+}
+
+impl Packet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn dispatch(&mut self, packet: BytesMut) -> Result<ParsedPacket, ParsingError> {
+        if packet.len() < ethernet::ETHER_MIN_LENGTH {
+            return Ok(self.drop_runt());
+        }
+        let eth_frame = ethernet::EthernetFrame::new_with_validation(&packet).inspect_err(|e| {
+            debug!("Malformed ethernet frame: {}", e);
+            self.counters.errors += 1;
+        })?;
+
+        // Tagged frames are dispatched on the Ethertype past their tags
+        let class = match eth_frame.classify() {
+            EtherClass::Vlan(_) => eth_frame.inner_class(),
+            class => class,
+        };
+        let min_length = match class {
+            EtherClass::Ipv4 => IPV4_MIN_LENGTH,
+            EtherClass::Ipv6 => IPV6_MIN_LENGTH,
+            EtherClass::Arp => ARP_MIN_LENGTH,
+            _ => 0,
+        };
+        if eth_frame.payload().len() < min_length {
+            return Ok(self.drop_runt());
+        }
+
+        // TODO: pass the original ethernet frame on to a route/packet.rs actor
+        let parsed = match class {
+            EtherClass::Ipv4 => ipv4::IPv4Packet::new_with_validation(eth_frame.payload())
+                .map(|_| ()),
+            EtherClass::Ipv6 => ipv6::IPv6Packet::new_with_validation(eth_frame.payload())
+                .map(|_| ()),
+            EtherClass::Arp => arp::ArpPacket::new_with_validation(eth_frame.payload())
+                .map(|_| ()),
+            EtherClass::Unknown(ethertype) => {
+                debug!("Unsupported ethertype {:#06x} in {} byte frame", ethertype, packet.len());
+                self.counters.dropped += 1;
+                return Ok(ParsedPacket::Unsupported { ethertype, frame_len: packet.len() });
+            }
+            EtherClass::Length(_) | EtherClass::Vlan(_) => {
+                self.counters.dropped += 1;
+                return Ok(ParsedPacket::Dropped { reason: "802.3 length frame" });
+            }
+        };
+        if let Err(e) = parsed {
+            debug!("Malformed packet: {}", e);
+            self.counters.errors += 1;
+            return Err(e);
+        }
+
+        Ok(match class {
+            EtherClass::Ipv4 => {
+                self.counters.ipv4 += 1;
+                ParsedPacket::Ipv4(packet)
+            }
+            EtherClass::Ipv6 => {
+                self.counters.ipv6 += 1;
+                ParsedPacket::Ipv6(packet)
+            }
+            _ => {
+                self.counters.arp += 1;
+                ParsedPacket::Arp(packet)
+            }
+        })
+    }
+
+    fn drop_runt(&mut self) -> ParsedPacket {
+        self.counters.runt += 1;
+        ParsedPacket::Dropped { reason: "runt" }
+    }
+}
 
 impl Actor for Packet {
     type Context = Context<Self>;
@@ -14,44 +338,293 @@ impl Actor for Packet {
 pub struct ParsePacket(pub BytesMut);
 
 impl Message for ParsePacket {
-    type Result = Result<(), ()>;
+    type Result = Result<ParsedPacket, ParsingError>;
 }
 
 impl Handler<ParsePacket> for Packet {
     type Result = MessageResult<ParsePacket>;
-    // type Result = ResponseActFuture<Self, Result<ParsedPacket, ParsingError>>;
 
     fn handle(&mut self, msg: ParsePacket, _: &mut Context<Self>) -> Self::Result {
-    //     let packet = msg.0;
-    //     let eth_frame: ethernet::EthernetFrame = EthernetFrame{ &packet };
-
-    //     // Decide whether to drop by passing systems mac to a drop method
-    //     // this will just pass the ethernet packet to a router/packet.rs actor
-
-
-    //     match eth_frame.ethertype {
-    //         ethernet::ETHERTYPE_IPV4 => {
-    //             // Handle IPv4 packet
-    //             let _ipv4_packet = ipv4::IPv4Packet::new(&eth_frame.payload);
-    //             // Decide whethere to drop
-    //             // pass original ethernet frame to a route/packet.rs actor
-    //         },
-    //         ethernet::ETHERTYPE_IPV6 => {
-    //             // Handle IPv4 packet
-    //             let _ipv6_packet = ipv6::parse(&eth_frame.payload).unwrap();
-    //             // pass original ethernet frame to a route/packet.rs actor
-    //         },
-    //         ethernet::ETHERTYPE_ARP => {
-    //             // Handle ARP packet
-    //             let _arp_packet = arp::parse(&eth_frame.payload).unwrap();
-    //             // pass original ethernet frame to a route/packet.rs actor
-    //         },
-    //         _ => {
-    //             // Handle unsupported ethertype
-    //         },
-    //     }
-
-    //     MessageResult(Ok(()))
-        todo!()
+        MessageResult(self.dispatch(msg.0))
+    }
+}
+
+// Frames received by `NetworkIO` are dispatched like `ParsePacket`; the
+// outcome is only reflected in the counters
+impl Handler<ProcessPacket> for Packet {
+    type Result = ();
+
+    fn handle(&mut self, msg: ProcessPacket, _: &mut Context<Self>) {
+        if let Ok(parsed) = self.dispatch(BytesMut::from(&msg.0[..])) {
+            debug!("{}", parsed);
+        }
+    }
+}
+
+// Message to query a snapshot of the dispatcher's counters
+pub struct GetCounters;
+
+impl Message for GetCounters {
+    type Result = PacketCounters;
+}
+
+impl Handler<GetCounters> for Packet {
+    type Result = MessageResult<GetCounters>;
+
+    fn handle(&mut self, _msg: GetCounters, _: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.counters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(ethertype: u16, payload: &[u8]) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        buf.extend_from_slice(&[0x11, 0x12, 0x13, 0x14, 0x15, 0x16]);
+        buf.extend_from_slice(&ethertype.to_be_bytes());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    fn ipv4_packet() -> Vec<u8> {
+        let mut p = vec![0u8; 20];
+        p[0] = 0x45; // Version 4, IHL 5
+        p[3] = 20; // Total Length
+        p
+    }
+
+    fn ipv6_packet() -> Vec<u8> {
+        let mut p = vec![0u8; 40];
+        p[0] = 0x60; // Version 6, Payload Length 0
+        p
+    }
+
+    #[actix_rt::test]
+    async fn test_counters() {
+        let addr = Packet::new().start();
+
+        let frames = vec![
+            frame(ethernet::ETHERTYPE_IPV4, &ipv4_packet()),
+            frame(ethernet::ETHERTYPE_IPV4, &ipv4_packet()),
+            frame(ethernet::ETHERTYPE_IPV6, &ipv6_packet()),
+            frame(ethernet::ETHERTYPE_ARP, &[0u8; 28]),
+            frame(0x1234, &[0u8; 4]),
+            frame(ethernet::ETHERTYPE_IPV4, &[0x45, 0x00]), // Runt IPv4
+            BytesMut::from(&[0u8; 6][..]), // Runt frame
+            frame(ethernet::ETHERTYPE_IPV4, &[0x4f; 20]), // Malformed IPv4
+        ];
+        for f in frames {
+            let _ = addr.send(ParsePacket(f)).await.unwrap();
+        }
+
+        let counters = addr.send(GetCounters).await.unwrap();
+        assert_eq!(counters, PacketCounters { ipv4: 2, ipv6: 1, arp: 1, dropped: 1, runt: 2, errors: 1 });
+    }
+
+    #[actix_rt::test]
+    async fn test_process_packet_counted() {
+        let addr = Packet::new().start();
+        addr.send(ProcessPacket(frame(ethernet::ETHERTYPE_IPV4, &ipv4_packet()).to_vec())).await.unwrap();
+        assert_eq!(addr.send(GetCounters).await.unwrap().ipv4, 1);
+    }
+
+    #[actix_rt::test]
+    async fn test_parse_result() {
+        let addr = Packet::new().start();
+        let ipv6 = frame(ethernet::ETHERTYPE_IPV6, &ipv6_packet());
+        assert_eq!(addr.send(ParsePacket(ipv6.clone())).await.unwrap(), Ok(ParsedPacket::Ipv6(ipv6)));
+
+        // Payload length claims more data than the frame carries
+        let mut truncated = ipv6_packet();
+        truncated[5] = 8;
+        assert!(addr.send(ParsePacket(frame(ethernet::ETHERTYPE_IPV6, &truncated))).await.unwrap().is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_parse_ipv4_view() {
+        let addr = Packet::new().start();
+        let mut packet = ipv4_packet();
+        packet[8] = 64; // TTL
+        packet[9] = 17; // UDP
+        let parsed = addr.send(ParsePacket(frame(ethernet::ETHERTYPE_IPV4, &packet))).await.unwrap().unwrap();
+        let view = parsed.ipv4().unwrap();
+        assert_eq!(view.ttl().unwrap(), 64);
+        assert_eq!(view.protocol().unwrap(), 17);
+        assert_eq!(parsed.frame().map(|f| f.len()), Some(34));
+        assert!(parsed.ipv6().is_none() && parsed.arp().is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_unsupported_ethertype_preserved() {
+        let addr = Packet::new().start();
+        // LLDP
+        let lldp = frame(0x88cc, &[0u8; 32]);
+        let result = addr.send(ParsePacket(lldp)).await.unwrap();
+        assert_eq!(result, Ok(ParsedPacket::Unsupported { ethertype: 0x88cc, frame_len: 46 }));
+
+        let counters = addr.send(GetCounters).await.unwrap();
+        assert_eq!(counters.dropped, 1);
+        assert_eq!(counters.errors, 0);
+    }
+
+    #[actix_rt::test]
+    async fn test_vlan_tagged_dispatch() {
+        let addr = Packet::new().start();
+        let mut tagged = BytesMut::new();
+        tagged.extend_from_slice(&frame(ethernet::ETHERTYPE_VLAN, &[0x00, 0x64])[..16]);
+        tagged.extend_from_slice(&ethernet::ETHERTYPE_IPV4.to_be_bytes());
+        tagged.extend_from_slice(&ipv4_packet());
+        let result = addr.send(ParsePacket(tagged.clone())).await.unwrap();
+        assert_eq!(result, Ok(ParsedPacket::Ipv4(tagged)));
+    }
+
+    #[actix_rt::test]
+    async fn test_runt_frame_dropped() {
+        let addr = Packet::new().start();
+        let result = addr.send(ParsePacket(BytesMut::from(&[0u8; 8][..]))).await.unwrap();
+        assert_eq!(result, Ok(ParsedPacket::Dropped { reason: "runt" }));
+
+        let result = addr.send(ParsePacket(frame(ethernet::ETHERTYPE_ARP, &[0u8; 27]))).await.unwrap();
+        assert_eq!(result, Ok(ParsedPacket::Dropped { reason: "runt" }));
+
+        let counters = addr.send(GetCounters).await.unwrap();
+        assert_eq!(counters.runt, 2);
+        assert_eq!(counters.errors, 0);
+    }
+
+    #[test]
+    fn test_display_ipv4() {
+        let mut packet = ipv4_packet();
+        packet[3] = 40; // Total Length
+        packet[8] = 64; // TTL
+        packet[9] = 6; // TCP
+        packet[12..16].copy_from_slice(&[192, 168, 1, 1]);
+        packet[16..20].copy_from_slice(&[192, 168, 1, 2]);
+        packet.resize(40, 0);
+        let parsed = Packet::new().dispatch(frame(ethernet::ETHERTYPE_IPV4, &packet)).unwrap();
+        assert_eq!(parsed.to_string(), "IPv4 192.168.1.1 -> 192.168.1.2 proto=6 len=40 ttl=64");
+    }
+
+    #[test]
+    fn test_display_arp() {
+        use crate::address::{ipv4::IPv4, mac::Mac};
+        use crate::assemblers;
+
+        let (mac, ip) = (Mac::new(0x02, 0, 0, 0, 0, 0x01), IPv4::new(10, 0, 0, 1));
+        let request = assemblers::arp::build_request(mac, ip, IPv4::new(10, 0, 0, 2));
+        let parsed = Packet::new().dispatch(frame(ethernet::ETHERTYPE_ARP, &request)).unwrap();
+        assert_eq!(parsed.to_string(), "ARP who-has 10.0.0.2 tell 10.0.0.1");
+
+        let reply = assemblers::arp::build_reply(mac, ip, Mac::new(0x02, 0, 0, 0, 0, 0x02), IPv4::new(10, 0, 0, 2));
+        let parsed = Packet::new().dispatch(frame(ethernet::ETHERTYPE_ARP, &reply)).unwrap();
+        assert_eq!(parsed.to_string(), "ARP reply 10.0.0.1 is-at 02:00:00:00:00:01");
+    }
+
+    #[test]
+    fn test_display_other_outcomes() {
+        assert_eq!(
+            ParsedPacket::Unsupported { ethertype: 0x88cc, frame_len: 46 }.to_string(),
+            "Ethernet type=0x88cc len=46"
+        );
+        assert_eq!(ParsedPacket::Dropped { reason: "runt" }.to_string(), "Dropped (runt)");
+    }
+
+    // 10.0.0.1:5353 -> 10.0.0.2:53 carrying "ping"
+    fn udp_frame() -> BytesMut {
+        let mut ip = vec![
+            0x45, 0x00, 0x00, 0x20, // Version, IHL, DSCP, Total Length 32
+            0x00, 0x01, 0x00, 0x00, // Identification, Flags
+            0x40, 0x11, 0x00, 0x00, // TTL 64, UDP, Checksum
+            10, 0, 0, 1, // Source
+            10, 0, 0, 2, // Destination
+            0x14, 0xe9, 0x00, 0x35, // Ports 5353 -> 53
+            0x00, 0x0c, 0x00, 0x00, // Length 12, Checksum
+        ];
+        ip.extend_from_slice(b"ping");
+        frame(ethernet::ETHERTYPE_IPV4, &ip)
+    }
+
+    #[test]
+    fn test_packet_stack_udp() {
+        let buf = udp_frame();
+        let layers: Vec<_> = PacketStack::parse(&buf).collect::<Result<_, _>>().unwrap();
+        assert_eq!(layers.iter().map(LayerView::layer).collect::<Vec<_>>(), vec![Layer::Ethernet, Layer::Ipv4, Layer::Udp]);
+
+        match &layers[0] {
+            LayerView::Ethernet(eth) => {
+                assert_eq!(eth.ethertype(), ethernet::ETHERTYPE_IPV4);
+                assert_eq!(eth.source_mac().to_string(), "11:12:13:14:15:16");
+            }
+            _ => panic!("expected Ethernet"),
+        }
+        match &layers[1] {
+            LayerView::Ipv4(ip) => {
+                assert_eq!(ip.source().unwrap().to_string(), "10.0.0.1");
+                assert_eq!(ip.destination().unwrap().to_string(), "10.0.0.2");
+                assert_eq!(ip.protocol(), Ok(udp::PROTOCOL_UDP));
+                assert_eq!(ip.ttl(), Ok(64));
+            }
+            _ => panic!("expected IPv4"),
+        }
+        match &layers[2] {
+            LayerView::Udp(datagram) => {
+                assert_eq!(datagram.source_port(), 5353);
+                assert_eq!(datagram.dest_port(), 53);
+                assert_eq!(datagram.length(), 12);
+                // The payload borrows the original frame
+                assert_eq!(datagram.payload(), b"ping");
+                assert_eq!(datagram.payload().as_ptr(), buf[buf.len() - 4..].as_ptr());
+            }
+            _ => panic!("expected UDP"),
+        }
+    }
+
+    #[test]
+    fn test_packet_stack_stops_at_unsupported_layer() {
+        let buf = frame(0x88cc, &[0u8; 46]);
+        let layers: Vec<_> = PacketStack::parse(&buf).map(|l| l.unwrap().layer()).collect();
+        assert_eq!(layers, vec![Layer::Ethernet]);
+
+        // An IPv4 packet carrying an unknown protocol
+        let mut ip = ipv4_packet();
+        ip[9] = 0xfd;
+        let buf = frame(ethernet::ETHERTYPE_IPV4, &ip);
+        let layers: Vec<_> = PacketStack::parse(&buf).map(|l| l.unwrap().layer()).collect();
+        assert_eq!(layers, vec![Layer::Ethernet, Layer::Ipv4]);
+    }
+
+    #[test]
+    fn test_packet_stack_stops_at_later_ipv6_fragment() {
+        let mut ip = ipv6_packet();
+        ip[5] = 16; // Payload Length
+        ip[6] = ipv6::NEXT_HEADER_FRAGMENT;
+        ip.extend_from_slice(&[udp::PROTOCOL_UDP, 0, 0x00, 0x08, 0, 0, 0, 1]); // Offset 1
+        ip.extend_from_slice(&[0xee; 8]); // Middle of the datagram, not a UDP header
+        let buf = frame(ethernet::ETHERTYPE_IPV6, &ip);
+        let layers: Vec<_> = PacketStack::parse(&buf).map(|l| l.unwrap().layer()).collect();
+        assert_eq!(layers, vec![Layer::Ethernet, Layer::Ipv6]);
+    }
+
+    #[test]
+    fn test_packet_stack_reports_failing_layer() {
+        use crate::parsers::ValidationError;
+
+        let mut buf = udp_frame();
+        buf.truncate(buf.len() - 2); // IPv4 Total Length now exceeds the buffer
+        let mut stack = PacketStack::parse(&buf);
+        assert!(matches!(stack.next(), Some(Ok(LayerView::Ethernet(_)))));
+        match stack.next() {
+            Some(Err(e)) => assert_eq!(e.layer, Layer::Ipv4),
+            _ => panic!("expected an IPv4 error"),
+        }
+        assert!(stack.next().is_none());
+
+        let mut buf = udp_frame();
+        buf[14 + 25] = 0x0e; // UDP Length 14 past the end of the packet
+        let err = PacketStack::parse(&buf).find_map(Result::err).unwrap();
+        assert_eq!(err, LayerError { layer: Layer::Udp, error: ValidationError::InvalidPacketLength.into() });
     }
 }
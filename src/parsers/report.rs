@@ -0,0 +1,392 @@
+// src/parsers/report.rs
+
+//! Decode every layer of a frame in one call
+//!
+//! [`parse_all`] walks a frame from the Ethernet header down to the
+//! transport header, copying each header it understands into an owned
+//! struct. It stops at the first layer that fails to parse and records
+//! which layer that was together with the error, so an inspector can
+//! still show everything decoded up to that point.
+
+use crate::address::ipv4::IPv4;
+use crate::address::ipv6::IPv6;
+use crate::address::mac::Mac;
+use crate::parsers::{arp, ethernet, icmp4, icmp6, ipv4, ipv6, tcp, udp, ParsingError};
+
+/// Protocol layers known to [`parse_all`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Ethernet,
+    Ipv4,
+    Ipv6,
+    Arp,
+    Tcp,
+    Udp,
+    Icmp,
+}
+
+/// Ethernet header fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthernetHeader {
+    pub destination: Mac,
+    pub source: Mac,
+    /// The Ethertype past any VLAN tags
+    pub ethertype: u16,
+    /// VLAN IDs, outermost first
+    pub vlans: Vec<u16>,
+}
+
+/// IPv4 header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ipv4Header {
+    pub source: IPv4,
+    pub destination: IPv4,
+    pub header_length: u8,
+    pub dscp: u8,
+    pub ecn: u8,
+    pub total_length: u16,
+    pub identification: u16,
+    pub dont_frag: bool,
+    pub more_frags: bool,
+    pub fragment_offset: u16,
+    pub ttl: u8,
+    pub protocol: u8,
+    pub checksum: u16,
+}
+
+/// IPv6 header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ipv6Header {
+    pub source: IPv6,
+    pub destination: IPv6,
+    pub traffic_class: u8,
+    pub flow_label: u32,
+    pub payload_length: u16,
+    pub next_header: u8,
+    pub hop_limit: u8,
+    /// The protocol found past any extension headers
+    pub upper_layer_protocol: u8,
+}
+
+/// ARP packet fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArpHeader {
+    pub hardware_type: u16,
+    pub protocol_type: u16,
+    pub operation: u16,
+    pub sender_mac: Mac,
+    pub sender_ip: IPv4,
+    pub target_mac: Mac,
+    pub target_ip: IPv4,
+}
+
+/// TCP header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpHeader {
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub seq_number: u32,
+    pub ack_number: u32,
+    pub header_length: usize,
+    pub flags: u8,
+    pub window: u16,
+    pub checksum: u16,
+    pub urgent_pointer: u16,
+    pub payload_length: usize,
+}
+
+/// UDP header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UdpHeader {
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub length: u16,
+    pub checksum: u16,
+}
+
+/// ICMP or ICMPv6 header fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcmpHeader {
+    pub msg_type: u8,
+    pub code: u8,
+    pub checksum: u16,
+}
+
+/// Every header [`parse_all`] managed to decode from a frame
+#[derive(Debug, Default, PartialEq)]
+pub struct ParseReport {
+    pub ethernet: Option<EthernetHeader>,
+    pub ipv4: Option<Ipv4Header>,
+    pub ipv6: Option<Ipv6Header>,
+    pub arp: Option<ArpHeader>,
+    pub tcp: Option<TcpHeader>,
+    pub udp: Option<UdpHeader>,
+    pub icmp: Option<IcmpHeader>,
+    /// The layer decoding stopped at, and why
+    pub error: Option<(Layer, ParsingError)>,
+}
+
+impl ParseReport {
+    /// Return true if every layer present in the frame was decoded
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Decode as many layers of `frame` as possible.
+///
+/// Unknown Ethertypes and transport protocols end the walk without an
+/// error, as do non-initial IPv4 fragments which carry no transport
+/// header.
+pub fn parse_all(frame: &[u8]) -> ParseReport {
+    let mut report = ParseReport::default();
+    if let Err((layer, e)) = walk(frame, &mut report) {
+        report.error = Some((layer, e));
+    }
+    report
+}
+
+fn walk(frame: &[u8], report: &mut ParseReport) -> Result<(), (Layer, ParsingError)> {
+    let at = |layer| move |e| (layer, e);
+
+    let eth = ethernet::EthernetFrame::new_with_validation(frame).map_err(at(Layer::Ethernet))?;
+    let ethertype = eth.ethertype();
+    report.ethernet = Some(EthernetHeader {
+        destination: eth.destination_mac(),
+        source: eth.source_mac(),
+        ethertype,
+        vlans: eth.vlan_stack(),
+    });
+
+    let (protocol, payload) = match ethertype {
+        ethernet::ETHERTYPE_IPV4 => {
+            let (header, payload) = parse_ipv4(eth.payload()).map_err(at(Layer::Ipv4))?;
+            report.ipv4 = Some(header);
+            if header.fragment_offset != 0 {
+                return Ok(());
+            }
+            (header.protocol, payload)
+        }
+        ethernet::ETHERTYPE_IPV6 => {
+            let (header, payload) = parse_ipv6(eth.payload()).map_err(at(Layer::Ipv6))?;
+            report.ipv6 = Some(header);
+            (header.upper_layer_protocol, payload)
+        }
+        ethernet::ETHERTYPE_ARP => {
+            report.arp = Some(parse_arp(eth.payload()).map_err(at(Layer::Arp))?);
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    match protocol {
+        tcp::PROTOCOL_TCP => {
+            report.tcp = Some(parse_tcp(payload).map_err(at(Layer::Tcp))?);
+        }
+        udp::PROTOCOL_UDP => {
+            report.udp = Some(parse_udp(payload).map_err(at(Layer::Udp))?);
+        }
+        icmp4::PROTOCOL_ICMP if report.ipv4.is_some() => {
+            let packet = icmp4::IcmpPacket::new_with_validation(payload).map_err(at(Layer::Icmp))?;
+            report.icmp = Some(IcmpHeader { msg_type: packet.msg_type(), code: packet.code(), checksum: packet.checksum() });
+        }
+        icmp6::NEXT_HEADER_ICMPV6 if report.ipv6.is_some() => {
+            report.icmp = Some(parse_icmpv6(payload).map_err(at(Layer::Icmp))?);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn parse_ipv4(buffer: &[u8]) -> Result<(Ipv4Header, &[u8]), ParsingError> {
+    let packet = ipv4::IPv4Packet::new_with_validation(buffer)?;
+    let header = Ipv4Header {
+        source: packet.source()?,
+        destination: packet.destination()?,
+        header_length: packet.ihl()?,
+        dscp: packet.dscp()?,
+        ecn: packet.ecn()?,
+        total_length: packet.total_length()?,
+        identification: packet.identification()?,
+        dont_frag: packet.dont_frag()?,
+        more_frags: packet.more_frags()?,
+        fragment_offset: packet.fragment_offset()?,
+        ttl: packet.ttl()?,
+        protocol: packet.protocol()?,
+        checksum: packet.checksum()?,
+    };
+    Ok((header, packet.payload()?))
+}
+
+fn parse_ipv6(buffer: &[u8]) -> Result<(Ipv6Header, &[u8]), ParsingError> {
+    let packet = ipv6::IPv6Packet::new_with_validation(buffer)?;
+    let (upper_layer_protocol, payload) = packet.upper_layer_protocol()?;
+    let header = Ipv6Header {
+        source: packet.source()?,
+        destination: packet.destination()?,
+        traffic_class: packet.traffic_class()?,
+        flow_label: packet.flow_label()?,
+        payload_length: packet.payload_length()?,
+        next_header: packet.next_header()?,
+        hop_limit: packet.hop_limit()?,
+        upper_layer_protocol,
+    };
+    Ok((header, payload))
+}
+
+fn parse_arp(buffer: &[u8]) -> Result<ArpHeader, ParsingError> {
+    let packet = arp::ArpPacket::new_with_validation(buffer)?;
+    Ok(ArpHeader {
+        hardware_type: packet.hardware_type(),
+        protocol_type: packet.protocol_type(),
+        operation: packet.operation(),
+        sender_mac: packet.sender_mac()?,
+        sender_ip: packet.sender_ip()?,
+        target_mac: packet.target_mac()?,
+        target_ip: packet.target_ip()?,
+    })
+}
+
+fn parse_tcp(buffer: &[u8]) -> Result<TcpHeader, ParsingError> {
+    let segment = tcp::TcpSegment::new_with_validation(buffer)?;
+    Ok(TcpHeader {
+        source_port: segment.source_port(),
+        destination_port: segment.destination_port(),
+        seq_number: segment.seq_number(),
+        ack_number: segment.ack_number(),
+        header_length: segment.header_length(),
+        flags: segment.flags(),
+        window: segment.window(),
+        checksum: segment.checksum(),
+        urgent_pointer: segment.urgent_pointer(),
+        payload_length: segment.payload().len(),
+    })
+}
+
+fn parse_udp(buffer: &[u8]) -> Result<UdpHeader, ParsingError> {
+    let datagram = udp::UdpDatagram::new_with_validation(buffer)?;
+    Ok(UdpHeader {
+        source_port: datagram.source_port(),
+        destination_port: datagram.dest_port(),
+        length: datagram.length(),
+        checksum: datagram.checksum(),
+    })
+}
+
+fn parse_icmpv6(buffer: &[u8]) -> Result<IcmpHeader, ParsingError> {
+    let packet = icmp6::Icmpv6Packet::new_with_validation(buffer)?;
+    Ok(IcmpHeader {
+        msg_type: packet.msg_type()?,
+        code: packet.code()?,
+        checksum: packet.checksum()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 02:00:00:00:00:01 -> 02:00:00:00:00:02, 10.0.0.1:1234 -> 10.0.0.2:80,
+    // TCP ACK|PSH carrying "data"
+    fn tcp_frame() -> Vec<u8> {
+        let mut frame = vec![
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x02, // Destination
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, // Source
+            0x08, 0x00, // Ethertype IPv4
+            0x45, 0x00, 0x00, 0x2c, // Version, IHL, DSCP, Total Length 44
+            0x12, 0x34, 0x40, 0x00, // Identification, DF
+            0x40, 0x06, 0x00, 0x00, // TTL 64, TCP, Checksum
+            10, 0, 0, 1, // Source
+            10, 0, 0, 2, // Destination
+            0x04, 0xd2, 0x00, 0x50, // Ports 1234 -> 80
+            0x00, 0x00, 0x00, 0x01, // Sequence
+            0x00, 0x00, 0x00, 0x02, // Acknowledgment
+            0x50, 0x18, 0x04, 0x00, // Header length 20, ACK|PSH, Window 1024
+            0x00, 0x00, 0x00, 0x00, // Checksum, Urgent pointer
+        ];
+        frame.extend_from_slice(b"data");
+        frame
+    }
+
+    #[test]
+    fn test_parse_full_tcp_frame() {
+        let report = parse_all(&tcp_frame());
+        assert!(report.is_complete());
+
+        let ethernet = report.ethernet.unwrap();
+        assert_eq!(ethernet.source, Mac::new(0x02, 0, 0, 0, 0, 0x01));
+        assert_eq!(ethernet.destination, Mac::new(0x02, 0, 0, 0, 0, 0x02));
+        assert_eq!(ethernet.ethertype, ethernet::ETHERTYPE_IPV4);
+        assert!(ethernet.vlans.is_empty());
+
+        let ipv4 = report.ipv4.unwrap();
+        assert_eq!(ipv4.source, IPv4::new(10, 0, 0, 1));
+        assert_eq!(ipv4.destination, IPv4::new(10, 0, 0, 2));
+        assert_eq!(ipv4.protocol, tcp::PROTOCOL_TCP);
+        assert_eq!(ipv4.total_length, 44);
+        assert!(ipv4.dont_frag);
+
+        let tcp = report.tcp.unwrap();
+        assert_eq!((tcp.source_port, tcp.destination_port), (1234, 80));
+        assert_eq!((tcp.seq_number, tcp.ack_number), (1, 2));
+        assert_eq!(tcp.flags, tcp::FLAG_ACK | tcp::FLAG_PSH);
+        assert_eq!(tcp.window, 1024);
+        assert_eq!(tcp.payload_length, 4);
+
+        assert!(report.ipv6.is_none() && report.udp.is_none() && report.icmp.is_none() && report.arp.is_none());
+    }
+
+    #[test]
+    fn test_parse_truncated_frame() {
+        // Cut the frame inside the TCP header; the IPv4 total length no
+        // longer fits, so decoding stops at the network layer.
+        let frame = tcp_frame();
+        let report = parse_all(&frame[..40]);
+        assert!(report.ethernet.is_some());
+        assert!(report.ipv4.is_none() && report.tcp.is_none());
+        assert_eq!(report.error.as_ref().map(|(layer, _)| *layer), Some(Layer::Ipv4));
+
+        // A frame too short for the Ethernet header yields nothing
+        let report = parse_all(&frame[..10]);
+        assert_eq!(report.ethernet, None);
+        assert_eq!(report.error.map(|(layer, _)| layer), Some(Layer::Ethernet));
+    }
+
+    #[test]
+    fn test_parse_truncated_transport() {
+        // IPv4 header consistent with a 10-byte TCP "segment"
+        let mut frame = tcp_frame();
+        frame.truncate(44);
+        frame[17] = 30;
+        let report = parse_all(&frame);
+        assert!(report.ipv4.is_some());
+        assert_eq!(report.error.map(|(layer, _)| layer), Some(Layer::Tcp));
+    }
+
+    #[test]
+    fn test_non_initial_fragment_stops_at_ip() {
+        let mut frame = tcp_frame();
+        frame[20] = 0x00;
+        frame[21] = 0x10; // Fragment offset 16 (128 bytes)
+        let report = parse_all(&frame);
+        assert!(report.is_complete());
+        assert!(report.ipv4.is_some() && report.tcp.is_none());
+    }
+
+    #[test]
+    fn test_parse_arp() {
+        let mut frame = tcp_frame()[..12].to_vec();
+        frame.extend_from_slice(&[0x08, 0x06]);
+        frame.extend_from_slice(&[
+            0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01, // Ethernet/IPv4 request
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 10, 0, 0, 1, // Sender
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 10, 0, 0, 2, // Target
+        ]);
+        let report = parse_all(&frame);
+        let arp = report.arp.unwrap();
+        assert_eq!(arp.operation, 1);
+        assert_eq!(arp.sender_ip, IPv4::new(10, 0, 0, 1));
+        assert_eq!(arp.target_ip, IPv4::new(10, 0, 0, 2));
+        assert!(report.error.is_none());
+    }
+}
@@ -0,0 +1,389 @@
+// src/parsers/tcp.rs
+use crate::utils::checksum;
+use super::{ParsingError, ValidationError};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// IPv4 Protocol / IPv6 Next Header value identifying TCP
+pub const PROTOCOL_TCP: u8 = 6;
+
+/// TCP control flags
+pub const FLAG_FIN: u8 = 0x01;
+pub const FLAG_SYN: u8 = 0x02;
+pub const FLAG_RST: u8 = 0x04;
+pub const FLAG_PSH: u8 = 0x08;
+pub const FLAG_ACK: u8 = 0x10;
+pub const FLAG_URG: u8 = 0x20;
+/// ECN-Echo and Congestion Window Reduced ([RFC 3168])
+///
+/// [RFC 3168]: https://datatracker.ietf.org/doc/html/rfc3168#section-6.1
+pub const FLAG_ECE: u8 = 0x40;
+pub const FLAG_CWR: u8 = 0x80;
+
+/// TCP option kinds
+///
+/// [TCP Option Kind Numbers]: https://www.iana.org/assignments/tcp-parameters
+pub const OPTION_EOL: u8 = 0;
+pub const OPTION_NOP: u8 = 1;
+pub const OPTION_MSS: u8 = 2;
+pub const OPTION_WINDOW_SCALE: u8 = 3;
+pub const OPTION_SACK_PERMITTED: u8 = 4;
+pub const OPTION_SACK: u8 = 5;
+pub const OPTION_TIMESTAMPS: u8 = 8;
+
+/// Transmission Control Protocol segment
+///
+/// [RFC 793]: https://datatracker.ietf.org/doc/html/rfc793#section-3.1
+
+//  0                   1                   2                   3
+//  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |          Source Port          |       Destination Port        |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                        Sequence Number                        |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                    Acknowledgment Number                      |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |  Data |       |C|E|U|A|P|R|S|F|                               |
+// | Offset| Rsrvd |W|C|R|C|S|S|Y|I|            Window             |
+// |       |       |R|E|G|K|H|T|N|N|                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |           Checksum            |         Urgent Pointer        |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                    Options                    |    Padding    |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(Debug, Clone)]
+pub struct TcpSegment<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> TcpSegment<'a> {
+    // Length of the header without options
+    const MIN_HEADER_LEN: usize = 20;
+
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    pub fn new_with_validation(buffer: &'a [u8]) -> Result<Self, ParsingError> {
+        if buffer.len() < Self::MIN_HEADER_LEN {
+            return Err(ParsingError::BufferUnderflow);
+        }
+        let segment = Self { buffer };
+        let header_len = segment.header_length();
+        if header_len < Self::MIN_HEADER_LEN || header_len > buffer.len() {
+            return Err(ValidationError::InvalidHeaderLength.into());
+        }
+        Ok(segment)
+    }
+
+    /// Return the Source Port
+    pub fn source_port(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[0], self.buffer[1]])
+    }
+
+    /// Return the Destination Port
+    pub fn destination_port(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[2], self.buffer[3]])
+    }
+
+    /// Return the Sequence Number
+    pub fn seq_number(&self) -> u32 {
+        u32::from_be_bytes([self.buffer[4], self.buffer[5], self.buffer[6], self.buffer[7]])
+    }
+
+    /// Return the Acknowledgment Number
+    pub fn ack_number(&self) -> u32 {
+        u32::from_be_bytes([self.buffer[8], self.buffer[9], self.buffer[10], self.buffer[11]])
+    }
+
+    /// Return the header length in bytes, from the Data Offset
+    pub fn header_length(&self) -> usize {
+        (self.buffer[12] >> 4) as usize * 4
+    }
+
+    /// Return the control flags
+    pub fn flags(&self) -> u8 {
+        self.buffer[13]
+    }
+
+    pub fn fin(&self) -> bool {
+        self.flags() & FLAG_FIN != 0
+    }
+
+    pub fn syn(&self) -> bool {
+        self.flags() & FLAG_SYN != 0
+    }
+
+    pub fn rst(&self) -> bool {
+        self.flags() & FLAG_RST != 0
+    }
+
+    pub fn psh(&self) -> bool {
+        self.flags() & FLAG_PSH != 0
+    }
+
+    pub fn ack(&self) -> bool {
+        self.flags() & FLAG_ACK != 0
+    }
+
+    pub fn urg(&self) -> bool {
+        self.flags() & FLAG_URG != 0
+    }
+
+    pub fn ece(&self) -> bool {
+        self.flags() & FLAG_ECE != 0
+    }
+
+    pub fn cwr(&self) -> bool {
+        self.flags() & FLAG_CWR != 0
+    }
+
+    /// Return the Window
+    pub fn window(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[14], self.buffer[15]])
+    }
+
+    /// Return the Checksum
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[16], self.buffer[17]])
+    }
+
+    /// Return the Urgent Pointer
+    pub fn urgent_pointer(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[18], self.buffer[19]])
+    }
+
+    /// Return the raw options
+    pub fn options(&self) -> &'a [u8] {
+        let header_len = self.header_length().min(self.buffer.len());
+        if header_len <= Self::MIN_HEADER_LEN {
+            return &[];
+        }
+        &self.buffer[Self::MIN_HEADER_LEN..header_len]
+    }
+
+    /// Return an iterator over the decoded options
+    #[cfg(feature = "alloc")]
+    pub fn options_iter(&self) -> TcpOptions<'a> {
+        TcpOptions { buffer: self.options() }
+    }
+
+    /// Return the segment data following the header
+    pub fn payload(&self) -> &'a [u8] {
+        &self.buffer[self.header_length().min(self.buffer.len())..]
+    }
+
+    /// Verify the checksum against the caller-supplied pseudo-header sum
+    /// (see `parsers::ipv4::pseudo_header_checksum` and
+    /// `parsers::ipv6::pseudo_header_checksum`).
+    pub fn verify_checksum(&self, pseudo_header: u32) -> bool {
+        checksum::checksum(self.buffer, pseudo_header) == 0
+    }
+
+    /// Return the sequence space consumed by the segment: the data length
+    /// plus one each for SYN and FIN.
+    pub fn segment_len(&self) -> u32 {
+        self.payload().len() as u32 + self.syn() as u32 + self.fin() as u32
+    }
+}
+
+/// A single decoded TCP option.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TcpOption<'a> {
+    EndOfList,
+    NoOp,
+    /// Maximum Segment Size
+    Mss(u16),
+    /// Window scale shift count
+    WindowScale(u8),
+    SackPermitted,
+    /// Selective acknowledgment blocks as (left edge, right edge)
+    Sack(Vec<(u32, u32)>),
+    Timestamps { tsval: u32, tsecr: u32 },
+    Unknown { kind: u8, data: &'a [u8] },
+}
+
+/// Iterator over the options of a TCP header.
+///
+/// Iteration stops at End of Option List, at the end of the header, or at
+/// the first option whose length is malformed.
+#[cfg(feature = "alloc")]
+pub struct TcpOptions<'a> {
+    buffer: &'a [u8],
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Iterator for TcpOptions<'a> {
+    type Item = TcpOption<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind = *self.buffer.first()?;
+        match kind {
+            OPTION_EOL => {
+                self.buffer = &[];
+                return Some(TcpOption::EndOfList);
+            }
+            OPTION_NOP => {
+                self.buffer = &self.buffer[1..];
+                return Some(TcpOption::NoOp);
+            }
+            _ => {}
+        }
+
+        let length = *self.buffer.get(1)? as usize;
+        if length < 2 || length > self.buffer.len() {
+            self.buffer = &[];
+            return None;
+        }
+        let data = &self.buffer[2..length];
+        self.buffer = &self.buffer[length..];
+
+        let option = match (kind, data.len()) {
+            (OPTION_MSS, 2) => TcpOption::Mss(u16::from_be_bytes([data[0], data[1]])),
+            (OPTION_WINDOW_SCALE, 1) => TcpOption::WindowScale(data[0]),
+            (OPTION_SACK_PERMITTED, 0) => TcpOption::SackPermitted,
+            (OPTION_SACK, len) if len > 0 && len % 8 == 0 => TcpOption::Sack(
+                data.chunks(8)
+                    .map(|b| (
+                        u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+                        u32::from_be_bytes([b[4], b[5], b[6], b[7]]),
+                    ))
+                    .collect(),
+            ),
+            (OPTION_TIMESTAMPS, 8) => TcpOption::Timestamps {
+                tsval: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+                tsecr: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            },
+            (OPTION_MSS, _) | (OPTION_WINDOW_SCALE, _) | (OPTION_SACK_PERMITTED, _)
+            | (OPTION_SACK, _) | (OPTION_TIMESTAMPS, _) => {
+                // A known option with the wrong length
+                self.buffer = &[];
+                return None;
+            }
+            _ => TcpOption::Unknown { kind, data },
+        };
+        Some(option)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SYN 40000 -> 80, seq 1000, window 65535, MSS option
+    static SYN_SEGMENT: [u8; 24] = [
+        0x9c, 0x40, 0x00, 0x50, // Source Port, Destination Port
+        0x00, 0x00, 0x03, 0xe8, // Sequence Number
+        0x00, 0x00, 0x00, 0x00, // Acknowledgment Number
+        0x60, 0x02, 0xff, 0xff, // Data Offset (6), Flags (SYN), Window
+        0x00, 0x00, 0x00, 0x00, // Checksum, Urgent Pointer
+        0x02, 0x04, 0x05, 0xb4, // MSS 1460
+    ];
+
+    #[test]
+    fn test_deconstruct() {
+        let segment = TcpSegment::new_with_validation(&SYN_SEGMENT).unwrap();
+        assert_eq!(segment.source_port(), 40000);
+        assert_eq!(segment.destination_port(), 80);
+        assert_eq!(segment.seq_number(), 1000);
+        assert_eq!(segment.ack_number(), 0);
+        assert_eq!(segment.header_length(), 24);
+        assert!(segment.syn() && !segment.ack() && !segment.fin() && !segment.rst());
+        assert_eq!(segment.window(), 65535);
+        assert_eq!(segment.options(), &[0x02, 0x04, 0x05, 0xb4]);
+        assert!(segment.payload().is_empty());
+        assert_eq!(segment.segment_len(), 1);
+    }
+
+    #[test]
+    fn test_ecn_flags() {
+        let mut bytes = SYN_SEGMENT;
+        assert!(!TcpSegment::new(&bytes).ece() && !TcpSegment::new(&bytes).cwr());
+        bytes[13] = FLAG_SYN | FLAG_ECE | FLAG_CWR;
+        let segment = TcpSegment::new(&bytes);
+        assert!(segment.ece() && segment.cwr() && segment.syn());
+        bytes[13] = FLAG_ACK | FLAG_ECE;
+        let segment = TcpSegment::new(&bytes);
+        assert!(segment.ece() && !segment.cwr());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            TcpSegment::new_with_validation(&SYN_SEGMENT[..19]).err(),
+            Some(ParsingError::BufferUnderflow)
+        );
+        let mut bad_offset = SYN_SEGMENT;
+        bad_offset[12] = 0x70; // 28 bytes claimed, 24 present
+        assert_eq!(
+            TcpSegment::new_with_validation(&bad_offset).err(),
+            Some(ValidationError::InvalidHeaderLength.into())
+        );
+    }
+
+    // SYN 40000 -> 80 with MSS 1460 and window scale 7
+    #[cfg(feature = "alloc")]
+    static SYN_WITH_WSCALE: [u8; 28] = [
+        0x9c, 0x40, 0x00, 0x50, // Source Port, Destination Port
+        0x00, 0x00, 0x03, 0xe8, // Sequence Number
+        0x00, 0x00, 0x00, 0x00, // Acknowledgment Number
+        0x70, 0x02, 0xff, 0xff, // Data Offset (7), Flags (SYN), Window
+        0x00, 0x00, 0x00, 0x00, // Checksum, Urgent Pointer
+        0x02, 0x04, 0x05, 0xb4, // MSS 1460
+        0x01, 0x03, 0x03, 0x07, // NOP, Window Scale 7
+    ];
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_options_iter_syn() {
+        let segment = TcpSegment::new_with_validation(&SYN_WITH_WSCALE).unwrap();
+        let options: Vec<_> = segment.options_iter().collect();
+        assert_eq!(
+            options,
+            vec![TcpOption::Mss(1460), TcpOption::NoOp, TcpOption::WindowScale(7)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_options_iter_all_kinds() {
+        let mut bytes = SYN_WITH_WSCALE[..20].to_vec();
+        bytes.extend_from_slice(&[OPTION_SACK_PERMITTED, 0x02]);
+        bytes.extend_from_slice(&[OPTION_TIMESTAMPS, 0x0a, 0, 0, 0, 1, 0, 0, 0, 2]);
+        bytes.extend_from_slice(&[OPTION_SACK, 0x0a, 0, 0, 0x03, 0xe8, 0, 0, 0x07, 0xd0]);
+        bytes.extend_from_slice(&[0xfe, 0x03, 0xaa]);
+        bytes.extend_from_slice(&[OPTION_EOL, 0x02, 0x04, 0x05]);
+        bytes[12] = ((bytes.len() / 4) as u8) << 4;
+        let segment = TcpSegment::new_with_validation(&bytes).unwrap();
+        let options: Vec<_> = segment.options_iter().collect();
+        assert_eq!(
+            options,
+            vec![
+                TcpOption::SackPermitted,
+                TcpOption::Timestamps { tsval: 1, tsecr: 2 },
+                TcpOption::Sack(vec![(1000, 2000)]),
+                TcpOption::Unknown { kind: 0xfe, data: &[0xaa] },
+                TcpOption::EndOfList,
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_options_iter_stops_at_header_end() {
+        // MSS claims 4 bytes but only 2 remain before the payload
+        let mut bytes = SYN_WITH_WSCALE;
+        bytes[12] = 0x60;
+        bytes[20..24].copy_from_slice(&[OPTION_NOP, OPTION_NOP, OPTION_MSS, 0x04]);
+        let options: Vec<_> = TcpSegment::new(&bytes).options_iter().collect();
+        assert_eq!(options, vec![TcpOption::NoOp, TcpOption::NoOp]);
+
+        let mut bad_len = SYN_WITH_WSCALE;
+        bad_len[21] = 0x03; // MSS with length 3
+        let options: Vec<_> = TcpSegment::new(&bad_len).options_iter().collect();
+        assert!(options.is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+// src/parsers/udp.rs
+use super::{ParsingError, PseudoHeader, ValidationError};
+use crate::utils::checksum;
+
+/// IPv4 Protocol / IPv6 Next Header value identifying UDP
+pub const PROTOCOL_UDP: u8 = 17;
+
+/// User Datagram Protocol datagram
+///
+/// [RFC 768]: https://datatracker.ietf.org/doc/html/rfc768
+
+//  0      7 8     15 16    23 24    31
+// +--------+--------+--------+--------+
+// |     Source      |   Destination   |
+// |      Port       |      Port       |
+// +--------+--------+--------+--------+
+// |                 |                 |
+// |     Length      |    Checksum     |
+// +--------+--------+--------+--------+
+// |          data octets ...
+// +---------------- ...
+#[derive(Debug, Clone)]
+pub struct UdpDatagram<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> UdpDatagram<'a> {
+    // Source Port, Destination Port, Length and Checksum
+    const HEADER_LEN: usize = 8;
+
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    pub fn new_with_validation(buffer: &'a [u8]) -> Result<Self, ParsingError> {
+        if buffer.len() < Self::HEADER_LEN {
+            return Err(ParsingError::BufferUnderflow);
+        }
+        let datagram = Self { buffer };
+        let length = datagram.length() as usize;
+        if length < Self::HEADER_LEN || length != buffer.len() {
+            return Err(ValidationError::InvalidPacketLength.into());
+        }
+        Ok(datagram)
+    }
+
+    /// Return the Source Port
+    pub fn source_port(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[0], self.buffer[1]])
+    }
+
+    /// Return the Destination Port
+    pub fn dest_port(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[2], self.buffer[3]])
+    }
+
+    /// Return the Length of header and data
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[4], self.buffer[5]])
+    }
+
+    /// Return the Checksum
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.buffer[6], self.buffer[7]])
+    }
+
+    /// Return the data following the header
+    pub fn payload(&self) -> &'a [u8] {
+        &self.buffer[Self::HEADER_LEN..]
+    }
+
+    /// Verify the checksum over the pseudo-header built from `src` and
+    /// `dst`, which may be IPv4 or IPv6 addresses.
+    ///
+    /// Over IPv4 a zero checksum means none was computed and is accepted.
+    pub fn verify_checksum<A: PseudoHeader>(&self, src: &A, dst: &A) -> bool {
+        if self.checksum() == 0 {
+            return A::UDP_ZERO_CHECKSUM_ALLOWED;
+        }
+        let pseudo_header = A::pseudo_header_checksum(src, dst, PROTOCOL_UDP, self.buffer.len() as u32);
+        checksum::checksum(self.buffer, pseudo_header) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::ipv4::IPv4;
+    use crate::address::ipv6::IPv6;
+
+    // DNS query for example.com/A, 192.168.1.10:53000 -> 8.8.8.8:53
+    static DNS_QUERY: [u8; 37] = [
+        0xcf, 0x08, 0x00, 0x35, 0x00, 0x25, 0x7d, 0x02, // UDP header
+        0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // DNS header
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, // QNAME
+        0x00, 0x01, 0x00, 0x01, // QTYPE A, QCLASS IN
+    ];
+
+    const SRC: IPv4 = IPv4([192, 168, 1, 10]);
+    const DST: IPv4 = IPv4([8, 8, 8, 8]);
+
+    #[test]
+    fn test_parse_dns_query() {
+        let datagram = UdpDatagram::new_with_validation(&DNS_QUERY).unwrap();
+        assert_eq!(datagram.source_port(), 53000);
+        assert_eq!(datagram.dest_port(), 53);
+        assert_eq!(datagram.length(), 37);
+        assert_eq!(datagram.checksum(), 0x7d02);
+        assert_eq!(&datagram.payload()[..2], &[0x12, 0x34]);
+        assert!(datagram.verify_checksum(&SRC, &DST));
+        assert!(!datagram.verify_checksum(&SRC, &IPv4([8, 8, 4, 4])));
+    }
+
+    #[test]
+    fn test_reject_truncated() {
+        assert_eq!(
+            UdpDatagram::new_with_validation(&DNS_QUERY[..7]).err(),
+            Some(ParsingError::BufferUnderflow)
+        );
+        assert_eq!(
+            UdpDatagram::new_with_validation(&DNS_QUERY[..30]).err(),
+            Some(ValidationError::InvalidPacketLength.into())
+        );
+    }
+
+    #[test]
+    fn test_zero_checksum() {
+        let mut bytes = DNS_QUERY;
+        bytes[6] = 0;
+        bytes[7] = 0;
+        let datagram = UdpDatagram::new(&bytes);
+        assert!(datagram.verify_checksum(&SRC, &DST));
+        let v6 = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+        assert!(!datagram.verify_checksum(&v6, &v6));
+    }
+}
@@ -0,0 +1,366 @@
+// src/protocols/arp.rs
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use actix::{Actor, Context, Handler, Message, Recipient};
+use log::debug;
+
+use crate::address::ipv4::IPv4;
+use crate::address::mac::{self, Mac};
+use crate::assemblers;
+use crate::assemblers::ethernet::EtherType;
+use crate::io::messages::Resolve;
+use crate::io::network_io::SendPacket;
+use crate::parsers::arp::{ArpPacket, Operation};
+use crate::parsers::ethernet::ETHER_MIN_LENGTH;
+use crate::utils::time::{Clock, SystemClock};
+
+/// Default time a learned address stays in the cache.
+pub const DEFAULT_ENTRY_TTL: Duration = Duration::from_secs(60);
+
+/// Default number of addresses the cache holds.
+pub const DEFAULT_MAX_ENTRIES: usize = 256;
+
+/// Default minimum time between two requests for the same address, the
+/// rate RFC 1122 section 2.3.2.1 recommends.
+pub const DEFAULT_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Cache of IPv4 to MAC address mappings learned from ARP.
+///
+/// `Resolve` answers from the cache, broadcasting an ARP request through
+/// `NetworkIO` on a miss, at most once per `request_interval` for each
+/// address; the reply is learned once it is passed in with `Learn`.
+/// Entries expire `entry_ttl` after they were last learned. When the cache
+/// holds `max_entries` addresses, inserting another evicts an expired
+/// entry, or else the oldest one.
+pub struct ArpCache {
+    entries: HashMap<IPv4, (Mac, Instant)>,
+    entry_ttl: Duration,
+    max_entries: usize,
+    // Addresses with an unanswered request, and when it was last sent
+    pending: HashMap<IPv4, Instant>,
+    request_interval: Duration,
+    // Addresses requests are sent from
+    mac: Mac,
+    ip: IPv4,
+    network: Recipient<SendPacket>,
+    nic_index: usize,
+    clock: Box<dyn Clock>,
+}
+
+impl ArpCache {
+    /// Creates an empty cache that sends requests from `mac`/`ip` through
+    /// the first NIC of `network`.
+    pub fn new(mac: Mac, ip: IPv4, network: Recipient<SendPacket>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            entry_ttl: DEFAULT_ENTRY_TTL,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            pending: HashMap::new(),
+            request_interval: DEFAULT_REQUEST_INTERVAL,
+            mac,
+            ip,
+            network,
+            nic_index: 0,
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    /// Expire entries `ttl` after they were learned.
+    pub fn with_entry_ttl(mut self, ttl: Duration) -> Self {
+        self.entry_ttl = ttl;
+        self
+    }
+
+    /// Hold at most `max_entries` addresses.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Send at most one request per `interval` for each address.
+    pub fn with_request_interval(mut self, interval: Duration) -> Self {
+        self.request_interval = interval;
+        self
+    }
+
+    /// Send requests through the NIC at `nic_index` instead of the first.
+    pub fn with_nic_index(mut self, nic_index: usize) -> Self {
+        self.nic_index = nic_index;
+        self
+    }
+
+    /// Read the time from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Return the MAC address cached for `ip`, dropping the entry if it
+    /// has expired.
+    pub fn lookup(&mut self, ip: IPv4) -> Option<Mac> {
+        let (mac, learned) = *self.entries.get(&ip)?;
+        if self.clock.now().duration_since(learned) >= self.entry_ttl {
+            self.entries.remove(&ip);
+            return None;
+        }
+        Some(mac)
+    }
+
+    /// Cache `mac` for `ip`, replacing any earlier entry. A full cache
+    /// first drops its expired entries, or else its oldest one.
+    pub fn insert(&mut self, ip: IPv4, mac: Mac) {
+        let now = self.clock.now();
+        if !self.entries.contains_key(&ip) && self.entries.len() >= self.max_entries {
+            let ttl = self.entry_ttl;
+            self.entries.retain(|_, (_, learned)| now.duration_since(*learned) < ttl);
+            if self.entries.len() >= self.max_entries {
+                let oldest = self.entries.iter().min_by_key(|(_, (_, learned))| *learned).map(|(ip, _)| *ip);
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        if self.max_entries > 0 {
+            self.entries.insert(ip, (mac, now));
+        }
+    }
+
+    /// Returns the number of cached entries, including expired ones not
+    /// yet looked up.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Query if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Broadcast an ARP request asking who has `target`, unless one went
+    // out less than `request_interval` ago. Requests left unanswered for
+    // `entry_ttl` are forgotten once as many are pending as entries fit.
+    fn request(&mut self, target: IPv4) {
+        let now = self.clock.now();
+        if let Some(sent) = self.pending.get(&target) {
+            if now.duration_since(*sent) < self.request_interval {
+                return;
+            }
+        } else if self.pending.len() >= self.max_entries {
+            let ttl = self.entry_ttl;
+            self.pending.retain(|_, sent| now.duration_since(*sent) < ttl);
+            if self.pending.len() >= self.max_entries {
+                debug!("Too many pending ARP requests, not asking for {}", target);
+                return;
+            }
+        }
+        self.pending.insert(target, now);
+
+        let mut frame = vec![0u8; ETHER_MIN_LENGTH + assemblers::arp::PACKET_LEN];
+        let mut ethernet = assemblers::ethernet::EthernetFrame::new(&mut frame);
+        ethernet.set_destination(mac::BROADCAST);
+        ethernet.set_source(self.mac);
+        ethernet.set_ethertype(EtherType::Arp);
+        ethernet
+            .mut_payload_ref()
+            .copy_from_slice(&assemblers::arp::build_request(self.mac, self.ip, target));
+        debug!("Sending ARP request for {}", target);
+        self.network.do_send(SendPacket { nic_index: self.nic_index, data: frame });
+    }
+}
+
+impl Actor for ArpCache {
+    type Context = Context<Self>;
+}
+
+impl Handler<Resolve> for ArpCache {
+    type Result = Option<Mac>;
+
+    fn handle(&mut self, msg: Resolve, _ctx: &mut Context<Self>) -> Self::Result {
+        let mac = self.lookup(msg.0);
+        if mac.is_none() {
+            self.request(msg.0);
+        }
+        mac
+    }
+}
+
+/// Message carrying an observed ARP packet (the Ethernet payload).
+///
+/// Following RFC 826, any packet refreshes the entry of a sender already
+/// in the cache. A new address is only learned from a reply to a request
+/// the cache sent; other packets are ignored.
+pub struct Learn(pub Vec<u8>);
+
+impl Message for Learn {
+    type Result = ();
+}
+
+impl Handler<Learn> for ArpCache {
+    type Result = ();
+
+    fn handle(&mut self, msg: Learn, _ctx: &mut Context<Self>) {
+        let Ok(packet) = ArpPacket::new_with_validation(&msg.0) else {
+            return;
+        };
+        let (Ok(ip), Ok(mac)) = (packet.sender_ip(), packet.sender_mac()) else {
+            return;
+        };
+        let requested = packet.operation_type() == Ok(Operation::Reply) && self.pending.remove(&ip).is_some();
+        if requested || self.entries.contains_key(&ip) {
+            debug!("Learned {} is at {}", ip, mac);
+            self.insert(ip, mac);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers;
+    use crate::utils::time::MockClock;
+    use std::io::Result as IoResult;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+
+    const LOCAL_MAC: Mac = Mac::new(0x02, 0, 0, 0, 0, 0x01);
+    const LOCAL_IP: IPv4 = IPv4::new(10, 0, 0, 1);
+    const PEER_MAC: Mac = Mac::new(0x02, 0, 0, 0, 0, 0x02);
+    const PEER_IP: IPv4 = IPv4::new(10, 0, 0, 2);
+
+    // Records the frames the cache sends
+    #[derive(Default)]
+    struct Network {
+        sent: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl Actor for Network {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<SendPacket> for Network {
+        type Result = IoResult<()>;
+
+        fn handle(&mut self, msg: SendPacket, _ctx: &mut Context<Self>) -> Self::Result {
+            self.sent.lock().unwrap().push(msg.data);
+            Ok(())
+        }
+    }
+
+    fn cache() -> (ArpCache, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let network = Network::default();
+        let sent = network.sent.clone();
+        (ArpCache::new(LOCAL_MAC, LOCAL_IP, network.start().recipient()), sent)
+    }
+
+    #[actix_rt::test]
+    async fn test_hit_after_learning_reply() {
+        let (cache, _sent) = cache();
+        let cache = cache.start();
+        let reply = assemblers::arp::build_reply(PEER_MAC, PEER_IP, LOCAL_MAC, LOCAL_IP);
+        assert_eq!(cache.send(Resolve(PEER_IP)).await.unwrap(), None);
+        cache.send(Learn(reply.to_vec())).await.unwrap();
+        assert_eq!(cache.send(Resolve(PEER_IP)).await.unwrap(), Some(PEER_MAC));
+    }
+
+    #[actix_rt::test]
+    async fn test_ignores_unsolicited_packets_for_unknown_senders() {
+        let (cache, _sent) = cache();
+        let cache = cache.start();
+        let reply = assemblers::arp::build_reply(PEER_MAC, PEER_IP, LOCAL_MAC, LOCAL_IP);
+        cache.send(Learn(reply.to_vec())).await.unwrap();
+        let announcement = assemblers::arp::build_request(PEER_MAC, PEER_IP, PEER_IP);
+        cache.send(Learn(announcement.to_vec())).await.unwrap();
+        assert_eq!(cache.send(Resolve(PEER_IP)).await.unwrap(), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_refreshes_known_senders() {
+        let (mut cache, _sent) = cache();
+        cache.insert(PEER_IP, LOCAL_MAC);
+        let cache = cache.start();
+
+        // A gratuitous ARP updates the entry
+        let announcement = assemblers::arp::build_request(PEER_MAC, PEER_IP, PEER_IP);
+        cache.send(Learn(announcement.to_vec())).await.unwrap();
+        assert_eq!(cache.send(Resolve(PEER_IP)).await.unwrap(), Some(PEER_MAC));
+    }
+
+    #[actix_rt::test]
+    async fn test_requests_are_rate_limited() {
+        let clock = Rc::new(MockClock::new());
+        let (cache, sent) = cache();
+        let mut cache = cache.with_clock(clock.clone());
+        cache.request(PEER_IP);
+        cache.request(PEER_IP);
+        clock.advance(DEFAULT_REQUEST_INTERVAL);
+        cache.request(PEER_IP);
+        // Let the network actor process the requests
+        for _ in 0..10 {
+            actix_rt::task::yield_now().await;
+        }
+        assert_eq!(sent.lock().unwrap().len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_full_cache_evicts_expired_then_oldest() {
+        let clock = Rc::new(MockClock::new());
+        let (cache, _sent) = cache();
+        let mut cache = cache.with_max_entries(2).with_entry_ttl(Duration::from_secs(30)).with_clock(clock.clone());
+        let ips = [IPv4::new(10, 0, 0, 2), IPv4::new(10, 0, 0, 3), IPv4::new(10, 0, 0, 4), IPv4::new(10, 0, 0, 5)];
+
+        cache.insert(ips[0], PEER_MAC);
+        clock.advance(Duration::from_secs(20));
+        cache.insert(ips[1], PEER_MAC);
+        clock.advance(Duration::from_secs(10));
+        // The first entry has expired and makes room
+        cache.insert(ips[2], PEER_MAC);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.lookup(ips[0]), None);
+
+        // Nothing has expired, so the oldest goes
+        cache.insert(ips[3], PEER_MAC);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.lookup(ips[1]), None);
+        assert_eq!(cache.lookup(ips[2]), Some(PEER_MAC));
+        assert_eq!(cache.lookup(ips[3]), Some(PEER_MAC));
+    }
+
+    #[actix_rt::test]
+    async fn test_miss_sends_request() {
+        let (cache, sent) = cache();
+        let cache = cache.start();
+        assert_eq!(cache.send(Resolve(PEER_IP)).await.unwrap(), None);
+        // Let the network actor process the request
+        for _ in 0..10 {
+            if !sent.lock().unwrap().is_empty() {
+                break;
+            }
+            actix_rt::task::yield_now().await;
+        }
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let frame = parsers::ethernet::EthernetFrame::new(&sent[0]);
+        assert_eq!(frame.destination_mac(), mac::BROADCAST);
+        assert_eq!(frame.ethertype_parsed(), Some(EtherType::Arp));
+        let request = ArpPacket::new_with_validation(frame.payload()).unwrap();
+        assert_eq!(request.operation_type(), Ok(Operation::Request));
+        assert_eq!(request.sender_ip(), Ok(LOCAL_IP));
+        assert_eq!(request.target_ip(), Ok(PEER_IP));
+    }
+
+    #[actix_rt::test]
+    async fn test_entries_expire() {
+        let clock = Rc::new(MockClock::new());
+        let (cache, _sent) = cache();
+        let mut cache = cache.with_entry_ttl(Duration::from_secs(30)).with_clock(clock.clone());
+        cache.insert(PEER_IP, PEER_MAC);
+
+        clock.advance(Duration::from_secs(29));
+        assert_eq!(cache.lookup(PEER_IP), Some(PEER_MAC));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(cache.lookup(PEER_IP), None);
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+// src/protocols/flow.rs
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::address::ipv4::IPv4;
+use crate::address::ipv6::IPv6;
+
+/// A key that can be assigned to a worker shard.
+///
+/// `stable_hash` must return the same value for the same key in every
+/// process, so that a flow is always handled by the same worker.
+pub trait FlowKey: Eq + Hash {
+    fn stable_hash(&self) -> u64;
+}
+
+impl FlowKey for IPv4 {
+    fn stable_hash(&self) -> u64 {
+        IPv4::stable_hash(self)
+    }
+}
+
+impl FlowKey for IPv6 {
+    fn stable_hash(&self) -> u64 {
+        IPv6::stable_hash(self)
+    }
+}
+
+/// Flow table sharded across a fixed number of workers.
+///
+/// Each key lives in the shard chosen by its `stable_hash`, so a worker that
+/// owns a shard (see `shards_mut`) never touches another worker's entries
+/// and no cross-core lock is needed.
+#[derive(Debug, Clone)]
+pub struct FlowMap<K, V> {
+    shards: Vec<HashMap<K, V>>,
+}
+
+impl<K: FlowKey, V> FlowMap<K, V> {
+    /// Construct a flow map with one shard per worker.
+    ///
+    /// Panics if `num_workers` is zero.
+    pub fn new(num_workers: usize) -> Self {
+        assert!(num_workers > 0, "FlowMap needs at least one worker");
+        FlowMap {
+            shards: (0..num_workers).map(|_| HashMap::new()).collect(),
+        }
+    }
+
+    /// Return the number of shards.
+    pub fn num_workers(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Return the index of the shard that owns `key`.
+    pub fn shard_index(&self, key: &K) -> usize {
+        (key.stable_hash() % self.shards.len() as u64) as usize
+    }
+
+    /// Return the entry for `key`, if any.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.shards[self.shard_index(key)].get(key)
+    }
+
+    /// Return a mutable reference to the entry for `key`, if any.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let index = self.shard_index(key);
+        self.shards[index].get_mut(key)
+    }
+
+    /// Insert an entry, returning the previous value for `key`.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let index = self.shard_index(&key);
+        self.shards[index].insert(key, value)
+    }
+
+    /// Remove the entry for `key`, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.shard_index(key);
+        self.shards[index].remove(key)
+    }
+
+    /// Return the total number of entries across all shards.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(HashMap::len).sum()
+    }
+
+    /// Query if no shard holds an entry.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(HashMap::is_empty)
+    }
+
+    /// Return the shard at `index`.
+    pub fn shard(&self, index: usize) -> &HashMap<K, V> {
+        &self.shards[index]
+    }
+
+    /// Return every shard mutably, e.g. to hand one to each worker.
+    pub fn shards_mut(&mut self) -> std::slice::IterMut<'_, HashMap<K, V>> {
+        self.shards.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_index_is_stable() {
+        let map: FlowMap<IPv4, ()> = FlowMap::new(4);
+        let addr = IPv4::new(192, 168, 1, 1);
+        assert_eq!(map.shard_index(&addr), (addr.stable_hash() % 4) as usize);
+        assert_eq!(map.shard_index(&addr), map.shard_index(&IPv4::new(192, 168, 1, 1)));
+    }
+
+    #[test]
+    fn test_same_bucket_same_shard() {
+        let mut map: FlowMap<IPv4, u32> = FlowMap::new(4);
+        let a = IPv4::new(10, 0, 0, 1);
+        let index = map.shard_index(&a);
+        // Find another key owned by the same shard.
+        let b = (2..=255)
+            .map(|octet| IPv4::new(10, 0, 0, octet))
+            .find(|key| map.shard_index(key) == index)
+            .unwrap();
+
+        map.insert(a, 1);
+        map.insert(b, 2);
+        assert_eq!(map.shard(index).len(), 2);
+        assert_eq!(map.get(&a), Some(&1));
+        assert_eq!(map.get(&b), Some(&2));
+    }
+
+    #[test]
+    fn test_distinct_buckets_independent() {
+        let mut map: FlowMap<IPv6, u32> = FlowMap::new(2);
+        let a = IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+        let b = (2..=u16::MAX)
+            .map(|seg| IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, seg))
+            .find(|key| map.shard_index(key) != map.shard_index(&a))
+            .unwrap();
+        map.insert(a, 1);
+        map.insert(b, 2);
+
+        // Each worker mutates only its own shard.
+        for shard in map.shards_mut() {
+            for value in shard.values_mut() {
+                *value *= 10;
+            }
+            assert_eq!(shard.len(), 1);
+        }
+        assert_eq!(map.get(&a), Some(&10));
+        assert_eq!(map.get(&b), Some(&20));
+
+        assert_eq!(map.remove(&a), Some(10));
+        assert_eq!(map.get(&b), Some(&20));
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,48 @@
+// src/protocols/icmp.rs
+use crate::parsers::icmp4::{IcmpPacket, TYPE_ECHO_REPLY, TYPE_ECHO_REQUEST};
+use crate::utils::checksum;
+
+/// Build the Echo Reply answering `request`, or `None` if it is not an
+/// Echo Request.
+///
+/// The identifier, sequence number and data are echoed back unchanged.
+pub fn echo_reply(request: &IcmpPacket) -> Option<Vec<u8>> {
+    if request.msg_type() != TYPE_ECHO_REQUEST {
+        return None;
+    }
+    let (identifier, sequence) = (request.identifier()?, request.sequence()?);
+    let mut reply = vec![TYPE_ECHO_REPLY, 0, 0, 0];
+    reply.extend_from_slice(&identifier.to_be_bytes());
+    reply.extend_from_slice(&sequence.to_be_bytes());
+    reply.extend_from_slice(request.echo_payload()?);
+    let checksum = checksum::checksum(&reply, 0);
+    reply[2..4].copy_from_slice(&checksum.to_be_bytes());
+    Some(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Echo Request, id 0x1234, seq 1, data "ping"
+    static ECHO_REQUEST: [u8; 12] = [
+        0x08, 0x00, 0x06, 0xfa, // Type, Code, Checksum
+        0x12, 0x34, 0x00, 0x01, // Identifier, Sequence Number
+        b'p', b'i', b'n', b'g', // Data
+    ];
+
+    #[test]
+    fn test_echo_reply() {
+        let reply = echo_reply(&IcmpPacket::new(&ECHO_REQUEST)).unwrap();
+        let packet = IcmpPacket::new_with_validation(&reply).unwrap();
+        assert_eq!(packet.msg_type(), TYPE_ECHO_REPLY);
+        assert_eq!(packet.checksum(), 0x0efa);
+        assert_eq!(packet.identifier(), Some(0x1234));
+        assert_eq!(packet.sequence(), Some(1));
+        assert_eq!(packet.echo_payload(), Some(&b"ping"[..]));
+        assert!(packet.verify_checksum());
+
+        // Only requests are answered.
+        assert_eq!(echo_reply(&packet), None);
+    }
+}
@@ -0,0 +1,424 @@
+// src/protocols/ipv4.rs
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::parsers::ipv4::{IPv4Packet, Key};
+use crate::parsers::{ParsingError, ValidationError};
+use crate::utils::time::Clock;
+
+// Largest payload an IPv4 datagram can carry
+const MAX_PAYLOAD_LEN: usize = 65535 - 20;
+
+/// Default time an incomplete datagram is kept before it is discarded.
+pub const DEFAULT_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Bounds on the memory the reassembler may hold, so a flood of
+/// incomplete datagrams cannot exhaust it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReassemblyLimits {
+    /// Bytes buffered across all incomplete datagrams, counting both the
+    /// payload and the record of which payload bytes have arrived. Above
+    /// this the oldest incomplete datagrams are discarded.
+    pub max_buffered_bytes: usize,
+    /// Fragments accepted for a single datagram before it is discarded.
+    pub max_fragments: usize,
+    /// Incomplete datagrams held at once. A fragment starting another one
+    /// first discards the oldest.
+    pub max_datagrams: usize,
+}
+
+impl Default for ReassemblyLimits {
+    fn default() -> Self {
+        ReassemblyLimits {
+            max_buffered_bytes: 4 * 1024 * 1024,
+            max_fragments: 64,
+            max_datagrams: 256,
+        }
+    }
+}
+
+// Fragments received so far for one datagram
+#[derive(Debug)]
+struct PartialDatagram {
+    first_seen: Instant,
+    fragments: usize,
+    data: Vec<u8>,
+    // Which bytes of `data` have been received
+    filled: Vec<bool>,
+    filled_count: usize,
+    // Payload length, known once the last fragment (MF clear) arrives
+    total_len: Option<usize>,
+}
+
+impl PartialDatagram {
+    fn new(now: Instant) -> Self {
+        PartialDatagram {
+            first_seen: now,
+            fragments: 0,
+            data: Vec::new(),
+            filled: Vec::new(),
+            filled_count: 0,
+            total_len: None,
+        }
+    }
+
+    // Copy in the bytes of a fragment not already covered by an earlier one
+    fn insert(&mut self, offset: usize, payload: &[u8]) {
+        let end = offset + payload.len();
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+            self.filled.resize(end, false);
+        }
+        for (i, byte) in payload.iter().enumerate() {
+            if !self.filled[offset + i] {
+                self.filled[offset + i] = true;
+                self.data[offset + i] = *byte;
+                self.filled_count += 1;
+            }
+        }
+    }
+
+    // Bytes held for the datagram: the payload and its `filled` map
+    fn footprint(&self) -> usize {
+        self.data.len() + self.filled.len()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.total_len == Some(self.filled_count) && self.data.len() == self.filled_count
+    }
+}
+
+/// Reassembles fragmented IPv4 datagrams.
+///
+/// Fragments may arrive in any order. Where fragments overlap, the bytes
+/// received first are kept. Datagrams still incomplete `timeout` after
+/// their first fragment are discarded, as are datagrams that exceed the
+/// `ReassemblyLimits`.
+///
+/// Time is read from the `Clock` passed to each call.
+#[derive(Debug)]
+pub struct FragmentReassembler {
+    timeout: Duration,
+    limits: ReassemblyLimits,
+    datagrams: HashMap<Key, PartialDatagram>,
+    // Datagrams in the order their first fragment arrived, so the oldest
+    // is found without a scan. Entries whose datagram has since completed
+    // or been discarded are skipped when reached.
+    arrivals: VecDeque<(Instant, Key)>,
+    // Sum of the footprints of all incomplete datagrams
+    buffered: usize,
+    dropped_over_limit: u64,
+}
+
+impl Default for FragmentReassembler {
+    fn default() -> Self {
+        Self::with_timeout(DEFAULT_REASSEMBLY_TIMEOUT)
+    }
+}
+
+impl FragmentReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_limits(timeout, ReassemblyLimits::default())
+    }
+
+    pub fn with_limits(timeout: Duration, limits: ReassemblyLimits) -> Self {
+        FragmentReassembler {
+            timeout,
+            limits,
+            datagrams: HashMap::new(),
+            arrivals: VecDeque::new(),
+            buffered: 0,
+            dropped_over_limit: 0,
+        }
+    }
+
+    /// Return the number of datagrams awaiting more fragments.
+    pub fn pending(&self) -> usize {
+        self.datagrams.len()
+    }
+
+    /// Return the bytes held for incomplete datagrams, counted as in
+    /// `ReassemblyLimits::max_buffered_bytes`.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered
+    }
+
+    /// Return the number of incomplete datagrams discarded for exceeding
+    /// the `ReassemblyLimits`.
+    pub fn dropped_over_limit(&self) -> u64 {
+        self.dropped_over_limit
+    }
+
+    fn remove(&mut self, key: &Key) -> Option<PartialDatagram> {
+        let datagram = self.datagrams.remove(key)?;
+        self.buffered -= datagram.footprint();
+        // Stale arrivals are dropped lazily; compact once they dominate
+        if self.arrivals.len() > 2 * self.datagrams.len() + 16 {
+            let datagrams = &self.datagrams;
+            self.arrivals.retain(|(seen, key)| datagrams.get(key).is_some_and(|d| d.first_seen == *seen));
+        }
+        Some(datagram)
+    }
+
+    // Return the key of the oldest incomplete datagram, dropping stale
+    // arrivals on the way
+    fn oldest(&mut self) -> Option<(Instant, Key)> {
+        while let Some(&(seen, key)) = self.arrivals.front() {
+            if self.datagrams.get(&key).is_some_and(|d| d.first_seen == seen) {
+                return Some((seen, key));
+            }
+            self.arrivals.pop_front();
+        }
+        None
+    }
+
+    // Discard the oldest incomplete datagram for exceeding a limit
+    fn drop_oldest(&mut self) -> bool {
+        match self.oldest() {
+            Some((_, key)) => {
+                self.remove(&key);
+                self.dropped_over_limit += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Discard the oldest incomplete datagrams until the buffered bytes are
+    // back within the limit
+    fn enforce_byte_limit(&mut self) {
+        while self.buffered > self.limits.max_buffered_bytes && self.drop_oldest() {}
+    }
+
+    /// Accept a packet, returning the reassembled payload once every
+    /// fragment of its datagram has arrived.
+    ///
+    /// Unfragmented packets are returned immediately.
+    pub fn accept(&mut self, packet: &IPv4Packet, clock: &dyn Clock) -> Result<Option<Vec<u8>>, ParsingError> {
+        self.evict_expired(clock);
+        let now = clock.now();
+
+        let payload = packet.payload()?;
+        let offset = packet.fragment_offset()? as usize * 8;
+        let more_frags = packet.more_frags()?;
+        if offset == 0 && !more_frags {
+            return Ok(Some(payload.to_vec()));
+        }
+
+        // An empty fragment other than the last adds nothing to the datagram
+        let end = offset + payload.len();
+        if end > MAX_PAYLOAD_LEN || (more_frags && payload.is_empty()) {
+            return Err(ValidationError::InvalidPacketLength.into());
+        }
+
+        let key = packet.key()?;
+        if !self.datagrams.contains_key(&key) {
+            while self.datagrams.len() >= self.limits.max_datagrams && self.drop_oldest() {}
+            if self.limits.max_datagrams == 0 {
+                return Ok(None);
+            }
+            self.datagrams.insert(key, PartialDatagram::new(now));
+            self.arrivals.push_back((now, key));
+        }
+        let datagram = self.datagrams.get_mut(&key).expect("datagram present");
+        datagram.fragments += 1;
+        if datagram.fragments > self.limits.max_fragments {
+            self.remove(&key);
+            self.dropped_over_limit += 1;
+            return Ok(None);
+        }
+        if !more_frags {
+            datagram.total_len = Some(end);
+        }
+        let before = datagram.footprint();
+        datagram.insert(offset, payload);
+        self.buffered += datagram.footprint() - before;
+
+        if datagram.is_complete() {
+            let datagram = self.remove(&key).expect("datagram present");
+            return Ok(Some(datagram.data));
+        }
+        self.enforce_byte_limit();
+        Ok(None)
+    }
+
+    /// Discard datagrams whose first fragment arrived `timeout` or more
+    /// ago, returning how many were dropped.
+    pub fn evict_expired(&mut self, clock: &dyn Clock) -> usize {
+        let now = clock.now();
+        let mut evicted = 0;
+        while let Some((seen, key)) = self.oldest() {
+            if now.saturating_duration_since(seen) < self.timeout {
+                break;
+            }
+            self.remove(&key);
+            evicted += 1;
+        }
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::time::MockClock;
+
+    // Build an IPv4 fragment 10.0.0.1 -> 10.0.0.2 carrying `payload`
+    fn fragment(id: u16, offset_units: u16, more: bool, payload: &[u8]) -> Vec<u8> {
+        let total = 20 + payload.len();
+        let flags_offset = offset_units | if more { 0x2000 } else { 0 };
+        let mut buf = vec![
+            0x45, 0x00, (total >> 8) as u8, total as u8,
+            (id >> 8) as u8, id as u8, (flags_offset >> 8) as u8, flags_offset as u8,
+            0x40, 0x11, 0x00, 0x00,
+            10, 0, 0, 1,
+            10, 0, 0, 2,
+        ];
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    fn accept(r: &mut FragmentReassembler, buf: &[u8], clock: &MockClock) -> Option<Vec<u8>> {
+        r.accept(&IPv4Packet::new(buf), clock).unwrap()
+    }
+
+    #[test]
+    fn test_reassemble_out_of_order() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::new();
+        let first = fragment(7, 0, true, &[1; 16]);
+        let second = fragment(7, 2, true, &[2; 16]);
+        let last = fragment(7, 4, false, &[3; 5]);
+
+        assert_eq!(accept(&mut r, &last, &clock), None);
+        assert_eq!(accept(&mut r, &first, &clock), None);
+        assert_eq!(r.pending(), 1);
+
+        let payload = accept(&mut r, &second, &clock).expect("datagram complete");
+        let mut expected = vec![1; 16];
+        expected.extend_from_slice(&[2; 16]);
+        expected.extend_from_slice(&[3; 5]);
+        assert_eq!(payload, expected);
+        assert_eq!(r.pending(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_returns_none() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::new();
+        assert_eq!(accept(&mut r, &fragment(1, 0, true, &[1; 8]), &clock), None);
+        assert_eq!(accept(&mut r, &fragment(1, 2, false, &[3; 8]), &clock), None);
+        assert_eq!(r.pending(), 1);
+    }
+
+    #[test]
+    fn test_overlap_prefers_first_seen() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::new();
+        assert_eq!(accept(&mut r, &fragment(2, 0, true, &[1; 16]), &clock), None);
+        // Overlaps the second half of the first fragment
+        let payload = accept(&mut r, &fragment(2, 1, false, &[9; 16]), &clock).unwrap();
+        let mut expected = vec![1; 16];
+        expected.extend_from_slice(&[9; 8]);
+        assert_eq!(payload, expected);
+    }
+
+    #[test]
+    fn test_unfragmented_passes_through() {
+        let mut r = FragmentReassembler::new();
+        let clock = MockClock::new();
+        assert_eq!(accept(&mut r, &fragment(3, 0, false, b"abc"), &clock), Some(b"abc".to_vec()));
+        assert_eq!(r.pending(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_evicted_after_timeout() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::with_timeout(Duration::from_secs(5));
+        accept(&mut r, &fragment(4, 0, true, &[1; 8]), &clock);
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(r.evict_expired(&clock), 0);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(r.evict_expired(&clock), 1);
+        assert_eq!(r.pending(), 0);
+        // The late fragment starts a new datagram rather than completing the old one.
+        assert_eq!(accept(&mut r, &fragment(4, 1, false, &[2; 8]), &clock), None);
+        assert_eq!(r.pending(), 1);
+        // The payload and its filled map
+        assert_eq!(r.buffered_bytes(), 32);
+    }
+
+    const LIMITS: ReassemblyLimits = ReassemblyLimits { max_buffered_bytes: 80, max_fragments: 3, max_datagrams: 8 };
+
+    #[test]
+    fn test_byte_limit_evicts_oldest() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::with_limits(DEFAULT_REASSEMBLY_TIMEOUT, LIMITS);
+        for id in 10..13 {
+            accept(&mut r, &fragment(id, 0, true, &[1; 16]), &clock);
+            clock.advance(Duration::from_secs(1));
+        }
+        // 96 bytes exceeds the cap: the first datagram goes.
+        assert_eq!(r.pending(), 2);
+        assert_eq!(r.buffered_bytes(), 64);
+        assert_eq!(r.dropped_over_limit(), 1);
+
+        // Its last fragment now starts over instead of completing it.
+        assert_eq!(accept(&mut r, &fragment(10, 2, false, &[2; 4]), &clock), None);
+        // The newer datagrams are intact.
+        let payload = accept(&mut r, &fragment(12, 2, false, &[2; 4]), &clock).unwrap();
+        assert_eq!(payload.len(), 20);
+    }
+
+    #[test]
+    fn test_fragment_limit_drops_stream() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::with_limits(DEFAULT_REASSEMBLY_TIMEOUT, LIMITS);
+        for _ in 0..3 {
+            accept(&mut r, &fragment(20, 0, true, &[1; 8]), &clock);
+        }
+        assert_eq!(r.pending(), 1);
+        assert_eq!(accept(&mut r, &fragment(20, 1, false, &[1; 8]), &clock), None);
+        assert_eq!(r.pending(), 0);
+        assert_eq!(r.buffered_bytes(), 0);
+        assert_eq!(r.dropped_over_limit(), 1);
+    }
+
+    #[test]
+    fn test_empty_non_final_fragment_rejected() {
+        let clock = MockClock::new();
+        let mut r = FragmentReassembler::new();
+        let packet = fragment(30, 1, true, &[]);
+        assert_eq!(
+            r.accept(&IPv4Packet::new(&packet), &clock),
+            Err(ValidationError::InvalidPacketLength.into())
+        );
+        assert_eq!(r.pending(), 0);
+    }
+
+    #[test]
+    fn test_datagram_limit_bounds_flood() {
+        let clock = MockClock::new();
+        // Bytes are no constraint here: only the datagram count is
+        let limits = ReassemblyLimits { max_datagrams: 8, ..ReassemblyLimits::default() };
+        let mut r = FragmentReassembler::with_limits(DEFAULT_REASSEMBLY_TIMEOUT, limits);
+        for id in 0..1000 {
+            // Tiny fragments and empty last fragments, each a new datagram
+            accept(&mut r, &fragment(id, 1, true, &[1]), &clock);
+            accept(&mut r, &fragment(id.wrapping_add(5000), 1, false, &[]), &clock);
+            assert!(r.pending() <= limits.max_datagrams);
+        }
+        assert_eq!(r.pending(), limits.max_datagrams);
+        assert_eq!(r.dropped_over_limit(), 2000 - limits.max_datagrams as u64);
+
+        // The newest datagrams survive the flood: adding to one drops nothing
+        let dropped = r.dropped_over_limit();
+        let first = fragment(999, 0, true, &[7; 8]);
+        assert_eq!(accept(&mut r, &first, &clock), None);
+        assert_eq!(r.dropped_over_limit(), dropped);
+    }
+}
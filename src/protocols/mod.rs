@@ -0,0 +1,9 @@
+// src/protocols/mod.rs
+pub mod arp;
+pub mod icmp;
+pub mod ipv4;
+pub mod routing;
+pub mod tcp;
+
+mod flow;
+pub use flow::{FlowKey, FlowMap};
@@ -0,0 +1,159 @@
+// src/protocols/routing.rs
+use crate::address::ipv4::{IPv4, Ipv4Net};
+
+/// Where a route sends matching packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NextHop {
+    /// The destination is on a directly connected network.
+    Direct,
+    /// Forward through the gateway at this address.
+    Gateway(IPv4),
+}
+
+/// A route to a network through an output interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Route {
+    pub destination: Ipv4Net,
+    pub next_hop: NextHop,
+    /// Index of the output NIC, as used by `NetworkIO`
+    pub interface: usize,
+}
+
+impl Route {
+    pub fn new(destination: Ipv4Net, next_hop: NextHop, interface: usize) -> Self {
+        Route { destination, next_hop, interface }
+    }
+
+    /// Return the address whose MAC address a packet to `destination` is
+    /// sent to: the gateway, or the destination itself if directly
+    /// connected.
+    pub fn next_hop_address(&self, destination: IPv4) -> IPv4 {
+        match self.next_hop {
+            NextHop::Direct => destination,
+            NextHop::Gateway(gateway) => gateway,
+        }
+    }
+}
+
+/// IPv4 routing table doing longest-prefix-match lookups.
+///
+/// A default route is added as `0.0.0.0/0`, which matches every address
+/// but loses to any more specific route.
+#[derive(Debug, Clone, Default)]
+pub struct RoutingTable {
+    // Ordered from the longest prefix to the shortest
+    routes: Vec<Route>,
+}
+
+impl RoutingTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a route, replacing any route to the same network.
+    pub fn add(&mut self, route: Route) {
+        self.remove(&route.destination);
+        let position = self
+            .routes
+            .iter()
+            .position(|r| r.destination.prefix() < route.destination.prefix())
+            .unwrap_or(self.routes.len());
+        self.routes.insert(position, route);
+    }
+
+    /// Remove and return the route to `destination`'s network, if any.
+    pub fn remove(&mut self, destination: &Ipv4Net) -> Option<Route> {
+        let position = self.routes.iter().position(|r| same_network(&r.destination, destination))?;
+        Some(self.routes.remove(position))
+    }
+
+    /// Return the most specific route to `destination`.
+    pub fn lookup(&self, destination: IPv4) -> Option<Route> {
+        // The first match is the longest prefix
+        self.routes.iter().find(|r| r.destination.contains(&destination)).copied()
+    }
+
+    /// Return an iterator over the routes, most specific first.
+    pub fn iter(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter()
+    }
+
+    /// Returns the number of routes.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Query if the table has no routes.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+// Whether two networks cover the same addresses, ignoring host bits
+fn same_network(a: &Ipv4Net, b: &Ipv4Net) -> bool {
+    a.prefix() == b.prefix() && a.network() == b.network()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GATEWAY: IPv4 = IPv4::new(192, 168, 0, 1);
+
+    fn net(s: &str) -> Ipv4Net {
+        s.parse().unwrap()
+    }
+
+    fn table() -> RoutingTable {
+        let mut table = RoutingTable::new();
+        table.add(Route::new(net("0.0.0.0/0"), NextHop::Gateway(GATEWAY), 0));
+        table.add(Route::new(net("10.1.2.0/24"), NextHop::Direct, 3));
+        table.add(Route::new(net("10.0.0.0/8"), NextHop::Direct, 1));
+        table.add(Route::new(net("10.1.0.0/16"), NextHop::Gateway(IPv4::new(10, 0, 0, 254)), 2));
+        table
+    }
+
+    #[test]
+    fn test_most_specific_route_wins() {
+        let table = table();
+        assert_eq!(table.lookup(IPv4::new(10, 1, 2, 3)).unwrap().interface, 3);
+        assert_eq!(table.lookup(IPv4::new(10, 1, 9, 9)).unwrap().interface, 2);
+        assert_eq!(table.lookup(IPv4::new(10, 9, 9, 9)).unwrap().interface, 1);
+    }
+
+    #[test]
+    fn test_default_route_is_fallback() {
+        let table = table();
+        let route = table.lookup(IPv4::new(8, 8, 8, 8)).unwrap();
+        assert_eq!(route.destination.prefix(), 0);
+        assert_eq!(route.next_hop_address(IPv4::new(8, 8, 8, 8)), GATEWAY);
+
+        let mut table = RoutingTable::new();
+        table.add(Route::new(net("10.0.0.0/8"), NextHop::Direct, 1));
+        assert_eq!(table.lookup(IPv4::new(8, 8, 8, 8)), None);
+    }
+
+    #[test]
+    fn test_next_hop_address() {
+        let table = table();
+        let destination = IPv4::new(10, 1, 2, 3);
+        assert_eq!(table.lookup(destination).unwrap().next_hop_address(destination), destination);
+        let destination = IPv4::new(10, 1, 9, 9);
+        assert_eq!(table.lookup(destination).unwrap().next_hop_address(destination), IPv4::new(10, 0, 0, 254));
+    }
+
+    #[test]
+    fn test_add_replaces_and_remove() {
+        let mut table = table();
+        table.add(Route::new(net("10.1.2.7/24"), NextHop::Direct, 5));
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.lookup(IPv4::new(10, 1, 2, 3)).unwrap().interface, 5);
+
+        assert!(table.remove(&net("10.1.2.0/24")).is_some());
+        assert_eq!(table.lookup(IPv4::new(10, 1, 2, 3)).unwrap().interface, 2);
+        assert!(table.remove(&net("10.1.2.0/24")).is_none());
+
+        let prefixes: Vec<u8> = table.iter().map(|r| r.destination.prefix()).collect();
+        assert_eq!(prefixes, vec![16, 8, 0]);
+    }
+}
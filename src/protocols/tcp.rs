@@ -0,0 +1,1247 @@
+// src/protocols/tcp.rs
+use std::time::{Duration, Instant};
+
+use crate::address::ipv4::IPv4;
+use crate::assemblers;
+use crate::parsers::tcp::{TcpOption, TcpSegment, FLAG_ACK, FLAG_RST};
+use crate::utils::time::Clock;
+
+/// Actions emitted by the TCP connection logic for the caller to carry out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TcpAction {
+    /// Send a keepalive probe: an empty ACK whose sequence number is one
+    /// less than SND.NXT, forcing the peer to answer with an ACK.
+    SendKeepalive { seq: u32, ack: u32 },
+    /// Send a zero-window probe: a segment carrying the single byte `data`
+    /// at sequence number `seq`, to learn when the peer's window reopens.
+    SendWindowProbe { seq: u32, ack: u32, data: u8 },
+    /// Send a SYN to open a connection.
+    SendSyn { seq: u32 },
+    /// Answer a SYN with a SYN-ACK.
+    SendSynAck { seq: u32, ack: u32 },
+    /// Send an empty ACK.
+    SendAck { seq: u32, ack: u32 },
+    /// Send a FIN-ACK to close our side of the connection.
+    SendFin { seq: u32, ack: u32 },
+    /// Send a RST, with the ACK flag set when `ack` is present.
+    SendReset { seq: u32, ack: Option<u32> },
+    /// Hand in-order data received from the peer to the application.
+    Deliver(Vec<u8>),
+    /// Tear the connection down.
+    Close,
+}
+
+/// Errors raised by the TCP connection logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TcpError {
+    /// A window scale shift count above the RFC 7323 limit of 14.
+    InvalidWindowScale(u8),
+}
+
+impl std::fmt::Display for TcpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TcpError::InvalidWindowScale(shift) => {
+                write!(f, "Window scale shift count {} exceeds {}", shift, MAX_WINDOW_SCALE)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TcpError {}
+
+/// Largest window scale shift count allowed by [RFC 7323].
+///
+/// [RFC 7323]: https://datatracker.ietf.org/doc/html/rfc7323#section-2.3
+pub const MAX_WINDOW_SCALE: u8 = 14;
+
+/// Window scale shift counts negotiated during the SYN exchange.
+///
+/// Scaling is only in effect when both SYNs carried the option; otherwise
+/// both shift counts are zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowScale {
+    /// Shift applied to windows advertised by the peer.
+    pub snd_shift: u8,
+    /// Shift applied to windows we advertise.
+    pub rcv_shift: u8,
+}
+
+impl WindowScale {
+    /// Record the outcome of the SYN exchange from the shift count we
+    /// offered and the one the peer offered, if any.
+    pub fn negotiate(local: Option<u8>, peer: Option<u8>) -> Result<Self, TcpError> {
+        for shift in [local, peer].into_iter().flatten() {
+            if shift > MAX_WINDOW_SCALE {
+                return Err(TcpError::InvalidWindowScale(shift));
+            }
+        }
+        match (local, peer) {
+            (Some(rcv_shift), Some(snd_shift)) => Ok(WindowScale { snd_shift, rcv_shift }),
+            _ => Ok(WindowScale::default()),
+        }
+    }
+
+    /// Return the effective send window for a window field received from
+    /// the peer.
+    pub fn send_window(&self, window: u16) -> u32 {
+        (window as u32) << self.snd_shift
+    }
+
+    /// Return the window field to advertise for a receive window of
+    /// `window` bytes.
+    pub fn advertised_window(&self, window: u32) -> u16 {
+        (window >> self.rcv_shift).min(u16::MAX as u32) as u16
+    }
+}
+
+/// Outgoing data that has been written but not yet acknowledged.
+#[derive(Debug, Clone, Default)]
+pub struct SendBuffer {
+    data: Vec<u8>,
+    // Bytes at the front of `data` that have been sent but not acknowledged
+    in_flight: usize,
+    scale: WindowScale,
+}
+
+impl SendBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the window scale negotiated during the SYN exchange.
+    pub fn set_window_scale(&mut self, scale: WindowScale) {
+        self.scale = scale;
+    }
+
+    /// Queue data for sending.
+    pub fn write(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    /// Return the bytes that may be sent now given the peer's unscaled
+    /// `window` field.
+    pub fn data_to_send(&self, window: u16) -> &[u8] {
+        let window = self.scale.send_window(window) as usize;
+        let allowed = window.saturating_sub(self.in_flight);
+        let unsent = &self.data[self.in_flight..];
+        &unsent[..unsent.len().min(allowed)]
+    }
+
+    /// Record that `len` bytes returned by `data_to_send` were sent.
+    pub fn mark_sent(&mut self, len: usize) {
+        self.in_flight = (self.in_flight + len).min(self.data.len());
+    }
+
+    /// Return the first byte not yet sent, if any.
+    pub fn next_unsent(&self) -> Option<u8> {
+        self.data.get(self.in_flight).copied()
+    }
+
+    /// Drop `len` acknowledged bytes from the front of the buffer.
+    pub fn acknowledge(&mut self, len: usize) {
+        let len = len.min(self.in_flight);
+        self.data.drain(..len);
+        self.in_flight -= len;
+    }
+}
+
+/// Default Maximum Segment Size when the peer sends no MSS option ([RFC 1122]).
+///
+/// [RFC 1122]: https://datatracker.ietf.org/doc/html/rfc1122#section-4.2.2.6
+pub const DEFAULT_MSS: u32 = 536;
+
+// Initial congestion window of ten segments (RFC 6928)
+const INITIAL_CWND: u32 = 10 * DEFAULT_MSS;
+
+/// Connection states from [RFC 793].
+///
+/// [RFC 793]: https://datatracker.ietf.org/doc/html/rfc793#section-3.2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TcpState {
+    Closed,
+    Listen,
+    SynSent,
+    SynReceived,
+    Established,
+    FinWait1,
+    FinWait2,
+    Closing,
+    TimeWait,
+    CloseWait,
+    LastAck,
+}
+
+// Sequence number comparisons modulo 2^32
+fn seq_lt(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) < 0
+}
+
+fn seq_le(a: u32, b: u32) -> bool {
+    a == b || seq_lt(a, b)
+}
+
+/// The state of a single TCP connection and the transitions between
+/// states, following the event processing of [RFC 793].
+///
+/// The machine does not send anything itself. Each call returns the
+/// `TcpAction`s the caller must carry out. Only in-order segments are
+/// accepted; anything else is answered with an ACK carrying RCV.NXT.
+///
+/// [RFC 793]: https://datatracker.ietf.org/doc/html/rfc793#section-3.9
+#[derive(Debug, Clone)]
+pub struct TcpStateMachine {
+    state: TcpState,
+    // Whether the connection came from LISTEN, so a reset returns there
+    passive: bool,
+    // Initial send sequence number
+    iss: u32,
+    // Oldest unacknowledged sequence number (SND.UNA)
+    snd_una: u32,
+    // Next sequence number to send (SND.NXT)
+    snd_nxt: u32,
+    // Window last advertised by the peer (SND.WND), scaled
+    snd_wnd: u32,
+    // Sequence and acknowledgment numbers of the segment that last set
+    // the window (SND.WL1, SND.WL2), so older segments cannot undo it
+    snd_wl1: u32,
+    snd_wl2: u32,
+    // Initial receive sequence number
+    irs: u32,
+    // Next sequence number expected from the peer (RCV.NXT)
+    rcv_nxt: u32,
+    // Shift count offered in our SYN or SYN-ACK, if any
+    local_wscale: Option<u8>,
+    // Window scale in effect once both SYNs carried the option
+    wscale: Option<WindowScale>,
+    // Congestion window in bytes. Only ECN reductions adjust it for now.
+    cwnd: u32,
+    // Set after reacting to ECE until CWR has gone out on a segment
+    cwr_pending: bool,
+}
+
+impl TcpStateMachine {
+    /// Create a connection in CLOSED that will use `iss` as its initial
+    /// send sequence number.
+    pub fn new(iss: u32) -> Self {
+        TcpStateMachine {
+            state: TcpState::Closed,
+            passive: false,
+            iss,
+            snd_una: iss,
+            snd_nxt: iss,
+            snd_wnd: 0,
+            snd_wl1: 0,
+            snd_wl2: 0,
+            irs: 0,
+            rcv_nxt: 0,
+            local_wscale: None,
+            wscale: None,
+            cwnd: INITIAL_CWND,
+            cwr_pending: false,
+        }
+    }
+
+    /// Offer a Window Scale option with `shift` during the handshake.
+    ///
+    /// The SYN sent for `SendSyn` must carry the option. The SYN-ACK sent
+    /// for `SendSynAck` carries it only when `window_scale` is `Some`,
+    /// since [RFC 7323] allows it there only if the peer's SYN had it.
+    ///
+    /// [RFC 7323]: https://datatracker.ietf.org/doc/html/rfc7323#section-2.2
+    pub fn with_window_scale(mut self, shift: u8) -> Result<Self, TcpError> {
+        if shift > MAX_WINDOW_SCALE {
+            return Err(TcpError::InvalidWindowScale(shift));
+        }
+        self.local_wscale = Some(shift);
+        Ok(self)
+    }
+
+    pub fn state(&self) -> TcpState {
+        self.state
+    }
+
+    /// Return the window scale negotiated in the handshake, or `None` if
+    /// either SYN left the option out.
+    pub fn window_scale(&self) -> Option<WindowScale> {
+        self.wscale
+    }
+
+    pub fn snd_una(&self) -> u32 {
+        self.snd_una
+    }
+
+    pub fn snd_nxt(&self) -> u32 {
+        self.snd_nxt
+    }
+
+    /// Return the peer's window in bytes, with its window scale applied.
+    pub fn snd_wnd(&self) -> u32 {
+        self.snd_wnd
+    }
+
+    pub fn irs(&self) -> u32 {
+        self.irs
+    }
+
+    pub fn rcv_nxt(&self) -> u32 {
+        self.rcv_nxt
+    }
+
+    /// Return the congestion window in bytes.
+    pub fn cwnd(&self) -> u32 {
+        self.cwnd
+    }
+
+    /// Return whether the next segment sent must carry CWR, clearing the
+    /// request so the flag goes out once.
+    pub fn take_cwr(&mut self) -> bool {
+        std::mem::take(&mut self.cwr_pending)
+    }
+
+    // React to an ECN-Echo as to a lost segment ([RFC 3168]): halve the
+    // congestion window, at most once until CWR has been sent.
+    //
+    // [RFC 3168]: https://datatracker.ietf.org/doc/html/rfc3168#section-6.1.2
+    fn on_ece(&mut self) {
+        if self.cwr_pending {
+            return;
+        }
+        self.cwnd = (self.cwnd / 2).max(2 * DEFAULT_MSS);
+        self.cwr_pending = true;
+    }
+
+    /// Passive open: wait for a SYN.
+    pub fn listen(&mut self) {
+        if self.state == TcpState::Closed {
+            self.state = TcpState::Listen;
+            self.passive = true;
+        }
+    }
+
+    /// Active open: send a SYN.
+    pub fn connect(&mut self) -> Vec<TcpAction> {
+        if self.state != TcpState::Closed {
+            return Vec::new();
+        }
+        self.state = TcpState::SynSent;
+        self.passive = false;
+        self.snd_una = self.iss;
+        self.snd_nxt = self.iss.wrapping_add(1);
+        vec![TcpAction::SendSyn { seq: self.iss }]
+    }
+
+    /// Close our side of the connection, sending a FIN where one is due.
+    pub fn close(&mut self) -> Vec<TcpAction> {
+        match self.state {
+            TcpState::Listen | TcpState::SynSent => {
+                self.state = TcpState::Closed;
+                vec![TcpAction::Close]
+            }
+            TcpState::SynReceived | TcpState::Established => {
+                self.state = TcpState::FinWait1;
+                vec![self.send_fin()]
+            }
+            TcpState::CloseWait => {
+                self.state = TcpState::LastAck;
+                vec![self.send_fin()]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn send_fin(&mut self) -> TcpAction {
+        let action = TcpAction::SendFin { seq: self.snd_nxt, ack: self.rcv_nxt };
+        self.snd_nxt = self.snd_nxt.wrapping_add(1);
+        action
+    }
+
+    fn ack_action(&self) -> TcpAction {
+        TcpAction::SendAck { seq: self.snd_nxt, ack: self.rcv_nxt }
+    }
+
+    // Whether every sequence number we sent, including our FIN, is acknowledged
+    fn fin_acked(&self) -> bool {
+        self.snd_una == self.snd_nxt
+    }
+
+    // Settle the window scale from the option on the peer's SYN. A shift
+    // above the limit is treated as the limit, as RFC 7323 asks.
+    fn negotiate_window_scale(&mut self, syn: &TcpSegment) {
+        let peer = syn.options_iter().find_map(|option| match option {
+            TcpOption::WindowScale(shift) => Some(shift.min(MAX_WINDOW_SCALE)),
+            _ => None,
+        });
+        self.wscale = match (self.local_wscale, peer) {
+            (Some(_), Some(_)) => WindowScale::negotiate(self.local_wscale, peer).ok(),
+            _ => None,
+        };
+    }
+
+    /// Process a segment received from the peer, returning the actions it
+    /// calls for.
+    pub fn process_segment(&mut self, seg: &TcpSegment) -> Vec<TcpAction> {
+        match self.state {
+            TcpState::Closed => Self::closed_reply(seg).into_iter().collect(),
+            TcpState::Listen => self.process_listen(seg),
+            TcpState::SynSent => self.process_syn_sent(seg),
+            _ => self.process_synchronized(seg),
+        }
+    }
+
+    // A reset for any segment other than a reset arriving in CLOSED
+    fn closed_reply(seg: &TcpSegment) -> Option<TcpAction> {
+        if seg.rst() {
+            None
+        } else if seg.ack() {
+            Some(TcpAction::SendReset { seq: seg.ack_number(), ack: None })
+        } else {
+            Some(TcpAction::SendReset {
+                seq: 0,
+                ack: Some(seg.seq_number().wrapping_add(seg.segment_len())),
+            })
+        }
+    }
+
+    fn process_listen(&mut self, seg: &TcpSegment) -> Vec<TcpAction> {
+        if seg.rst() {
+            return Vec::new();
+        }
+        if seg.ack() {
+            return vec![TcpAction::SendReset { seq: seg.ack_number(), ack: None }];
+        }
+        if !seg.syn() {
+            return Vec::new();
+        }
+        self.irs = seg.seq_number();
+        self.rcv_nxt = self.irs.wrapping_add(1);
+        self.negotiate_window_scale(seg);
+        // The window field of a SYN is never scaled
+        self.snd_wnd = seg.window() as u32;
+        self.snd_wl1 = seg.seq_number();
+        self.snd_wl2 = self.iss;
+        self.snd_una = self.iss;
+        self.snd_nxt = self.iss.wrapping_add(1);
+        self.state = TcpState::SynReceived;
+        vec![TcpAction::SendSynAck { seq: self.iss, ack: self.rcv_nxt }]
+    }
+
+    fn process_syn_sent(&mut self, seg: &TcpSegment) -> Vec<TcpAction> {
+        let ack = seg.ack_number();
+        let ack_ok = seg.ack() && seq_lt(self.iss, ack) && seq_le(ack, self.snd_nxt);
+        if seg.ack() && !ack_ok {
+            if seg.rst() {
+                return Vec::new();
+            }
+            return vec![TcpAction::SendReset { seq: ack, ack: None }];
+        }
+        if seg.rst() {
+            if ack_ok {
+                self.state = TcpState::Closed;
+                return vec![TcpAction::Close];
+            }
+            return Vec::new();
+        }
+        if !seg.syn() {
+            return Vec::new();
+        }
+
+        self.irs = seg.seq_number();
+        self.rcv_nxt = self.irs.wrapping_add(1);
+        self.negotiate_window_scale(seg);
+        // The window field of a SYN is never scaled
+        self.snd_wnd = seg.window() as u32;
+        self.snd_wl1 = seg.seq_number();
+        if ack_ok {
+            self.snd_wl2 = ack;
+            self.snd_una = ack;
+            self.state = TcpState::Established;
+            vec![self.ack_action()]
+        } else {
+            // Simultaneous open
+            self.state = TcpState::SynReceived;
+            vec![TcpAction::SendSynAck { seq: self.iss, ack: self.rcv_nxt }]
+        }
+    }
+
+    // Take the peer's window from `seg` unless an earlier segment was the
+    // last to set it, by the SND.WL1/SND.WL2 rule of RFC 793. This also
+    // accepts pure window updates that acknowledge nothing new.
+    fn update_window(&mut self, seg: &TcpSegment) {
+        let seq = seg.seq_number();
+        let ack = seg.ack_number();
+        if seq_lt(self.snd_wl1, seq) || (self.snd_wl1 == seq && seq_le(self.snd_wl2, ack)) {
+            self.snd_wnd = self.wscale.unwrap_or_default().send_window(seg.window());
+            self.snd_wl1 = seq;
+            self.snd_wl2 = ack;
+        }
+    }
+
+    // Processing for SYN-RECEIVED and every state after it
+    fn process_synchronized(&mut self, seg: &TcpSegment) -> Vec<TcpAction> {
+        if seg.seq_number() != self.rcv_nxt {
+            if seg.rst() {
+                return Vec::new();
+            }
+            return vec![self.ack_action()];
+        }
+
+        if seg.rst() {
+            if self.state == TcpState::SynReceived && self.passive {
+                self.state = TcpState::Listen;
+                return Vec::new();
+            }
+            self.state = TcpState::Closed;
+            return vec![TcpAction::Close];
+        }
+
+        if seg.syn() {
+            self.state = TcpState::Closed;
+            return vec![TcpAction::SendReset { seq: self.snd_nxt, ack: None }, TcpAction::Close];
+        }
+
+        if !seg.ack() {
+            return Vec::new();
+        }
+
+        let ack = seg.ack_number();
+        if seq_lt(self.snd_nxt, ack) {
+            if self.state == TcpState::SynReceived {
+                return vec![TcpAction::SendReset { seq: ack, ack: None }];
+            }
+            return vec![self.ack_action()];
+        }
+        // SND.UNA =< SEG.ACK =< SND.NXT; older duplicates are ignored
+        let ack_acceptable = seq_le(self.snd_una, ack);
+        if seq_lt(self.snd_una, ack) {
+            self.snd_una = ack;
+        } else if self.state == TcpState::SynReceived {
+            return vec![TcpAction::SendReset { seq: ack, ack: None }];
+        }
+        if ack_acceptable {
+            self.update_window(seg);
+        }
+
+        if seg.ece() && self.state != TcpState::SynReceived {
+            self.on_ece();
+        }
+
+        match self.state {
+            TcpState::SynReceived => self.state = TcpState::Established,
+            TcpState::FinWait1 if self.fin_acked() => self.state = TcpState::FinWait2,
+            TcpState::Closing if self.fin_acked() => self.state = TcpState::TimeWait,
+            TcpState::LastAck if self.fin_acked() => {
+                self.state = TcpState::Closed;
+                return vec![TcpAction::Close];
+            }
+            _ => {}
+        }
+
+        let mut actions = Vec::new();
+        let mut ack_needed = false;
+
+        let payload = seg.payload();
+        if !payload.is_empty()
+            && matches!(self.state, TcpState::Established | TcpState::FinWait1 | TcpState::FinWait2)
+        {
+            self.rcv_nxt = self.rcv_nxt.wrapping_add(payload.len() as u32);
+            actions.push(TcpAction::Deliver(payload.to_vec()));
+            ack_needed = true;
+        }
+
+        if seg.fin() {
+            self.rcv_nxt = self.rcv_nxt.wrapping_add(1);
+            ack_needed = true;
+            self.state = match self.state {
+                TcpState::Established => TcpState::CloseWait,
+                TcpState::FinWait1 if self.fin_acked() => TcpState::TimeWait,
+                TcpState::FinWait1 => TcpState::Closing,
+                TcpState::FinWait2 => TcpState::TimeWait,
+                other => other,
+            };
+        }
+
+        if ack_needed {
+            actions.push(self.ack_action());
+        }
+        actions
+    }
+}
+
+/// Build a RST answering `for_segment`, which arrived from `their_addr`
+/// to `our_addr`, per [RFC 793]. Returns `None` if `for_segment` is itself
+/// a RST, which must never be answered.
+///
+/// If the segment carried an ACK, the reset takes its sequence number from
+/// that acknowledgment. Otherwise it uses sequence number zero and
+/// acknowledges everything the segment occupied.
+///
+/// [RFC 793]: https://datatracker.ietf.org/doc/html/rfc793#section-3.4
+pub fn build_reset(for_segment: &TcpSegment, our_addr: IPv4, their_addr: IPv4) -> Option<Vec<u8>> {
+    if for_segment.rst() {
+        return None;
+    }
+    let mut buffer = vec![0u8; 20];
+    let mut reset = assemblers::tcp::TcpSegment::new(&mut buffer);
+    reset.set_source_port(for_segment.destination_port());
+    reset.set_destination_port(for_segment.source_port());
+    reset.set_header_length(20);
+    if for_segment.ack() {
+        reset.set_seq_number(for_segment.ack_number());
+        reset.set_flags(FLAG_RST);
+    } else {
+        reset.set_seq_number(0);
+        reset.set_ack_number(for_segment.seq_number().wrapping_add(for_segment.segment_len()));
+        reset.set_flags(FLAG_RST | FLAG_ACK);
+    }
+    reset.fill_checksum(our_addr, their_addr);
+    Some(buffer)
+}
+
+/// Keepalive timer settings.
+///
+/// Defaults follow [RFC 1122]: two hours of idle time before the first probe.
+///
+/// [RFC 1122]: https://datatracker.ietf.org/doc/html/rfc1122#section-4.2.3.6
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepaliveConfig {
+    /// Idle time before the first probe is sent.
+    pub idle: Duration,
+    /// Time between unanswered probes.
+    pub interval: Duration,
+    /// Number of unanswered probes before the connection is closed.
+    pub max_probes: u32,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        KeepaliveConfig {
+            idle: Duration::from_secs(2 * 60 * 60),
+            interval: Duration::from_secs(75),
+            max_probes: 9,
+        }
+    }
+}
+
+/// Keepalive timer for a single connection.
+///
+/// Time is read from the `Clock` passed to each call, so the timer can be
+/// driven by a real or simulated clock.
+#[derive(Debug, Clone)]
+pub struct Keepalive {
+    config: KeepaliveConfig,
+    last_activity: Instant,
+    last_probe: Option<Instant>,
+    unanswered: u32,
+}
+
+impl Keepalive {
+    pub fn new(config: KeepaliveConfig, clock: &dyn Clock) -> Self {
+        Keepalive {
+            config,
+            last_activity: clock.now(),
+            last_probe: None,
+            unanswered: 0,
+        }
+    }
+
+    /// Record a segment received from the peer, resetting the idle timer
+    /// and the unanswered probe count.
+    pub fn on_activity(&mut self, clock: &dyn Clock) {
+        self.last_activity = clock.now();
+        self.last_probe = None;
+        self.unanswered = 0;
+    }
+
+    /// Return the number of probes sent since the peer was last heard from.
+    pub fn unanswered_probes(&self) -> u32 {
+        self.unanswered
+    }
+
+    /// Return the instant at which the timer next fires.
+    pub fn deadline(&self) -> Instant {
+        match self.last_probe {
+            Some(probe) => probe + self.config.interval,
+            None => self.last_activity + self.config.idle,
+        }
+    }
+
+    /// Advance the timer, returning a probe to send or a request to close
+    /// once `max_probes` have gone unanswered.
+    pub fn poll(&mut self, clock: &dyn Clock, snd_nxt: u32, rcv_nxt: u32) -> Option<TcpAction> {
+        let now = clock.now();
+        if now < self.deadline() {
+            return None;
+        }
+
+        if self.unanswered >= self.config.max_probes {
+            return Some(TcpAction::Close);
+        }
+
+        self.unanswered += 1;
+        self.last_probe = Some(now);
+        Some(TcpAction::SendKeepalive {
+            seq: snd_nxt.wrapping_sub(1),
+            ack: rcv_nxt,
+        })
+    }
+}
+
+/// Persist timer settings.
+///
+/// The interval doubles after each unanswered probe, up to `max_interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PersistConfig {
+    /// Time from the window closing to the first probe.
+    pub initial_interval: Duration,
+    /// Upper bound on the time between probes.
+    pub max_interval: Duration,
+}
+
+impl Default for PersistConfig {
+    fn default() -> Self {
+        PersistConfig {
+            initial_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Persist timer for a single connection.
+///
+/// While the peer advertises a zero window and data is waiting to be sent,
+/// the timer periodically asks for a one-byte window probe so that a lost
+/// window update cannot deadlock the connection ([RFC 1122]).
+///
+/// [RFC 1122]: https://datatracker.ietf.org/doc/html/rfc1122#section-4.2.2.17
+#[derive(Debug, Clone)]
+pub struct PersistTimer {
+    config: PersistConfig,
+    deadline: Option<Instant>,
+    probes: u32,
+}
+
+impl PersistTimer {
+    pub fn new(config: PersistConfig) -> Self {
+        PersistTimer { config, deadline: None, probes: 0 }
+    }
+
+    /// Return the instant at which the next probe is due, if armed.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Return the number of probes sent since the window closed.
+    pub fn probes_sent(&self) -> u32 {
+        self.probes
+    }
+
+    // Interval before the next probe, doubling with each one sent
+    fn interval(&self) -> Duration {
+        let factor = 1u32.checked_shl(self.probes).unwrap_or(u32::MAX);
+        self.config.initial_interval.saturating_mul(factor).min(self.config.max_interval)
+    }
+
+    /// Advance the timer given the peer's latest `window` field and the
+    /// data waiting in `send`, returning a probe to send when one is due.
+    ///
+    /// The timer is armed when the window is zero with data pending and
+    /// disarmed as soon as the window opens or nothing is left to send.
+    pub fn poll(
+        &mut self,
+        clock: &dyn Clock,
+        window: u16,
+        send: &SendBuffer,
+        snd_nxt: u32,
+        rcv_nxt: u32,
+    ) -> Option<TcpAction> {
+        let data = match send.next_unsent() {
+            Some(data) if window == 0 => data,
+            _ => {
+                self.deadline = None;
+                self.probes = 0;
+                return None;
+            }
+        };
+
+        let now = clock.now();
+        match self.deadline {
+            None => {
+                self.deadline = Some(now + self.interval());
+                None
+            }
+            Some(deadline) if now < deadline => None,
+            Some(_) => {
+                self.probes = self.probes.saturating_add(1);
+                self.deadline = Some(now + self.interval());
+                Some(TcpAction::SendWindowProbe { seq: snd_nxt, ack: rcv_nxt, data })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tcp::{FLAG_FIN, FLAG_SYN};
+    use crate::utils::time::MockClock;
+
+    const CONFIG: KeepaliveConfig = KeepaliveConfig {
+        idle: Duration::from_secs(60),
+        interval: Duration::from_secs(10),
+        max_probes: 3,
+    };
+
+    #[test]
+    fn test_keepalive_not_sent_before_idle() {
+        let clock = MockClock::new();
+        let mut keepalive = Keepalive::new(CONFIG, &clock);
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(keepalive.poll(&clock, 1000, 500), None);
+    }
+
+    #[test]
+    fn test_keepalive_probes_then_closes() {
+        let clock = MockClock::new();
+        let mut keepalive = Keepalive::new(CONFIG, &clock);
+        clock.advance(CONFIG.idle);
+
+        for probe in 1..=CONFIG.max_probes {
+            assert_eq!(
+                keepalive.poll(&clock, 1000, 500),
+                Some(TcpAction::SendKeepalive { seq: 999, ack: 500 })
+            );
+            assert_eq!(keepalive.unanswered_probes(), probe);
+            // Nothing more until the probe interval elapses.
+            clock.advance(Duration::from_secs(1));
+            assert_eq!(keepalive.poll(&clock, 1000, 500), None);
+            clock.advance(CONFIG.interval - Duration::from_secs(1));
+        }
+
+        assert_eq!(keepalive.poll(&clock, 1000, 500), Some(TcpAction::Close));
+    }
+
+    #[test]
+    fn test_keepalive_reset_by_activity() {
+        let clock = MockClock::new();
+        let mut keepalive = Keepalive::new(CONFIG, &clock);
+        clock.advance(CONFIG.idle);
+
+        assert!(keepalive.poll(&clock, 1000, 500).is_some());
+        keepalive.on_activity(&clock);
+        assert_eq!(keepalive.unanswered_probes(), 0);
+        assert_eq!(keepalive.deadline(), clock.now() + CONFIG.idle);
+        clock.advance(CONFIG.interval);
+        assert_eq!(keepalive.poll(&clock, 1000, 500), None);
+    }
+
+    #[test]
+    fn test_keepalive_sequence_wraps() {
+        let clock = MockClock::new();
+        let mut keepalive = Keepalive::new(CONFIG, &clock);
+        clock.advance(CONFIG.idle);
+        assert_eq!(
+            keepalive.poll(&clock, 0, 0),
+            Some(TcpAction::SendKeepalive { seq: u32::MAX, ack: 0 })
+        );
+    }
+
+    // Segment 10.0.0.2:40000 -> 10.0.0.1:80 with the given flags and data
+    fn segment(flags: u8, seq: u32, ack: u32, data: &[u8]) -> Vec<u8> {
+        let mut buffer = vec![0u8; 20 + data.len()];
+        let mut seg = assemblers::tcp::TcpSegment::new(&mut buffer);
+        seg.set_source_port(40000);
+        seg.set_destination_port(80);
+        seg.set_seq_number(seq);
+        seg.set_ack_number(ack);
+        seg.set_header_length(20);
+        seg.set_flags(flags);
+        seg.mut_payload_ref().copy_from_slice(data);
+        buffer
+    }
+
+    // Like `segment`, advertising `window`
+    fn segment_with_window(flags: u8, seq: u32, ack: u32, window: u16) -> Vec<u8> {
+        let mut buffer = segment(flags, seq, ack, &[]);
+        assemblers::tcp::TcpSegment::new(&mut buffer).set_window(window);
+        buffer
+    }
+
+    const OURS: IPv4 = IPv4([10, 0, 0, 1]);
+    const THEIRS: IPv4 = IPv4([10, 0, 0, 2]);
+
+    #[test]
+    fn test_reset_for_segment_with_ack() {
+        let incoming = segment(FLAG_ACK, 5000, 7777, b"stray");
+        let reset = build_reset(&TcpSegment::new(&incoming), OURS, THEIRS).unwrap();
+        let reset = TcpSegment::new_with_validation(&reset).unwrap();
+        assert_eq!(reset.source_port(), 80);
+        assert_eq!(reset.destination_port(), 40000);
+        assert_eq!(reset.seq_number(), 7777);
+        assert!(reset.rst() && !reset.ack());
+    }
+
+    #[test]
+    fn test_reset_for_segment_without_ack() {
+        // A SYN to a closed port: SYN occupies one sequence number.
+        let incoming = segment(FLAG_SYN, 1000, 0, &[]);
+        let bytes = build_reset(&TcpSegment::new(&incoming), OURS, THEIRS).unwrap();
+        let reset = TcpSegment::new_with_validation(&bytes).unwrap();
+        assert_eq!(reset.seq_number(), 0);
+        assert_eq!(reset.ack_number(), 1001);
+        assert!(reset.rst() && reset.ack());
+
+        // Data without ACK: acknowledge seq + data length.
+        let incoming = segment(0, u32::MAX, 0, b"ab");
+        let bytes = build_reset(&TcpSegment::new(&incoming), OURS, THEIRS).unwrap();
+        assert_eq!(TcpSegment::new(&bytes).ack_number(), 1);
+    }
+
+    #[test]
+    fn test_no_reset_for_reset() {
+        for flags in [FLAG_RST, FLAG_RST | FLAG_ACK] {
+            let incoming = segment(flags, 5000, 7777, &[]);
+            assert_eq!(build_reset(&TcpSegment::new(&incoming), OURS, THEIRS), None);
+        }
+    }
+
+    #[test]
+    fn test_window_scale_negotiation() {
+        assert_eq!(
+            WindowScale::negotiate(Some(7), Some(3)),
+            Ok(WindowScale { snd_shift: 3, rcv_shift: 7 })
+        );
+        // Scaling requires both sides to offer the option.
+        assert_eq!(WindowScale::negotiate(Some(7), None), Ok(WindowScale::default()));
+        assert_eq!(WindowScale::negotiate(None, Some(7)), Ok(WindowScale::default()));
+        assert_eq!(WindowScale::negotiate(Some(7), Some(15)), Err(TcpError::InvalidWindowScale(15)));
+        assert_eq!(WindowScale::negotiate(Some(15), None), Err(TcpError::InvalidWindowScale(15)));
+    }
+
+    #[test]
+    fn test_scaled_send_window() {
+        let scale = WindowScale::negotiate(Some(7), Some(7)).unwrap();
+        let mut buffer = SendBuffer::new();
+        buffer.set_window_scale(scale);
+        buffer.write(&[0xaa; 100_000]);
+
+        let window: u16 = 0x0200;
+        assert_eq!(buffer.data_to_send(window).len(), (window as usize) << 7);
+
+        // Unscaled, the same field allows only 512 bytes.
+        buffer.set_window_scale(WindowScale::default());
+        assert_eq!(buffer.data_to_send(window).len(), 512);
+    }
+
+    #[test]
+    fn test_send_buffer_in_flight() {
+        let mut buffer = SendBuffer::new();
+        buffer.write(b"hello world");
+        assert_eq!(buffer.data_to_send(5), b"hello");
+        buffer.mark_sent(5);
+        // The window is already filled by unacknowledged data.
+        assert!(buffer.data_to_send(5).is_empty());
+        buffer.acknowledge(5);
+        assert_eq!(buffer.data_to_send(100), b" world");
+        assert_eq!(WindowScale { snd_shift: 0, rcv_shift: 2 }.advertised_window(4096), 1024);
+    }
+
+    // Like `segment_with_window`, also carrying a Window Scale option
+    fn segment_with_window_scale(flags: u8, seq: u32, ack: u32, window: u16, shift: u8) -> Vec<u8> {
+        let mut buffer = vec![0u8; 24];
+        let mut seg = assemblers::tcp::TcpSegment::new(&mut buffer);
+        seg.set_source_port(40000);
+        seg.set_destination_port(80);
+        seg.set_seq_number(seq);
+        seg.set_ack_number(ack);
+        seg.set_header_length(24);
+        seg.set_flags(flags);
+        seg.set_window(window);
+        buffer[20..24].copy_from_slice(&[0x01, 0x03, 0x03, shift]); // NOP, Window Scale
+        buffer
+    }
+
+    const ISS: u32 = 5000;
+
+    fn process(tcb: &mut TcpStateMachine, flags: u8, seq: u32, ack: u32, data: &[u8]) -> Vec<TcpAction> {
+        let bytes = segment(flags, seq, ack, data);
+        tcb.process_segment(&TcpSegment::new(&bytes))
+    }
+
+    // A connection brought up by a passive open, with the peer's ISS at 1000
+    fn established() -> TcpStateMachine {
+        let mut tcb = TcpStateMachine::new(ISS);
+        tcb.listen();
+        process(&mut tcb, FLAG_SYN, 1000, 0, &[]);
+        process(&mut tcb, FLAG_ACK, 1001, ISS + 1, &[]);
+        tcb
+    }
+
+    #[test]
+    fn test_passive_open_handshake() {
+        let mut tcb = TcpStateMachine::new(ISS);
+        tcb.listen();
+        assert_eq!(tcb.state(), TcpState::Listen);
+
+        let actions = process(&mut tcb, FLAG_SYN, 1000, 0, &[]);
+        assert_eq!(actions, vec![TcpAction::SendSynAck { seq: ISS, ack: 1001 }]);
+        assert_eq!(tcb.state(), TcpState::SynReceived);
+        assert_eq!((tcb.irs(), tcb.rcv_nxt(), tcb.snd_nxt()), (1000, 1001, ISS + 1));
+
+        let actions = process(&mut tcb, FLAG_ACK, 1001, ISS + 1, &[]);
+        assert!(actions.is_empty());
+        assert_eq!(tcb.state(), TcpState::Established);
+        assert_eq!(tcb.snd_una(), ISS + 1);
+    }
+
+    #[test]
+    fn test_listen_rejects_ack() {
+        let mut tcb = TcpStateMachine::new(ISS);
+        tcb.listen();
+        let actions = process(&mut tcb, FLAG_ACK, 1000, 42, &[]);
+        assert_eq!(actions, vec![TcpAction::SendReset { seq: 42, ack: None }]);
+        assert_eq!(tcb.state(), TcpState::Listen);
+    }
+
+    #[test]
+    fn test_active_open() {
+        let mut tcb = TcpStateMachine::new(ISS);
+        assert_eq!(tcb.connect(), vec![TcpAction::SendSyn { seq: ISS }]);
+        assert_eq!(tcb.state(), TcpState::SynSent);
+
+        let actions = process(&mut tcb, FLAG_SYN | FLAG_ACK, 9000, ISS + 1, &[]);
+        assert_eq!(actions, vec![TcpAction::SendAck { seq: ISS + 1, ack: 9001 }]);
+        assert_eq!(tcb.state(), TcpState::Established);
+    }
+
+    #[test]
+    fn test_deliver_data() {
+        let mut tcb = established();
+        let actions = process(&mut tcb, FLAG_ACK, 1001, ISS + 1, b"hello");
+        assert_eq!(
+            actions,
+            vec![TcpAction::Deliver(b"hello".to_vec()), TcpAction::SendAck { seq: ISS + 1, ack: 1006 }]
+        );
+
+        // A segment from the future is not accepted, only acknowledged.
+        let actions = process(&mut tcb, FLAG_ACK, 2000, ISS + 1, b"later");
+        assert_eq!(actions, vec![TcpAction::SendAck { seq: ISS + 1, ack: 1006 }]);
+        assert_eq!(tcb.rcv_nxt(), 1006);
+    }
+
+    #[test]
+    fn test_active_close() {
+        let mut tcb = established();
+        assert_eq!(tcb.close(), vec![TcpAction::SendFin { seq: ISS + 1, ack: 1001 }]);
+        assert_eq!(tcb.state(), TcpState::FinWait1);
+
+        assert!(process(&mut tcb, FLAG_ACK, 1001, ISS + 2, &[]).is_empty());
+        assert_eq!(tcb.state(), TcpState::FinWait2);
+
+        let actions = process(&mut tcb, FLAG_FIN | FLAG_ACK, 1001, ISS + 2, &[]);
+        assert_eq!(actions, vec![TcpAction::SendAck { seq: ISS + 2, ack: 1002 }]);
+        assert_eq!(tcb.state(), TcpState::TimeWait);
+    }
+
+    #[test]
+    fn test_simultaneous_close() {
+        let mut tcb = established();
+        tcb.close();
+        // The peer's FIN arrives before it has seen ours.
+        let actions = process(&mut tcb, FLAG_FIN | FLAG_ACK, 1001, ISS + 1, &[]);
+        assert_eq!(actions, vec![TcpAction::SendAck { seq: ISS + 2, ack: 1002 }]);
+        assert_eq!(tcb.state(), TcpState::Closing);
+
+        process(&mut tcb, FLAG_ACK, 1002, ISS + 2, &[]);
+        assert_eq!(tcb.state(), TcpState::TimeWait);
+    }
+
+    #[test]
+    fn test_passive_close() {
+        let mut tcb = established();
+        let actions = process(&mut tcb, FLAG_FIN | FLAG_ACK, 1001, ISS + 1, &[]);
+        assert_eq!(actions, vec![TcpAction::SendAck { seq: ISS + 1, ack: 1002 }]);
+        assert_eq!(tcb.state(), TcpState::CloseWait);
+
+        assert_eq!(tcb.close(), vec![TcpAction::SendFin { seq: ISS + 1, ack: 1002 }]);
+        assert_eq!(tcb.state(), TcpState::LastAck);
+
+        assert_eq!(process(&mut tcb, FLAG_ACK, 1002, ISS + 2, &[]), vec![TcpAction::Close]);
+        assert_eq!(tcb.state(), TcpState::Closed);
+    }
+
+    #[test]
+    fn test_reset_closes_connection() {
+        let mut tcb = established();
+        assert_eq!(process(&mut tcb, FLAG_RST, 1001, 0, &[]), vec![TcpAction::Close]);
+        assert_eq!(tcb.state(), TcpState::Closed);
+
+        // A reset outside the window is ignored.
+        let mut tcb = established();
+        assert!(process(&mut tcb, FLAG_RST, 7000, 0, &[]).is_empty());
+        assert_eq!(tcb.state(), TcpState::Established);
+    }
+
+    #[test]
+    fn test_closed_answers_with_reset() {
+        let mut tcb = TcpStateMachine::new(ISS);
+        assert_eq!(
+            process(&mut tcb, FLAG_SYN, 1000, 0, &[]),
+            vec![TcpAction::SendReset { seq: 0, ack: Some(1001) }]
+        );
+        assert!(process(&mut tcb, FLAG_RST, 1000, 0, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_ece_reduces_cwnd_and_requests_cwr() {
+        use crate::parsers::tcp::FLAG_ECE;
+
+        let mut tcb = established();
+        let initial = tcb.cwnd();
+        assert!(!tcb.take_cwr());
+
+        process(&mut tcb, FLAG_ACK | FLAG_ECE, 1001, ISS + 1, &[]);
+        assert_eq!(tcb.cwnd(), initial / 2);
+        // A second ECE before CWR is sent does not reduce again.
+        process(&mut tcb, FLAG_ACK | FLAG_ECE, 1001, ISS + 1, &[]);
+        assert_eq!(tcb.cwnd(), initial / 2);
+
+        assert!(tcb.take_cwr());
+        assert!(!tcb.take_cwr());
+
+        // Reductions stop at two segments.
+        for _ in 0..8 {
+            process(&mut tcb, FLAG_ACK | FLAG_ECE, 1001, ISS + 1, &[]);
+            tcb.take_cwr();
+        }
+        assert_eq!(tcb.cwnd(), 2 * DEFAULT_MSS);
+    }
+
+    #[test]
+    fn test_sequence_comparison_wraps() {
+        assert!(seq_lt(u32::MAX, 0));
+        assert!(!seq_lt(0, u32::MAX));
+        assert!(seq_le(5, 5));
+    }
+
+    const PERSIST: PersistConfig = PersistConfig {
+        initial_interval: Duration::from_secs(1),
+        max_interval: Duration::from_secs(3),
+    };
+
+    #[test]
+    fn test_zero_window_probe_with_backoff() {
+        let clock = MockClock::new();
+        let mut timer = PersistTimer::new(PERSIST);
+        let mut send = SendBuffer::new();
+        send.write(b"xyz");
+
+        // The window closes: the timer arms but nothing is due yet.
+        assert_eq!(timer.poll(&clock, 0, &send, 100, 50), None);
+        assert_eq!(timer.deadline(), Some(clock.now() + Duration::from_secs(1)));
+
+        clock.advance(Duration::from_secs(1));
+        let probe = Some(TcpAction::SendWindowProbe { seq: 100, ack: 50, data: b'x' });
+        assert_eq!(timer.poll(&clock, 0, &send, 100, 50), probe);
+
+        // The second probe waits twice as long.
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(timer.poll(&clock, 0, &send, 100, 50), None);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(timer.poll(&clock, 0, &send, 100, 50), probe);
+        assert_eq!(timer.probes_sent(), 2);
+
+        // Backoff is capped at the maximum interval.
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(timer.poll(&clock, 0, &send, 100, 50), probe);
+        assert_eq!(timer.deadline(), Some(clock.now() + Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_zero_window_probe_stops_when_window_opens() {
+        let clock = MockClock::new();
+        let mut timer = PersistTimer::new(PERSIST);
+        let mut send = SendBuffer::new();
+        send.write(b"xyz");
+
+        timer.poll(&clock, 0, &send, 100, 50);
+        clock.advance(Duration::from_secs(1));
+        assert!(timer.poll(&clock, 0, &send, 100, 50).is_some());
+
+        assert_eq!(timer.poll(&clock, 512, &send, 100, 50), None);
+        assert_eq!(timer.deadline(), None);
+        assert_eq!(timer.probes_sent(), 0);
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(timer.poll(&clock, 512, &send, 100, 50), None);
+    }
+
+    #[test]
+    fn test_zero_window_without_pending_data() {
+        let clock = MockClock::new();
+        let mut timer = PersistTimer::new(PERSIST);
+        assert_eq!(timer.poll(&clock, 0, &SendBuffer::new(), 100, 50), None);
+        assert_eq!(timer.deadline(), None);
+    }
+
+    #[test]
+    fn test_window_update_without_new_ack() {
+        let mut tcb = established();
+        assert_eq!(tcb.snd_wnd(), 0);
+
+        // A duplicate ACK reopening the window is still taken
+        let update = segment_with_window(FLAG_ACK, 1001, ISS + 1, 4096);
+        assert!(tcb.process_segment(&TcpSegment::new(&update)).is_empty());
+        assert_eq!(tcb.snd_una(), ISS + 1);
+        assert_eq!(tcb.snd_wnd(), 4096);
+
+        // An older segment, acknowledging less, cannot shrink it back
+        let stale = segment_with_window(FLAG_ACK, 1001, ISS, 0);
+        tcb.process_segment(&TcpSegment::new(&stale));
+        assert_eq!(tcb.snd_wnd(), 4096);
+    }
+
+    #[test]
+    fn test_window_scale_applied_after_handshake() {
+        let mut tcb = TcpStateMachine::new(ISS).with_window_scale(7).unwrap();
+        tcb.listen();
+        let syn = segment_with_window_scale(FLAG_SYN, 1000, 0, 0xffff, 7);
+        tcb.process_segment(&TcpSegment::new(&syn));
+        assert_eq!(tcb.window_scale(), Some(WindowScale { snd_shift: 7, rcv_shift: 7 }));
+        // The SYN's own window is taken as is.
+        assert_eq!(tcb.snd_wnd(), 0xffff);
+
+        let ack = segment_with_window(FLAG_ACK, 1001, ISS + 1, 0x0200);
+        tcb.process_segment(&TcpSegment::new(&ack));
+        assert_eq!(tcb.state(), TcpState::Established);
+        assert_eq!(tcb.snd_wnd(), 0x0200 << 7);
+    }
+
+    #[test]
+    fn test_window_scale_active_open() {
+        let mut tcb = TcpStateMachine::new(ISS).with_window_scale(2).unwrap();
+        tcb.connect();
+        let syn_ack = segment_with_window_scale(FLAG_SYN | FLAG_ACK, 9000, ISS + 1, 1024, 20);
+        tcb.process_segment(&TcpSegment::new(&syn_ack));
+        // An oversized shift is clamped to the limit.
+        assert_eq!(tcb.window_scale(), Some(WindowScale { snd_shift: MAX_WINDOW_SCALE, rcv_shift: 2 }));
+
+        let ack = segment_with_window(FLAG_ACK, 9001, ISS + 1, 3);
+        tcb.process_segment(&TcpSegment::new(&ack));
+        assert_eq!(tcb.snd_wnd(), 3 << MAX_WINDOW_SCALE);
+    }
+
+    #[test]
+    fn test_window_scale_needs_both_sides() {
+        // The peer offers scaling but we did not.
+        let mut tcb = TcpStateMachine::new(ISS);
+        tcb.listen();
+        let syn = segment_with_window_scale(FLAG_SYN, 1000, 0, 4096, 7);
+        tcb.process_segment(&TcpSegment::new(&syn));
+        assert_eq!(tcb.window_scale(), None);
+
+        let ack = segment_with_window(FLAG_ACK, 1001, ISS + 1, 4096);
+        tcb.process_segment(&TcpSegment::new(&ack));
+        assert_eq!(tcb.snd_wnd(), 4096);
+        assert_eq!(TcpStateMachine::new(ISS).with_window_scale(15).err(), Some(TcpError::InvalidWindowScale(15)));
+    }
+}
@@ -0,0 +1,68 @@
+// src/utils/checksum.rs
+//! The internet checksum used by IPv4, TCP, UDP and ICMP.
+//!
+//! [RFC 1071]: https://datatracker.ietf.org/doc/html/rfc1071
+
+/// Fold the carries of a one's complement sum back into the low 16 bits.
+pub fn fold(mut sum: u32) -> u16 {
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    sum as u16
+}
+
+/// Add the 16-bit big-endian words of `data` to the partial sum `initial`
+/// and fold the result. An odd final byte is padded with a zero.
+pub fn sum(data: &[u8], initial: u32) -> u16 {
+    let mut sum = initial;
+    for chunk in data.chunks(2) {
+        sum += u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)]) as u32;
+        if sum > 0xFFFF {
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+    }
+    fold(sum)
+}
+
+/// Return the internet checksum of `data`, starting from the partial sum
+/// `initial` (zero, or a pseudo-header sum).
+///
+/// Run over data that already carries its checksum, the result is zero
+/// when the checksum is correct.
+pub fn checksum(data: &[u8], initial: u32) -> u16 {
+    !sum(data, initial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 1071 section 3
+    const EXAMPLE: [u8; 8] = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+
+    #[test]
+    fn test_rfc1071_example() {
+        assert_eq!(sum(&EXAMPLE, 0), 0xddf2);
+        assert_eq!(checksum(&EXAMPLE, 0), 0x220d);
+    }
+
+    #[test]
+    fn test_odd_length_is_padded() {
+        assert_eq!(sum(&EXAMPLE[..7], 0), sum(&[0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0x00], 0));
+        assert_eq!(checksum(&[0xab], 0), !0xab00);
+    }
+
+    #[test]
+    fn test_initial_sum_is_folded_in() {
+        assert_eq!(sum(&EXAMPLE[2..], 0x0001), 0xddf2);
+        assert_eq!(fold(0x2_ddf0), 0xddf2);
+    }
+
+    #[test]
+    fn test_verifies_to_zero() {
+        let mut data = [0x45, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x00, 0x40, 0x11, 0x00, 0x00];
+        let value = checksum(&data, 0);
+        data[10..12].copy_from_slice(&value.to_be_bytes());
+        assert_eq!(checksum(&data, 0), 0);
+    }
+}
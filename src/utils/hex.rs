@@ -0,0 +1,67 @@
+// src/utils/hex.rs
+//! Hex dumps of raw frames for debugging.
+use alloc::string::String;
+use core::fmt::Write;
+
+// Bytes shown on each row of a dump
+const ROW_LEN: usize = 16;
+
+/// Format `bytes` as a classic hex dump: the offset, sixteen bytes in hex
+/// split into two groups of eight, and their printable ASCII characters.
+///
+/// ```
+/// use thunda::utils::hex_dump;
+///
+/// assert_eq!(
+///     hex_dump(b"Hi!\x00"),
+///     "00000000  48 69 21 00                                       |Hi!.|\n"
+/// );
+/// ```
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(ROW_LEN).enumerate() {
+        // Writing to a String cannot fail
+        let _ = write!(out, "{:08x} ", row * ROW_LEN);
+        for i in 0..ROW_LEN {
+            if i % 8 == 0 {
+                out.push(' ');
+            }
+            match chunk.get(i) {
+                Some(byte) => {
+                    let _ = write!(out, "{:02x} ", byte);
+                }
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }
+        }));
+        out.push_str("|\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_dump() {
+        let bytes: [u8; 20] = [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0xde, 0xad,
+            0xbe, 0xef, 0x00, 0x01, 0x08, 0x00, b'T', b'h',
+            b'u', b'n', b'd', b'a',
+        ];
+        assert_eq!(
+            hex_dump(&bytes),
+            "00000000  02 00 00 00 00 01 de ad  be ef 00 01 08 00 54 68  |..............Th|\n\
+             00000010  75 6e 64 61                                       |unda|\n"
+        );
+    }
+
+    #[test]
+    fn test_hex_dump_empty() {
+        assert_eq!(hex_dump(&[]), "");
+    }
+}
@@ -0,0 +1,35 @@
+// src/utils/logger.rs
+//! Minimal logger writing records to standard error.
+//!
+//! Installed by `Config::apply` so that `log_level` takes effect without the
+//! application bringing its own logger. If one is already installed it is
+//! left in place.
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct StderrLogger;
+
+static LOGGER: StderrLogger = StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{:<5} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the stderr logger if no logger is installed yet, and set the
+/// maximum level to `level`.
+///
+/// Returns true if this call installed the logger.
+pub fn init(level: LevelFilter) -> bool {
+    let installed = log::set_logger(&LOGGER).is_ok();
+    log::set_max_level(level);
+    installed
+}
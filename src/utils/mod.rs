@@ -0,0 +1,11 @@
+// src/utils/mod.rs
+pub mod checksum;
+#[cfg(feature = "alloc")]
+mod hex;
+#[cfg(all(feature = "std", feature = "log"))]
+pub mod logger;
+#[cfg(feature = "std")]
+pub mod time;
+
+#[cfg(feature = "alloc")]
+pub use hex::hex_dump;
@@ -0,0 +1,21 @@
+// tests/address.rs
+use thunda::address::{IPv4, IPv6, IpAddr, Ipv4Net, Ipv6Net, Mac};
+
+#[test]
+fn address_types_are_reexported() {
+    let v4: IPv4 = "192.168.1.1".parse().unwrap();
+    assert_eq!(v4, thunda::address::ipv4::IPv4::new(192, 168, 1, 1));
+
+    let v6: IPv6 = "fe80::1".parse().unwrap();
+    assert_eq!(v6, thunda::address::ipv6::IPv6::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+
+    let mac: Mac = "02:00:00:00:00:01".parse().unwrap();
+    assert_eq!(mac, thunda::address::mac::Mac::new(0x02, 0, 0, 0, 0, 0x01));
+
+    let net: Ipv4Net = "192.168.1.0/24".parse().unwrap();
+    assert!(net.contains(&v4));
+    let net: Ipv6Net = "fe80::/10".parse().unwrap();
+    assert!(net.contains(&v6));
+
+    assert_eq!(IpAddr::from(v4), "192.168.1.1".parse().unwrap());
+}
@@ -0,0 +1,227 @@
+// tests/parsers_fuzz.rs
+//
+// Feeds random and adversarial buffers to the parsers built with the
+// non-validating `new` and calls every accessor. None of them may panic,
+// whatever the input.
+#![cfg(feature = "alloc")]
+
+use thunda::parsers::arp::ArpPacket;
+use thunda::parsers::ethernet::EthernetFrame;
+use thunda::parsers::icmp6::Icmpv6Packet;
+use thunda::parsers::ipv4::IPv4Packet;
+use thunda::parsers::ipv6::{IPv6Packet, OptionsHeader, RoutingHeader};
+
+// Small xorshift generator so runs are reproducible without extra deps
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+fn exercise_ethernet(buffer: &[u8]) {
+    let frame = EthernetFrame::new(buffer);
+    let _ = frame.destination();
+    let _ = frame.source();
+    let _ = frame.destination_mac();
+    let _ = frame.source_mac();
+    let _ = frame.ethertype();
+    let _ = frame.ethertype_parsed();
+    let _ = frame.classify();
+    let _ = frame.inner_class();
+    let _ = frame.is_vlan_tagged();
+    let _ = frame.vlan_id();
+    let _ = frame.vlan_priority();
+    let _ = frame.vlan_stack();
+    let _ = frame.payload();
+}
+
+fn exercise_ipv4(buffer: &[u8]) {
+    let packet = IPv4Packet::new(buffer);
+    let _ = packet.is_header_valid();
+    let _ = packet.version();
+    let _ = packet.ihl();
+    let _ = packet.dscp();
+    let _ = packet.ecn();
+    let _ = packet.total_length();
+    let _ = packet.is_truncated();
+    let _ = packet.identification();
+    let _ = packet.dont_frag();
+    let _ = packet.more_frags();
+    let _ = packet.fragment_offset();
+    let _ = packet.ttl();
+    let _ = packet.protocol();
+    let _ = packet.checksum();
+    let _ = packet.source();
+    let _ = packet.destination();
+    let _ = packet.options();
+    let _ = packet.options_iter().count();
+    let _ = packet.pseudo_header_checksum();
+    let _ = packet.summary();
+    let _ = packet.payload();
+    let _ = packet.key();
+    let _ = packet.verify_checksum();
+    let _ = IPv4Packet::recompute_and_store(&mut buffer.to_vec());
+}
+
+fn exercise_ipv6(buffer: &[u8]) {
+    let packet = IPv6Packet::new(buffer);
+    let _ = packet.check_length();
+    let _ = packet.header_length();
+    let _ = packet.version();
+    let _ = packet.traffic_class();
+    let _ = packet.flow_label();
+    let _ = packet.payload_length();
+    let _ = packet.total_length();
+    let _ = packet.next_header();
+    let _ = packet.hop_limit();
+    let _ = packet.source();
+    let _ = packet.destination();
+    let _ = packet.summary();
+    let _ = packet.payload();
+    let _ = packet.pseudo_header_checksum();
+    let _ = packet.upper_layer_protocol();
+}
+
+fn exercise_options(buffer: &[u8]) {
+    let header = OptionsHeader::new(buffer);
+    let _ = header.next_header();
+    let _ = header.header_length();
+    for option in header.options_iter() {
+        let _ = option.action();
+    }
+    let _ = OptionsHeader::new_with_validation(buffer);
+}
+
+fn exercise_routing(buffer: &[u8]) {
+    let header = RoutingHeader::new(buffer);
+    let _ = header.next_header();
+    let _ = header.header_length();
+    let _ = header.routing_type();
+    let _ = header.segments_left();
+    let _ = header.segments().count();
+    let _ = header.active_segment();
+    let _ = RoutingHeader::new_with_validation(buffer);
+}
+
+fn exercise_arp(buffer: &[u8]) {
+    let packet = ArpPacket::new(buffer);
+    let _ = packet.hardware_type();
+    let _ = packet.hardware();
+    let _ = packet.protocol_type();
+    let _ = packet.hardware_address_length();
+    let _ = packet.protocol_address_length();
+    let _ = packet.operation();
+    let _ = packet.operation_type();
+    let _ = packet.sender_hardware_address();
+    let _ = packet.sender_protocol_address();
+    let _ = packet.target_hardware_address();
+    let _ = packet.target_protocol_address();
+    let _ = packet.sender_mac();
+    let _ = packet.sender_ip();
+    let _ = packet.target_mac();
+    let _ = packet.target_ip();
+    let _ = packet.is_gratuitous();
+    let _ = packet.is_probe();
+    let _ = packet.is_announcement();
+}
+
+fn exercise_icmpv6(buffer: &[u8]) {
+    let packet = Icmpv6Packet::new(buffer);
+    let _ = packet.msg_type();
+    let _ = packet.code();
+    let _ = packet.checksum();
+    let _ = packet.body();
+    let _ = packet.verify_checksum(0);
+}
+
+fn exercise_all(buffer: &[u8]) {
+    exercise_ethernet(buffer);
+    exercise_ipv4(buffer);
+    exercise_ipv6(buffer);
+    exercise_options(buffer);
+    exercise_routing(buffer);
+    exercise_arp(buffer);
+    exercise_icmpv6(buffer);
+}
+
+// Well-formed headers whose every prefix is also tried
+fn seeds() -> Vec<Vec<u8>> {
+    let mut ipv4 = vec![0x46, 0x00, 0x00, 0x1c, 0, 0, 0x40, 0, 0x40, 0x06, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2];
+    ipv4.extend_from_slice(&[0x94, 0x04, 0x00, 0x00, 0xde, 0xad, 0xbe, 0xef]);
+
+    let mut ipv6 = vec![0x60, 0, 0, 0, 0, 16, 0, 64];
+    ipv6.extend_from_slice(&[0xfe; 32]);
+    ipv6.extend_from_slice(&[6, 0, 0x05, 0x02, 0, 0, 0x01, 0x00]); // Hop-by-Hop
+    ipv6.extend_from_slice(&[0xaa; 8]);
+
+    let arp = vec![
+        0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, // Ethernet/IPv4 request
+        0x02, 0, 0, 0, 0, 0x01, 10, 0, 0, 1,
+        0, 0, 0, 0, 0, 0, 10, 0, 0, 2,
+    ];
+
+    // Doubly tagged Ethernet frame carrying the IPv4 packet
+    let mut ethernet = vec![0xff; 12];
+    ethernet.extend_from_slice(&[0x88, 0xa8, 0x00, 0x0a, 0x81, 0x00, 0x20, 0x14, 0x08, 0x00]);
+    ethernet.extend_from_slice(&ipv4);
+
+    // SRv6 header with two segments
+    let mut routing = vec![6, 4, 4, 1, 1, 0, 0, 0];
+    routing.extend_from_slice(&[0x20; 32]);
+
+    vec![ipv4, ipv6, arp, ethernet, routing]
+}
+
+#[test]
+fn truncated_headers_do_not_panic() {
+    for seed in seeds() {
+        for len in 0..=seed.len() {
+            exercise_all(&seed[..len]);
+        }
+    }
+}
+
+#[test]
+fn adversarial_buffers_do_not_panic() {
+    for len in 0..80 {
+        for fill in [0x00, 0xff, 0x45, 0x4f, 0x60, 0x81] {
+            exercise_all(&vec![fill; len]);
+        }
+        // Nothing but VLAN tags
+        let tags: Vec<u8> = [0x81, 0x00].iter().copied().cycle().take(len).collect();
+        exercise_all(&tags);
+    }
+
+    // Lengths claiming more than the buffer holds
+    exercise_ipv4(&[0x4f, 0x00, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    exercise_ipv4(&[0x45, 0x00, 0x00, 0x04, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let mut ipv6 = vec![0x60, 0, 0, 0, 0xff, 0xff, 0, 64];
+    ipv6.extend_from_slice(&[0; 32]);
+    exercise_ipv6(&ipv6);
+    exercise_arp(&[0x00, 0x01, 0x08, 0x00, 0x10, 0x10, 0x00, 0x02]);
+}
+
+#[test]
+fn random_buffers_do_not_panic() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..5000 {
+        let len = (rng.next() % 96) as usize;
+        let mut buffer = rng.bytes(len);
+        exercise_all(&buffer);
+
+        // Give the buffer a plausible version nibble so deeper paths run
+        if let Some(first) = buffer.first_mut() {
+            *first = if rng.next() & 1 == 0 { 0x40 | (*first & 0x0f) } else { 0x60 | (*first & 0x0f) };
+        }
+        exercise_all(&buffer);
+    }
+}
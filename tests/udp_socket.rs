@@ -0,0 +1,54 @@
+// tests/udp_socket.rs
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+
+use actix::Actor;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration};
+
+use thunda::address::IPv4;
+use thunda::io::loopback::LoopbackNic;
+use thunda::io::network_io::NetworkIO;
+use thunda::io::udp_socket::{RecvFrom, SendTo, UdpDispatcher, UdpSocket};
+use thunda::Config;
+
+#[actix_rt::test]
+async fn datagram_loops_back_to_bound_socket() {
+    let config = Config::new();
+    let local = IPv4::new(10, 0, 0, 1);
+
+    let dispatcher = UdpDispatcher::new().start();
+    let network = NetworkIO::with_config(Arc::new(Mutex::new(LoopbackNic::new())), config.clone())
+        .with_receiver(dispatcher.clone().recipient())
+        .start();
+    let socket = UdpSocket::new(local, 5000, network.clone().recipient(), &config)
+        .bind(&dispatcher)
+        .await
+        .unwrap();
+    // Bound elsewhere, so it must not see the datagram
+    let other = UdpSocket::new(local, 5001, network.recipient(), &config)
+        .bind(&dispatcher)
+        .await
+        .unwrap();
+
+    socket
+        .send(SendTo { data: b"hello".to_vec(), destination: local, port: 5000 })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut received = None;
+    for _ in 0..20 {
+        sleep(Duration::from_millis(50)).await;
+        received = socket.send(RecvFrom).await.unwrap();
+        if received.is_some() {
+            break;
+        }
+    }
+    let datagram = received.expect("datagram was not delivered");
+    assert_eq!(datagram.source, local);
+    assert_eq!(datagram.source_port, 5000);
+    assert_eq!(datagram.data, b"hello");
+    assert_eq!(other.send(RecvFrom).await.unwrap(), None);
+}